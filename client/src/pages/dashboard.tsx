@@ -332,6 +332,9 @@ export default function Dashboard() {
 
         addLog("success", `Saved: ${selectedDevice.name} (${selectedDevice.vendorId}:${selectedDevice.productId}, Interface ${selectedDevice.interfaceNumber}) → ${config.appPath}`, "Config");
         toast({ title: "Saved", description: `${selectedDevice.name} configured successfully` });
+      } else {
+        addLog("error", result.error ?? "Failed to save configuration", "Config");
+        toast({ title: "Not saved", description: result.error ?? "Failed to save configuration", variant: "destructive" });
       }
     } catch (error) {
       addLog("error", "Failed to save configuration", "Config");
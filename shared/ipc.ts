@@ -5,11 +5,33 @@
 
 import type {
   HidDevice,
+  HidInterfaceInfo,
+  InterfaceActivity,
+  CapturedReport,
+  ConfigPaths,
+  HidReportKind,
   DeviceBinding,
+  DeviceBindingGroup,
   AppSettings,
   LogEntry,
   IpcResult,
   MonitoringState,
+  CompactLogsResult,
+  LogOrder,
+  InstanceStatus,
+  ConfigLockStatus,
+  PluginRegistration,
+  Profile,
+  ProfileRule,
+  ProfileSummary,
+  HotkeyPreview,
+  HotkeyConflict,
+  BindingWarning,
+  BindingTestResult,
+  ActionConfig,
+  TriggerType,
+  UsageStats,
+  DeviceExplanation,
 } from "./types";
 
 // ============================================
@@ -19,8 +41,17 @@ import type {
 export const IPC_COMMANDS = {
   // Device Management
   LIST_DEVICES: "list_devices",
+  LIST_DEVICES_CACHED: "list_devices_cached",
   REFRESH_DEVICES: "refresh_devices",
   GET_DEVICE_INFO: "get_device_info",
+  EXPORT_DEVICE_LIST: "export_device_list",
+  FIND_STALE_BINDINGS: "find_stale_bindings",
+  RESYNC_HID_CONFIGURED: "resync_hid_configured",
+  RESCAN_CONFIGURED_DEVICES: "rescan_configured_devices",
+  DETECT_ACTIVE_INTERFACE: "detect_active_interface",
+  CAPTURE_HID_REPORT: "capture_hid_report",
+  SEND_HID_REPORT: "send_hid_report",
+  LIST_RAW_INPUT_DEVICES: "list_raw_input_devices",
   
   // Monitoring
   START_MONITORING: "start_monitoring",
@@ -29,26 +60,69 @@ export const IPC_COMMANDS = {
   
   // Configuration
   GET_ALL_BINDINGS: "get_all_bindings",
+  GET_BINDINGS_GROUPED: "get_bindings_grouped",
   GET_BINDING: "get_binding",
   SAVE_BINDING: "save_binding",
+  CREATE_BINDING_TEMPLATE: "create_binding_template",
+  SET_BINDING_TRIGGER: "set_binding_trigger",
+  SET_BINDING_ENABLED: "set_binding_enabled",
+  REWRITE_ACTION_PATHS: "rewrite_action_paths",
   DELETE_BINDING: "delete_binding",
+  EXPORT_BINDINGS_MARKDOWN: "export_bindings_markdown",
   
   // Settings
   GET_SETTINGS: "get_settings",
   SAVE_SETTINGS: "save_settings",
+  SET_DEVICE_VERBOSE: "set_device_verbose",
+  GET_CONFIG_DIR: "get_config_dir",
+  OPEN_CONFIG_DIR: "open_config_dir",
+  MOVE_CONFIG_DIR: "move_config_dir",
+  EXPORT_CONFIG: "export_config",
+  IMPORT_CONFIG: "import_config",
+
+  // Kiosk lock
+  GET_LOCK_STATUS: "get_lock_status",
+  LOCK: "lock",
+  UNLOCK: "unlock",
+  REMOVE_LOCK: "remove_lock",
   
   // Actions
   TEST_ACTION: "test_action",
+  TEST_BINDING: "test_binding",
   EXECUTE_ACTION: "execute_action",
+  PREVIEW_HOTKEY: "preview_hotkey",
+  CHECK_HOTKEY_CONFLICTS: "check_hotkey_conflicts",
+  VALIDATE_ACTION: "validate_action",
+  VALIDATE_ALL_BINDINGS: "validate_all_bindings",
+  EXPLAIN_DEVICE: "explain_device",
+
+  // Plugins
+  GET_PLUGINS: "get_plugins",
+  REGISTER_PLUGIN: "register_plugin",
+  UNREGISTER_PLUGIN: "unregister_plugin",
+
+  // Profiles
+  LIST_PROFILES: "list_profiles",
+  CREATE_PROFILE: "create_profile",
+  SWITCH_PROFILE: "switch_profile",
+  DELETE_PROFILE: "delete_profile",
+  GET_PROFILE_RULES: "get_profile_rules",
+  SAVE_PROFILE_RULES: "save_profile_rules",
   
   // Logs
   GET_LOGS: "get_logs",
   CLEAR_LOGS: "clear_logs",
+  COMPACT_LOGS: "compact_logs",
   EXPORT_LOGS: "export_logs",
+
+  // Usage stats (local only)
+  GET_USAGE_STATS: "get_usage_stats",
+  RESET_USAGE_STATS: "reset_usage_stats",
   
   // System
   OPEN_FILE_DIALOG: "open_file_dialog",
   GET_APP_VERSION: "get_app_version",
+  GET_INSTANCE_STATUS: "get_instance_status",
   MINIMIZE_TO_TRAY: "minimize_to_tray",
   QUIT_APP: "quit_app",
 } as const;
@@ -63,8 +137,12 @@ export const IPC_EVENTS = {
   BUTTON_PRESSED: "button-pressed",
   BUTTON_RELEASED: "button-released",
   MONITORING_DETECTED: "monitoring-detected",
+  DEVICE_ACTIVITY: "device-activity",
+  BINDING_ARMED: "binding-armed",
   LOG_ENTRY: "log-entry",
   CONFIG_CHANGED: "config-changed",
+  PROFILE_SWITCHED: "profile-switched",
+  CONFIG_RELOADED: "config-reloaded",
 } as const;
 
 // ============================================
@@ -75,8 +153,17 @@ export const IPC_EVENTS = {
 export interface TauriCommands {
   // Device Management
   [IPC_COMMANDS.LIST_DEVICES]: () => Promise<IpcResult<HidDevice[]>>;
+  [IPC_COMMANDS.LIST_DEVICES_CACHED]: () => Promise<IpcResult<HidDevice[]>>;
   [IPC_COMMANDS.REFRESH_DEVICES]: () => Promise<IpcResult<HidDevice[]>>;
   [IPC_COMMANDS.GET_DEVICE_INFO]: (deviceId: string) => Promise<IpcResult<HidDevice>>;
+  [IPC_COMMANDS.EXPORT_DEVICE_LIST]: () => Promise<IpcResult<HidInterfaceInfo[]>>;
+  [IPC_COMMANDS.FIND_STALE_BINDINGS]: () => Promise<IpcResult<DeviceBinding[]>>;
+  [IPC_COMMANDS.RESYNC_HID_CONFIGURED]: () => Promise<IpcResult<void>>;
+  [IPC_COMMANDS.RESCAN_CONFIGURED_DEVICES]: () => Promise<IpcResult<HidDevice[]>>; // Call after a device-connected event
+  [IPC_COMMANDS.DETECT_ACTIVE_INTERFACE]: (deviceId: string, windowMs?: number) => Promise<IpcResult<InterfaceActivity[]>>;
+  [IPC_COMMANDS.CAPTURE_HID_REPORT]: (deviceId: string, durationMs?: number) => Promise<IpcResult<CapturedReport[]>>;
+  [IPC_COMMANDS.SEND_HID_REPORT]: (deviceId: string, reportHex: string, reportId?: number, kind?: HidReportKind) => Promise<IpcResult<number>>; // Bytes written, report ID included
+  [IPC_COMMANDS.LIST_RAW_INPUT_DEVICES]: () => Promise<IpcResult<HidDevice[]>>; // Windows only; name is the device path
   
   // Monitoring
   [IPC_COMMANDS.START_MONITORING]: () => Promise<IpcResult<void>>;
@@ -85,26 +172,69 @@ export interface TauriCommands {
   
   // Configuration
   [IPC_COMMANDS.GET_ALL_BINDINGS]: () => Promise<IpcResult<DeviceBinding[]>>;
+  [IPC_COMMANDS.GET_BINDINGS_GROUPED]: () => Promise<IpcResult<DeviceBindingGroup[]>>;
   [IPC_COMMANDS.GET_BINDING]: (deviceId: string) => Promise<IpcResult<DeviceBinding | null>>;
   [IPC_COMMANDS.SAVE_BINDING]: (binding: DeviceBinding) => Promise<IpcResult<DeviceBinding>>;
+  [IPC_COMMANDS.CREATE_BINDING_TEMPLATE]: (device: HidDevice) => Promise<IpcResult<DeviceBinding>>;
+  [IPC_COMMANDS.SET_BINDING_TRIGGER]: (bindingId: string, triggerType: TriggerType) => Promise<IpcResult<DeviceBinding>>;
+  [IPC_COMMANDS.SET_BINDING_ENABLED]: (bindingId: string, enabled: boolean) => Promise<IpcResult<DeviceBinding>>;
+  [IPC_COMMANDS.REWRITE_ACTION_PATHS]: (fromPrefix: string, toPrefix: string, dryRun?: boolean) => Promise<IpcResult<number>>;
   [IPC_COMMANDS.DELETE_BINDING]: (bindingId: string) => Promise<IpcResult<void>>;
+  [IPC_COMMANDS.EXPORT_BINDINGS_MARKDOWN]: (path?: string) => Promise<IpcResult<string>>;
   
   // Settings
   [IPC_COMMANDS.GET_SETTINGS]: () => Promise<IpcResult<AppSettings>>;
   [IPC_COMMANDS.SAVE_SETTINGS]: (settings: AppSettings) => Promise<IpcResult<AppSettings>>;
+  [IPC_COMMANDS.SET_DEVICE_VERBOSE]: (deviceId: string, verbose: boolean) => Promise<IpcResult<void>>;
+  [IPC_COMMANDS.GET_CONFIG_DIR]: () => Promise<IpcResult<ConfigPaths>>;
+  [IPC_COMMANDS.OPEN_CONFIG_DIR]: () => Promise<IpcResult<void>>; // Opens Explorer/Finder/file manager
+  [IPC_COMMANDS.MOVE_CONFIG_DIR]: (newPath: string, removeOld?: boolean) => Promise<IpcResult<string>>;
+  [IPC_COMMANDS.EXPORT_CONFIG]: (path: string) => Promise<IpcResult<number>>;
+  [IPC_COMMANDS.IMPORT_CONFIG]: (path: string, replace: boolean) => Promise<IpcResult<number>>;
+
+  // Kiosk lock
+  [IPC_COMMANDS.GET_LOCK_STATUS]: () => Promise<IpcResult<ConfigLockStatus>>;
+  [IPC_COMMANDS.LOCK]: (passphrase?: string) => Promise<IpcResult<ConfigLockStatus>>;
+  [IPC_COMMANDS.UNLOCK]: (passphrase: string) => Promise<IpcResult<ConfigLockStatus>>;
+  [IPC_COMMANDS.REMOVE_LOCK]: () => Promise<IpcResult<ConfigLockStatus>>;
   
   // Actions
   [IPC_COMMANDS.TEST_ACTION]: (binding: DeviceBinding) => Promise<IpcResult<void>>;
+  [IPC_COMMANDS.TEST_BINDING]: (bindingId: string) => Promise<IpcResult<BindingTestResult>>;
   [IPC_COMMANDS.EXECUTE_ACTION]: (bindingId: string) => Promise<IpcResult<void>>;
+  [IPC_COMMANDS.PREVIEW_HOTKEY]: (hotkey: string) => Promise<IpcResult<HotkeyPreview>>;
+  [IPC_COMMANDS.CHECK_HOTKEY_CONFLICTS]: () => Promise<IpcResult<HotkeyConflict[]>>;
+  [IPC_COMMANDS.VALIDATE_ACTION]: (action: ActionConfig) => Promise<IpcResult<string[]>>;
+  [IPC_COMMANDS.VALIDATE_ALL_BINDINGS]: () => Promise<IpcResult<BindingWarning[]>>;
+  [IPC_COMMANDS.EXPLAIN_DEVICE]: (deviceId: string) => Promise<IpcResult<DeviceExplanation>>;
+
+  // Plugins
+  [IPC_COMMANDS.GET_PLUGINS]: () => Promise<IpcResult<PluginRegistration[]>>;
+  [IPC_COMMANDS.REGISTER_PLUGIN]: (plugin: PluginRegistration) => Promise<IpcResult<PluginRegistration>>;
+  [IPC_COMMANDS.UNREGISTER_PLUGIN]: (name: string) => Promise<IpcResult<void>>;
+
+  // Profiles
+  [IPC_COMMANDS.LIST_PROFILES]: () => Promise<IpcResult<ProfileSummary[]>>;
+  [IPC_COMMANDS.CREATE_PROFILE]: (name: string) => Promise<IpcResult<Profile>>;
+  [IPC_COMMANDS.SWITCH_PROFILE]: (profileId: string) => Promise<IpcResult<void>>;
+  [IPC_COMMANDS.DELETE_PROFILE]: (profileId: string) => Promise<IpcResult<void>>;
+  [IPC_COMMANDS.GET_PROFILE_RULES]: () => Promise<IpcResult<ProfileRule[]>>;
+  [IPC_COMMANDS.SAVE_PROFILE_RULES]: (rules: ProfileRule[]) => Promise<IpcResult<ProfileRule[]>>;
   
   // Logs
-  [IPC_COMMANDS.GET_LOGS]: (limit?: number) => Promise<IpcResult<LogEntry[]>>;
+  [IPC_COMMANDS.GET_LOGS]: (limit?: number, order?: LogOrder) => Promise<IpcResult<LogEntry[]>>;
   [IPC_COMMANDS.CLEAR_LOGS]: () => Promise<IpcResult<void>>;
+  [IPC_COMMANDS.COMPACT_LOGS]: () => Promise<IpcResult<CompactLogsResult>>;
   [IPC_COMMANDS.EXPORT_LOGS]: (filePath: string) => Promise<IpcResult<string>>;
+
+  // Usage stats
+  [IPC_COMMANDS.GET_USAGE_STATS]: () => Promise<IpcResult<UsageStats>>;
+  [IPC_COMMANDS.RESET_USAGE_STATS]: () => Promise<IpcResult<void>>;
   
   // System
   [IPC_COMMANDS.OPEN_FILE_DIALOG]: (filters?: string[]) => Promise<IpcResult<string | null>>;
   [IPC_COMMANDS.GET_APP_VERSION]: () => Promise<IpcResult<string>>;
+  [IPC_COMMANDS.GET_INSTANCE_STATUS]: () => Promise<IpcResult<InstanceStatus>>;
   [IPC_COMMANDS.MINIMIZE_TO_TRAY]: () => Promise<IpcResult<void>>;
   [IPC_COMMANDS.QUIT_APP]: () => Promise<IpcResult<void>>;
 }
@@ -18,11 +18,51 @@ export interface HidDevice {
   path?: string; // OS-specific device path for opening
   serialNumber?: string;
   manufacturer?: string;
+  consumerUsage?: number; // Consumer-page usage (media key) of this input
+  isRepeat?: boolean; // OS auto-repeat of a held key, not a new press
+  buttonCode?: number; // Virtual-key code of the key pressed
 }
 
+// Every field hidapi reports for one interface (support export, not deduplicated)
+export interface HidInterfaceInfo {
+  path: string;
+  vendorId: string;
+  productId: string;
+  serialNumber?: string;
+  releaseNumber: string;
+  manufacturer?: string;
+  product?: string;
+  usagePage: number;
+  usage: number;
+  interfaceNumber: number;
+  busType: string;
+}
+
+export interface InterfaceActivity {
+  interfaceNumber: number;
+  usagePage: number;
+  usage: number;
+  path: string;
+  reportCount: number;
+  error?: string; // Interface couldn't be opened or read
+  recommended: boolean; // Produced the most reports in the window
+}
+
+// One input report read by capture_hid_report
+export interface CapturedReport {
+  interfaceNumber: number;
+  usagePage: number;
+  elapsedMs: number; // Since the capture started
+  length: number;
+  hex: string; // Space-separated bytes, e.g. "02 CD 00"
+}
+
+// Which kind of report send_hid_report writes
+export type HidReportKind = "output" | "feature";
+
 // --- Configuration Types ---
 
-export type TriggerType = "single-press" | "double-press" | "long-press";
+export type TriggerType = "single-press" | "double-press" | "triple-press" | "long-press";
 
 export interface DeviceBinding {
   id: string;
@@ -32,16 +72,103 @@ export interface DeviceBinding {
   triggerType: TriggerType;
   action: ActionConfig;
   enabled: boolean;
+  requiredLayer?: string; // Only fire while this layer is active; takes over from the button's unlayered binding
+  powerCondition?: PowerCondition; // Gate/branch on AC vs battery
+  notifyOnExecute?: boolean; // Show a desktop notification when the action runs
+  confirmDialog?: boolean; // Ask yes/no before running; no answer cancels
+  armToFire?: ArmToFire; // First press arms, second press within the window fires
+  manufacturer?: string; // Only match devices reporting this manufacturer
+  consumerUsage?: string; // Only match this media key, e.g. "play-pause"
+  buttonCode?: number; // Only match this key (virtual-key code); unset matches any
+  cooldownMs?: number; // Min time between executions; unset = 150ms default, 0 = off
   createdAt: string; // ISO date string
   updatedAt: string;
 }
 
+// One device's bindings joined with whether it's connected
+export interface DeviceBindingGroup {
+  deviceId: string; // As saved in the bindings
+  device?: HidDevice; // The connected device, if present
+  status: DeviceStatus; // "configured" when present, otherwise "disconnected"
+  bindings: DeviceBinding[];
+}
+
+export interface ArmToFire {
+  windowSecs: number; // Default 5
+  beep: boolean; // Audible cue when armed
+}
+
+export type PowerSource = "ac" | "battery" | "unknown";
+
+export interface PowerCondition {
+  runOn: PowerSource;
+  otherwise?: ActionConfig; // Runs when not on runOn power; omit to skip
+}
+
 export interface ActionConfig {
-  type: "launch-app" | "run-script" | "system-command" | "hotkey";
+  type:
+    | "launch-app"
+    | "run-script"
+    | "system-command"
+    | "hotkey"
+    | "set-volume"  // executablePath holds the percentage
+    | "plugin"      // executablePath holds a registered plugin name
+    | "clipboard-template" // executablePath holds a template using {clipboard}
+    | "text-macro"  // executablePath holds text typed into the focused window
+    | "script-block" // executablePath holds a multi-line script; arguments "cmd" | "powershell"
+    | "mouse-move"  // executablePath holds "x,y" pixels; arguments "normalized" for 0-65535
+    | "mouse-action"  // executablePath holds "click:middle", "scroll:up:3" or "move:+10,-5"
+    | "set-layer"   // executablePath holds the layer name
+    | "clear-layer"
+    | "toggle-layer"
+    | "sequence"    // runs sequence.steps in order
+    | "delay"       // executablePath holds milliseconds to wait (max 60000)
+    | "shell-capture"; // like system-command, but waits and logs the exit code and output (30s timeout)
   executablePath: string;
   arguments: string;
   workingDirectory?: string;
-  runAsAdmin?: boolean;
+  runAsAdmin?: boolean; // Launch/script/command: start elevated through a UAC prompt (Windows only)
+  targetWindow?: string; // Hotkey only: window title/process to post keys to
+  holdMs?: number; // Hotkey only: keep keys down this long before releasing (max 5000)
+  windowState?: "normal" | "minimized" | "maximized" | "hidden"; // LaunchApp only
+  sequence?: ActionSequence; // Sequence only
+  env?: [string, string][]; // Launched processes: extra environment variables as [name, value]
+  timeoutMs?: number; // Launch/script/command/shell-capture: kill after this many ms (shell-capture default 30000)
+}
+
+// Steps of a "sequence" action (steps can't be sequences themselves)
+export interface ActionSequence {
+  steps: ActionConfig[];
+  delayMs: number; // Pause between steps
+  continueOnError: boolean; // Run remaining steps after a failure
+}
+
+// External executable invoked for "plugin" actions (JSON payload on stdin)
+export interface PluginRegistration {
+  name: string;
+  executablePath: string;
+}
+
+// --- Profile Types ---
+
+// A named set of bindings; only the active profile's bindings are matched
+export interface Profile {
+  id: string;
+  name: string;
+  bindings: DeviceBinding[];
+}
+
+// Activates a profile while a process is in the foreground (Windows)
+export interface ProfileRule {
+  processName: string; // Executable name, e.g. "Photoshop.exe"
+  profileId: string;
+}
+
+export interface ProfileSummary {
+  id: string;
+  name: string;
+  bindingCount: number;
+  active: boolean; // Its bindings are the ones the listener matches
 }
 
 // --- Preset Types ---
@@ -69,12 +196,103 @@ export interface AppSettings {
   theme: "light" | "dark" | "system";
   logLevel: "debug" | "info" | "warn" | "error";
   maxLogEntries: number;
+  reenableOnReconnect?: boolean; // Re-enable a device's bindings when it reconnects
+  maxLogMessageLength?: number; // Longer log messages are truncated by compact_logs
+  persistLayers?: boolean; // Keep active layers across restarts
+  secondInstanceBehavior?: "focus-existing" | "warn";
+  listenerStartDelayMs?: number; // Ignore presses for this long after launch
+  usageStatsEnabled?: boolean; // Count executed action/trigger types locally
+  verboseDevices?: string[]; // Devices whose raw reports are logged on each press
+  useSerialInDeviceId?: boolean; // Key devices as VID:PID:SERIAL so identical devices bind separately
+  remappingEnabled?: boolean; // Off pauses every binding without deleting any (default true)
+  maxLogFileBytes?: number; // activity.log rotates to activity.log.1 past this size (default 1 MiB)
+  multiPressWindowMs?: number; // Max gap between presses of a double/triple press (default 400)
+  enableHttpApi?: boolean; // Serve the local control API on 127.0.0.1
+  httpApiPort?: number; // Port for the HTTP API (default 17380)
+  httpApiToken?: string; // Bearer token for the HTTP API; generated on enable if empty
+}
+
+// Where config and logs live on disk
+export interface ConfigPaths {
+  configDir: string;
+  configFile: string; // config.json
+  logsFile: string; // logs.json, the log shown in the app
+  activityLog: string; // activity.log, plain-text copy for tailing
+}
+
+export interface ConfigLockStatus {
+  locked: boolean; // A lock is configured
+  unlocked: boolean; // Unlocked for this session
+  hasPassphrase: boolean;
+}
+
+export interface InstanceStatus {
+  isPrimary: boolean;
+  otherInstanceRunning: boolean;
 }
 
 // --- Log Types ---
 
 export type LogLevel = "info" | "success" | "error" | "warn" | "debug";
 
+export interface ResolvedKey {
+  name: string;
+  vkCode: number;
+}
+
+export interface HotkeyPreview {
+  keys: ResolvedKey[];
+  warnings: string[];
+}
+
+export interface HotkeyConflict {
+  bindingId: string;
+  deviceId: string;
+  hotkey: string;
+  warning: string;
+}
+
+export interface BindingWarning {
+  bindingId: string;
+  deviceId: string;
+  message: string;
+}
+
+// Result of explain_device: candidate bindings in the order the listener tries them
+export interface ConditionCheck {
+  condition: string; // e.g. "enabled", "manufacturer", "trigger", "layer", "power"
+  state: "pass" | "fail" | "depends-on-input";
+  detail: string;
+}
+
+export interface BindingExplanation {
+  bindingId: string;
+  conditions: ConditionCheck[];
+  canFire: boolean; // No condition fails outright
+}
+
+export interface DeviceExplanation {
+  deviceId: string;
+  manufacturer?: string;
+  bindings: BindingExplanation[];
+}
+
+// Result of test_binding: one entry per action run (per step for sequences)
+export interface BindingTestStep {
+  step: number; // 1-based
+  description: string;
+  succeeded: boolean;
+  message?: string; // Why it failed or was skipped
+}
+
+export interface BindingTestResult {
+  bindingId: string;
+  succeeded: boolean;
+  steps: BindingTestStep[];
+}
+
+export type LogOrder = "newest-first" | "oldest-first";
+
 export interface LogEntry {
   id: string;
   timestamp: string; // ISO date string
@@ -83,12 +301,52 @@ export interface LogEntry {
   source?: string; // e.g., "HID", "Config", "System"
 }
 
+// Local counts keyed by action type / trigger type, e.g. { "launch-app": 3 }
+export interface UsageStats {
+  since?: string; // First count after the last reset
+  actionTypes: Record<string, number>;
+  triggerTypes: Record<string, number>;
+}
+
+export interface CompactLogsResult {
+  entriesRemoved: number;
+  messagesTruncated: number;
+  bytesBefore: number;
+  bytesAfter: number;
+  bytesReclaimed: number;
+}
+
 // --- IPC Response Types ---
 
+// Stable error identifiers, one per backend HidError/ConfigError variant
+export type ErrorCode =
+  // HID
+  | "hid-init-failed"
+  | "device-not-found"
+  | "device-open-failed"
+  | "device-read-failed"
+  | "device-write-failed"
+  | "permission-denied"
+  // Config
+  | "no-config-dir"
+  | "io"
+  | "invalid-json"
+  | "invalid-binding"
+  | "binding-not-found"
+  | "binding-conflict"
+  | "config-locked"
+  | "incorrect-passphrase"
+  | "invalid-import"
+  | "migration-failed"
+  | "invalid-settings"
+  | "profile-not-found"
+  | "invalid-profile";
+
 export interface IpcResult<T> {
   success: boolean;
   data?: T;
-  error?: string;
+  error?: string; // Message to show
+  code?: ErrorCode; // Set when the error maps to a backend error type; react to this rather than the message
 }
 
 // --- Event Types (Tauri Events) ---
@@ -102,6 +360,12 @@ export interface DeviceEvent {
   rawData?: number[]; // Raw HID report data
 }
 
+// Throttled "device sent input" ping from the background listener
+export interface DeviceActivityEvent {
+  deviceId: string;
+  timestamp: string;
+}
+
 export interface MonitoringState {
   isActive: boolean;
   detectedDevice?: HidDevice;
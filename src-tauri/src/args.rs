@@ -0,0 +1,154 @@
+// ============================================
+// Action Arguments
+// Splits an action's `arguments` string the same way for every code path
+// that spawns a process (background listener and test_action)
+// ============================================
+
+#[derive(Clone, Copy, PartialEq)]
+enum Quote {
+    None,
+    Double,
+    Single,
+}
+
+/// Parse arguments string respecting quoted sections.
+///
+/// - `"..."` groups; inside it `\"` is a literal quote. Other backslashes are
+///   kept as-is so Windows paths survive.
+/// - `'...'` groups with no escapes at all, but only when the `'` starts an
+///   argument, so apostrophes inside words (`don't`) stay literal.
+/// - An unterminated quote never errors: the rest of the string becomes part
+///   of the current argument, spaces included.
+///
+/// Examples:
+///   `arg1 arg2` -> ["arg1", "arg2"]
+///   `"path with spaces" arg2` -> ["path with spaces", "arg2"]
+///   `arg1 "quoted arg"` -> ["arg1", "quoted arg"]
+///   `say \"hi\"` -> ["say", "\"hi\""]
+///   `'single quoted' it's` -> ["single quoted", "it's"]
+pub fn parse_arguments(args: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut quote = Quote::None;
+    // Set by quotes too, so `""` yields an empty argument
+    let mut in_argument = false;
+    let mut chars = args.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Quote::Single, '\'') => quote = Quote::None,
+            (Quote::Single, _) => current.push(c),
+            (_, '\\') if chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+                in_argument = true;
+            }
+            (Quote::Double, '"') => quote = Quote::None,
+            (Quote::None, '"') => {
+                quote = Quote::Double;
+                in_argument = true;
+            }
+            (Quote::None, '\'') if !in_argument => {
+                quote = Quote::Single;
+                in_argument = true;
+            }
+            (Quote::None, ' ' | '\t') => {
+                if in_argument {
+                    result.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            _ => {
+                current.push(c);
+                in_argument = true;
+            }
+        }
+    }
+
+    if in_argument {
+        result.push(current);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_args() {
+        let result = parse_arguments("arg1 arg2 arg3");
+        assert_eq!(result, vec!["arg1", "arg2", "arg3"]);
+    }
+
+    #[test]
+    fn test_parse_quoted_path() {
+        let result = parse_arguments("\"C:\\Program Files\\App\\app.exe\" --flag");
+        assert_eq!(result, vec!["C:\\Program Files\\App\\app.exe", "--flag"]);
+    }
+
+    #[test]
+    fn test_parse_mixed_args() {
+        let result = parse_arguments("normal \"quoted arg\" another");
+        assert_eq!(result, vec!["normal", "quoted arg", "another"]);
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        let result = parse_arguments("");
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_parse_multiple_spaces() {
+        let result = parse_arguments("arg1    arg2");
+        assert_eq!(result, vec!["arg1", "arg2"]);
+    }
+
+    #[test]
+    fn test_parse_embedded_quotes() {
+        // Quotes can open and close mid-argument
+        let result = parse_arguments("--name=\"John Smith\" --x");
+        assert_eq!(result, vec!["--name=John Smith", "--x"]);
+        assert_eq!(parse_arguments("a \"\" b"), vec!["a", "", "b"]);
+    }
+
+    #[test]
+    fn test_parse_escaped_quotes() {
+        let result = parse_arguments("echo \\\"hi there\\\"");
+        assert_eq!(result, vec!["echo", "\"hi", "there\""]);
+        let result = parse_arguments("\"say \\\"hi\\\"\" C:\\dir\\file");
+        assert_eq!(result, vec!["say \"hi\"", "C:\\dir\\file"]);
+    }
+
+    #[test]
+    fn test_parse_escaped_inner_quotes() {
+        let result = parse_arguments("--msg \"He said \\\"hi\\\"\"");
+        assert_eq!(result, vec!["--msg", "He said \"hi\""]);
+    }
+
+    #[test]
+    fn test_parse_single_quotes() {
+        assert_eq!(parse_arguments("'two words' x"), vec!["two words", "x"]);
+        // No escapes inside single quotes, and double quotes are literal
+        assert_eq!(parse_arguments("'say \"hi\"'"), vec!["say \"hi\""]);
+        assert_eq!(parse_arguments("'C:\\dir\\'"), vec!["C:\\dir\\"]);
+        // Apostrophes inside words aren't quotes
+        assert_eq!(parse_arguments("don't stop"), vec!["don't", "stop"]);
+        assert_eq!(parse_arguments("\"it's\" ok"), vec!["it's", "ok"]);
+    }
+
+    #[test]
+    fn test_parse_unterminated_quote_takes_the_rest() {
+        assert_eq!(parse_arguments("a \"b c"), vec!["a", "b c"]);
+        assert_eq!(parse_arguments("a 'b c "), vec!["a", "b c "]);
+    }
+
+    #[test]
+    fn test_parse_trailing_and_leading_spaces() {
+        assert_eq!(parse_arguments("  arg1 arg2 \t "), vec!["arg1", "arg2"]);
+        assert_eq!(parse_arguments("\"trailing space \""), vec!["trailing space "]);
+        assert!(parse_arguments("   ").is_empty());
+    }
+}
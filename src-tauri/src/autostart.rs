@@ -0,0 +1,187 @@
+// ============================================
+// Auto-Start
+// Keeps the HKCU Run key entry in line with `start_with_windows`
+// ============================================
+
+#[cfg(target_os = "windows")]
+use windows::core::PCWSTR;
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegDeleteValueW, RegOpenKeyExW, RegQueryValueExW, RegSetValueExW, HKEY,
+    HKEY_CURRENT_USER, KEY_QUERY_VALUE, KEY_SET_VALUE, REG_SAM_FLAGS, REG_SZ,
+};
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+/// Name of our value under the Run key
+#[cfg(target_os = "windows")]
+const RUN_VALUE_NAME: &str = "USB Configurator";
+
+/// Command line stored in the Run key for `exe`
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn autostart_command(exe: &std::path::Path) -> String {
+    format!("\"{}\"", exe.display())
+}
+
+/// Whether a stored Run command still launches `expected` (paths are
+/// case-insensitive on Windows)
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn command_matches(stored: &str, expected: &str) -> bool {
+    stored.trim().eq_ignore_ascii_case(expected)
+}
+
+/// Whether our Run value (`stored`, if there is one) already matches the
+/// setting. When enabled it must launch `expected`, so an entry left by an
+/// old install location is rewritten; when disabled, any value of ours is
+/// stale and removed.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn run_value_in_sync(enabled: bool, stored: Option<&str>, expected: &str) -> bool {
+    match stored {
+        Some(stored) => enabled && command_matches(stored, expected),
+        None => !enabled,
+    }
+}
+
+/// Add or remove the Run key entry for the current executable
+pub fn set_autostart(enabled: bool) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let key = open_run_key(KEY_SET_VALUE)?;
+        let name = wide(RUN_VALUE_NAME);
+
+        let status = if enabled {
+            let command = wide(&current_command()?);
+            let bytes = unsafe { std::slice::from_raw_parts(command.as_ptr() as *const u8, command.len() * 2) };
+            unsafe { RegSetValueExW(key, PCWSTR(name.as_ptr()), 0, REG_SZ, Some(bytes)) }
+        } else {
+            match unsafe { RegDeleteValueW(key, PCWSTR(name.as_ptr())) } {
+                ERROR_FILE_NOT_FOUND => ERROR_SUCCESS, // Already off
+                status => status,
+            }
+        };
+        unsafe {
+            let _ = RegCloseKey(key);
+        }
+
+        if status != ERROR_SUCCESS {
+            return Err(format!("Failed to update auto-start entry (error {})", status.0));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        if enabled {
+            Err("Auto-start is only supported on Windows".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Bring the Run key in line with the setting. Returns whether anything had
+/// to change.
+pub fn sync_autostart(enabled: bool) -> Result<bool, String> {
+    #[cfg(target_os = "windows")]
+    let in_sync = run_value_in_sync(enabled, read_run_value()?.as_deref(), &current_command()?);
+    #[cfg(not(target_os = "windows"))]
+    let in_sync = !enabled;
+
+    if in_sync {
+        return Ok(false);
+    }
+    set_autostart(enabled)?;
+    Ok(true)
+}
+
+#[cfg(target_os = "windows")]
+fn current_command() -> Result<String, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Cannot locate executable: {}", e))?;
+    Ok(autostart_command(&exe))
+}
+
+#[cfg(target_os = "windows")]
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn open_run_key(access: REG_SAM_FLAGS) -> Result<HKEY, String> {
+    let subkey = wide(RUN_KEY);
+    let mut key = HKEY::default();
+    let status = unsafe { RegOpenKeyExW(HKEY_CURRENT_USER, PCWSTR(subkey.as_ptr()), 0, access, &mut key) };
+    if status != ERROR_SUCCESS {
+        return Err(format!("Failed to open Run key (error {})", status.0));
+    }
+    Ok(key)
+}
+
+/// Our Run key value, or `None` if there isn't one
+#[cfg(target_os = "windows")]
+fn read_run_value() -> Result<Option<String>, String> {
+    let key = open_run_key(KEY_QUERY_VALUE)?;
+    let name = wide(RUN_VALUE_NAME);
+
+    let result = unsafe {
+        let mut size = 0u32;
+        match RegQueryValueExW(key, PCWSTR(name.as_ptr()), None, None, None, Some(&mut size)) {
+            ERROR_FILE_NOT_FOUND => Ok(None),
+            ERROR_SUCCESS => {
+                let mut buffer = vec![0u16; (size as usize).div_ceil(2)];
+                let status = RegQueryValueExW(
+                    key,
+                    PCWSTR(name.as_ptr()),
+                    None,
+                    None,
+                    Some(buffer.as_mut_ptr() as *mut u8),
+                    Some(&mut size),
+                );
+                if status == ERROR_SUCCESS {
+                    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+                    Ok(Some(String::from_utf16_lossy(&buffer[..len])))
+                } else {
+                    Err(format!("Failed to read auto-start entry (error {})", status.0))
+                }
+            }
+            status => Err(format!("Failed to read auto-start entry (error {})", status.0)),
+        }
+    };
+    unsafe {
+        let _ = RegCloseKey(key);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autostart_command_is_quoted() {
+        let exe = std::path::Path::new(r"C:\Program Files\USB Configurator\app.exe");
+        assert_eq!(autostart_command(exe), r#""C:\Program Files\USB Configurator\app.exe""#);
+    }
+
+    #[test]
+    fn test_command_matches() {
+        let expected = r#""C:\Apps\app.exe""#;
+        assert!(command_matches(r#""c:\apps\APP.exe" "#, expected));
+        assert!(!command_matches(r#""D:\Old\app.exe""#, expected));
+    }
+
+    #[test]
+    fn test_run_value_in_sync() {
+        let expected = r#""C:\Apps\app.exe""#;
+        let moved = r#""D:\Old\app.exe""#;
+        assert!(run_value_in_sync(true, Some(expected), expected));
+        assert!(!run_value_in_sync(true, Some(moved), expected));
+        assert!(!run_value_in_sync(true, None, expected));
+        assert!(run_value_in_sync(false, None, expected));
+        // A stale entry for a moved install is removed when disabled
+        assert!(!run_value_in_sync(false, Some(moved), expected));
+        assert!(!run_value_in_sync(false, Some(expected), expected));
+    }
+}
@@ -0,0 +1,107 @@
+// ============================================
+// Mouse and Gamepad Buttons
+// Remap devices that present as mice or game controllers report buttons
+// rather than keys. These map them to `button_code`s alongside keyboard
+// virtual keys:
+//   - Mouse buttons use their virtual-key codes (VK_LBUTTON = 0x01, ...)
+//   - Gamepad buttons are `GAMEPAD_BUTTON_BASE` + HID button usage, which
+//     keeps them clear of every virtual key
+// ============================================
+
+use std::collections::{HashMap, HashSet};
+
+/// `RAWMOUSE::usButtonFlags` down bits and the virtual key of each button
+const MOUSE_BUTTON_DOWN_FLAGS: [(u16, u16); 5] = [
+    (0x0001, 0x01), // RI_MOUSE_LEFT_BUTTON_DOWN -> VK_LBUTTON
+    (0x0004, 0x02), // RI_MOUSE_RIGHT_BUTTON_DOWN -> VK_RBUTTON
+    (0x0010, 0x04), // RI_MOUSE_MIDDLE_BUTTON_DOWN -> VK_MBUTTON
+    (0x0040, 0x05), // RI_MOUSE_BUTTON_4_DOWN -> VK_XBUTTON1
+    (0x0100, 0x06), // RI_MOUSE_BUTTON_5_DOWN -> VK_XBUTTON2
+];
+
+/// Gamepad button N (HID button page usage) has button code this + N
+pub const GAMEPAD_BUTTON_BASE: u16 = 0x100;
+
+/// Generic desktop usages registered besides the keyboard
+pub const HID_USAGE_GENERIC_JOYSTICK: u16 = 0x04;
+pub const HID_USAGE_GENERIC_GAMEPAD: u16 = 0x05;
+
+/// Button codes of the mouse buttons that went down in one raw mouse event.
+/// Movement-only and button-up events yield nothing.
+pub fn mouse_buttons_down(button_flags: u16) -> Vec<u16> {
+    MOUSE_BUTTON_DOWN_FLAGS
+        .iter()
+        .filter(|(flag, _)| button_flags & flag != 0)
+        .map(|(_, vkey)| *vkey)
+        .collect()
+}
+
+pub fn gamepad_button_code(usage: u16) -> u16 {
+    GAMEPAD_BUTTON_BASE.saturating_add(usage)
+}
+
+/// Whether a top-level collection is a joystick or gamepad
+pub fn is_game_controller(usage_page: u16, usage: u16) -> bool {
+    usage_page == 0x01 && matches!(usage, HID_USAGE_GENERIC_JOYSTICK | HID_USAGE_GENERIC_GAMEPAD)
+}
+
+/// Game controllers report the full set of held buttons in every report
+/// (and send reports for stick movement too), so a press is a button that
+/// wasn't held in the device's previous report
+#[derive(Debug, Default)]
+pub struct ButtonStateTracker {
+    held: HashMap<isize, HashSet<u16>>, // device handle -> held button usages
+}
+
+impl ButtonStateTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the buttons held in a report. Returns the newly pressed ones,
+    /// in usage order.
+    pub fn update(&mut self, device: isize, held: &[u16]) -> Vec<u16> {
+        let current: HashSet<u16> = held.iter().copied().collect();
+        let previous = self.held.insert(device, current.clone()).unwrap_or_default();
+        let mut pressed: Vec<u16> = current.difference(&previous).copied().collect();
+        pressed.sort_unstable();
+        pressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mouse_buttons_down() {
+        assert_eq!(mouse_buttons_down(0x0001), vec![0x01]);
+        assert_eq!(mouse_buttons_down(0x0040 | 0x0100), vec![0x05, 0x06]);
+        // Button-up flags (0x0002, 0x0008, ...) and plain movement
+        assert!(mouse_buttons_down(0x0002 | 0x0008 | 0x0200).is_empty());
+        assert!(mouse_buttons_down(0).is_empty());
+    }
+
+    #[test]
+    fn test_gamepad_codes_clear_of_virtual_keys() {
+        assert_eq!(gamepad_button_code(1), 0x101);
+        assert!(gamepad_button_code(1) > 0xFF);
+        assert!(is_game_controller(0x01, HID_USAGE_GENERIC_GAMEPAD));
+        assert!(is_game_controller(0x01, HID_USAGE_GENERIC_JOYSTICK));
+        assert!(!is_game_controller(0x0C, 0x01));
+    }
+
+    #[test]
+    fn test_button_tracker_reports_new_presses_only() {
+        let mut tracker = ButtonStateTracker::new();
+        assert_eq!(tracker.update(1, &[3]), vec![3]);
+        // Held across a stick-movement report
+        assert!(tracker.update(1, &[3]).is_empty());
+        assert_eq!(tracker.update(1, &[3, 1]), vec![1]);
+        // Released, then pressed again
+        assert!(tracker.update(1, &[]).is_empty());
+        assert_eq!(tracker.update(1, &[3]), vec![3]);
+        // Devices are tracked separately
+        assert_eq!(tracker.update(2, &[3]), vec![3]);
+    }
+}
@@ -0,0 +1,248 @@
+// ============================================
+// Command-Line Mode
+// `--list-devices` and `--run-binding <id>` work headless: they print an
+// IpcResult as JSON to stdout and exit without starting the UI.
+// `--config-dir <path>` picks the config directory for either mode.
+//
+// Exit codes:
+//   0  success
+//   1  the operation ran but failed (action error, disabled binding, remapping
+//      paused, HID error)
+//   2  usage error (unknown option, missing binding ID)
+//   3  no binding with the given ID
+//   4  the config or HID API could not be initialized
+// ============================================
+
+use crate::config::ConfigManager;
+use crate::executor::ActionExecutor;
+use crate::hid::HidManager;
+use crate::types::IpcResult;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_NOT_FOUND: i32 = 3;
+pub const EXIT_INIT: i32 = 4;
+
+const USAGE: &str = "\
+Usage: buttonremap [--config-dir <path>] [OPTION]
+
+With no option the app starts normally.
+
+Options:
+  --list-devices        Print connected HID devices as JSON
+  --run-binding <id>    Run a saved binding's action and print the result as JSON
+  --help                Show this help
+  --config-dir <path>   Keep config and logs in <path> (created if missing)
+                        instead of the default; overrides BUTTONREMAP_CONFIG_DIR
+
+Exit codes: 0 success, 1 failed, 2 usage error, 3 binding not found,
+4 initialization error";
+
+#[derive(Debug, PartialEq)]
+pub enum CliCommand {
+    ListDevices,
+    RunBinding(String),
+    Help,
+}
+
+/// Remove `--config-dir <path>` (or `--config-dir=<path>`) from `args` and
+/// return the path
+pub fn take_config_dir(args: &mut Vec<String>) -> Result<Option<PathBuf>, String> {
+    let Some(position) = args
+        .iter()
+        .position(|a| a == "--config-dir" || a.starts_with("--config-dir="))
+    else {
+        return Ok(None);
+    };
+
+    let option = args.remove(position);
+    let path = match option.strip_prefix("--config-dir=") {
+        Some(path) => path.to_string(),
+        None if args.get(position).is_some_and(|p| !p.starts_with("--")) => args.remove(position),
+        None => String::new(),
+    };
+    if path.trim().is_empty() {
+        return Err("--config-dir needs a path".to_string());
+    }
+    Ok(Some(PathBuf::from(path)))
+}
+
+/// The command in the process arguments (without the program name), or
+/// `None` to start the UI. Only `--` options count, so platform arguments
+/// such as macOS's `-psn_...` still start the UI.
+pub fn parse_cli_args(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let Some(option) = args.iter().find(|a| a.starts_with("--")) else {
+        return Ok(None);
+    };
+
+    let command = match option.as_str() {
+        "--list-devices" => CliCommand::ListDevices,
+        "--run-binding" => {
+            let position = args.iter().position(|a| a == option).unwrap_or_default();
+            match args.get(position + 1).filter(|id| !id.starts_with("--")) {
+                Some(id) => CliCommand::RunBinding(id.clone()),
+                None => return Err("--run-binding needs a binding ID".to_string()),
+            }
+        }
+        "--help" => CliCommand::Help,
+        other => return Err(format!("Unknown option: {}", other)),
+    };
+    Ok(Some(command))
+}
+
+/// Run the CLI command in `args`, if any, and return the process exit code.
+/// `None` means no CLI option was given and the UI should start. A
+/// `--config-dir` applies either way.
+pub fn run_from_args(args: &[String]) -> Option<i32> {
+    let mut args = args.to_vec();
+    match take_config_dir(&mut args) {
+        Ok(Some(dir)) => crate::config::set_config_dir_arg(dir),
+        Ok(None) => {}
+        Err(e) => return Some(usage_error(&e)),
+    }
+
+    let command = match parse_cli_args(&args) {
+        Ok(Some(command)) => command,
+        Ok(None) => return None,
+        Err(e) => return Some(usage_error(&e)),
+    };
+
+    attach_console();
+    Some(match command {
+        CliCommand::Help => {
+            println!("{}", USAGE);
+            EXIT_OK
+        }
+        CliCommand::ListDevices => list_devices(),
+        CliCommand::RunBinding(id) => run_binding(&id),
+    })
+}
+
+fn usage_error(error: &str) -> i32 {
+    attach_console();
+    eprintln!("{}\n\n{}", error, USAGE);
+    EXIT_USAGE
+}
+
+fn list_devices() -> i32 {
+    let config = match ConfigManager::new() {
+        Ok(config) => config,
+        Err(e) => return print_error::<()>(format!("Failed to load config: {}", e), EXIT_INIT),
+    };
+    let mut hid = match HidManager::new() {
+        Ok(hid) => hid,
+        Err(e) => return print_error::<()>(e.to_string(), EXIT_INIT),
+    };
+    hid.set_use_serial_in_device_id(config.get_settings().use_serial_in_device_id);
+    for device_id in config.get_configured_device_ids() {
+        hid.set_device_configured(&device_id);
+    }
+
+    match hid.list_devices() {
+        Ok(devices) => print_result(&IpcResult::ok(devices), EXIT_OK),
+        Err(e) => print_error::<()>(e.to_string(), EXIT_FAILED),
+    }
+}
+
+fn run_binding(binding_id: &str) -> i32 {
+    let config = match ConfigManager::new() {
+        Ok(config) => config,
+        Err(e) => return print_error::<()>(format!("Failed to load config: {}", e), EXIT_INIT),
+    };
+    let Some(binding) = config.get_binding_by_id(binding_id) else {
+        return print_error::<()>(format!("Binding not found: {}", binding_id), EXIT_NOT_FOUND);
+    };
+    // Same gates as a physical press and the HTTP API
+    if !config.is_remapping_enabled() {
+        return print_error::<()>("Remapping is paused".to_string(), EXIT_FAILED);
+    }
+    if !binding.enabled {
+        return print_error::<()>(format!("Binding {} is disabled", binding_id), EXIT_FAILED);
+    }
+
+    let config = Arc::new(Mutex::new(config));
+    let result = ActionExecutor::new(config.clone()).run_binding(&binding, "CLI");
+
+    // The process exits right after, so write the buffered log entries now
+    if let Ok(mut config) = config.lock() {
+        if let Err(e) = config.flush_logs() {
+            eprintln!("Failed to write logs: {}", e);
+        }
+    }
+
+    let code = if result.succeeded { EXIT_OK } else { EXIT_FAILED };
+    print_result(&IpcResult::ok(result), code)
+}
+
+fn print_result<T: Serialize>(result: &IpcResult<T>, code: i32) -> i32 {
+    match serde_json::to_string_pretty(result) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to serialize result: {}", e),
+    }
+    code
+}
+
+fn print_error<T: Serialize>(error: String, code: i32) -> i32 {
+    print_result(&IpcResult::<T>::err(error), code)
+}
+
+/// Release builds use the Windows GUI subsystem and start without a console,
+/// so borrow the one of the shell that launched us for stdout/stderr
+fn attach_console() {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_cli_args() {
+        assert_eq!(parse_cli_args(&args(&[])), Ok(None));
+        assert_eq!(parse_cli_args(&args(&["-psn_0_12345"])), Ok(None));
+        assert_eq!(parse_cli_args(&args(&["--list-devices"])), Ok(Some(CliCommand::ListDevices)));
+        assert_eq!(
+            parse_cli_args(&args(&["--run-binding", "abc-123"])),
+            Ok(Some(CliCommand::RunBinding("abc-123".to_string())))
+        );
+        assert_eq!(parse_cli_args(&args(&["--help"])), Ok(Some(CliCommand::Help)));
+    }
+
+    #[test]
+    fn test_take_config_dir() {
+        let mut list = args(&["--config-dir", "D:\\Portable", "--list-devices"]);
+        assert_eq!(take_config_dir(&mut list), Ok(Some(PathBuf::from("D:\\Portable"))));
+        assert_eq!(list, args(&["--list-devices"]));
+
+        let mut list = args(&["--config-dir=/tmp/remap"]);
+        assert_eq!(take_config_dir(&mut list), Ok(Some(PathBuf::from("/tmp/remap"))));
+        assert!(list.is_empty());
+
+        let mut list = args(&["--list-devices"]);
+        assert_eq!(take_config_dir(&mut list), Ok(None));
+        assert_eq!(list, args(&["--list-devices"]));
+
+        assert!(take_config_dir(&mut args(&["--config-dir"])).is_err());
+        assert!(take_config_dir(&mut args(&["--config-dir", "--help"])).is_err());
+        assert!(take_config_dir(&mut args(&["--config-dir="])).is_err());
+    }
+
+    #[test]
+    fn test_parse_cli_args_errors() {
+        assert!(parse_cli_args(&args(&["--run-binding"])).is_err());
+        assert!(parse_cli_args(&args(&["--run-binding", "--list-devices"])).is_err());
+        assert!(parse_cli_args(&args(&["--frobnicate"])).is_err());
+    }
+}
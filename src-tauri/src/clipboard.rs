@@ -0,0 +1,96 @@
+// ============================================
+// Clipboard Templates
+// Reads the clipboard, substitutes it into a template and retypes the result
+// ============================================
+
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::HGLOBAL;
+#[cfg(target_os = "windows")]
+use windows::Win32::System::DataExchange::{
+    CloseClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Memory::{GlobalLock, GlobalUnlock};
+
+/// Placeholder replaced with the clipboard text
+pub const CLIPBOARD_PLACEHOLDER: &str = "{clipboard}";
+
+#[cfg(target_os = "windows")]
+const CF_UNICODETEXT: u32 = 13;
+
+/// Substitute clipboard text into a template. An empty template types the
+/// clipboard as-is.
+pub fn apply_template(template: &str, clipboard: &str) -> String {
+    if template.is_empty() {
+        return clipboard.to_string();
+    }
+    template.replace(CLIPBOARD_PLACEHOLDER, clipboard)
+}
+
+/// Current clipboard text, or `None` if the clipboard is empty or holds
+/// something other than text
+pub fn read_clipboard_text() -> Result<Option<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        unsafe {
+            if IsClipboardFormatAvailable(CF_UNICODETEXT).is_err() {
+                return Ok(None);
+            }
+
+            OpenClipboard(None).map_err(|e| format!("Failed to open clipboard: {}", e))?;
+            let text = read_locked_unicode_text();
+            let _ = CloseClipboard();
+
+            Ok(text.filter(|t| !t.is_empty()))
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("Clipboard access is only supported on Windows".to_string())
+    }
+}
+
+/// Read CF_UNICODETEXT while the clipboard is open
+#[cfg(target_os = "windows")]
+unsafe fn read_locked_unicode_text() -> Option<String> {
+    let handle = GetClipboardData(CF_UNICODETEXT).ok()?;
+    let hglobal = HGLOBAL(handle.0);
+
+    let ptr = GlobalLock(hglobal) as *const u16;
+    if ptr.is_null() {
+        return None;
+    }
+
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+        len += 1;
+    }
+    let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+    let _ = GlobalUnlock(hglobal);
+
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_code_fence_template() {
+        let clipboard = "fn main() {\n    println!(\"hi\");\n}";
+        let result = apply_template("```\n{clipboard}\n```", clipboard);
+        assert_eq!(result, "```\nfn main() {\n    println!(\"hi\");\n}\n```");
+    }
+
+    #[test]
+    fn test_apply_template_multiple_placeholders() {
+        assert_eq!(apply_template("{clipboard} / {clipboard}", "x"), "x / x");
+    }
+
+    #[test]
+    fn test_apply_template_empty_or_without_placeholder() {
+        assert_eq!(apply_template("", "plain"), "plain");
+        assert_eq!(apply_template("static text", "ignored"), "static text");
+    }
+}
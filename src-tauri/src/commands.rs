@@ -1,48 +1,69 @@
 use crate::types::{
-    ActionConfig, AppSettings, DeviceBinding, DeviceStatus, HidDevice, IpcResult, LogEntry, LogEntryLevel,
-    MonitoringState, TriggerType,
+    ActionConfig, AppSettings, BindingTestResult, BindingWarning, CapturedReport, CompactLogsResult, ConfigLockStatus, ConfigPaths, DeviceBinding, DeviceBindingGroup, DeviceStatus, HidDevice, HidReportKind, IpcResult, LogEntry, LogEntryLevel,
+    CodedError, HidInterfaceInfo, DeviceExplanation, HotkeyConflict, HotkeyPreview, InterfaceActivity, InstanceStatus, LogOrder, MonitoringState, ProfileSummary, TriggerType, UsageStats,
 };
+use crate::autostart;
+use crate::config::{ConfigError, ConfigManager, Profile, ProfileRule};
+use crate::executor::ActionExecutor;
+use crate::plugins::PluginRegistration;
+use crate::summary;
 use crate::AppState;
-use std::process::Command;
 use tauri::{Emitter, State};
 
-/// Parse arguments string respecting quoted sections
-fn parse_arguments(args: &str) -> Vec<String> {
-    let mut result = Vec::new();
-    let mut current = String::new();
-    let mut in_quotes = false;
-
-    for c in args.chars() {
-        match c {
-            '"' => in_quotes = !in_quotes,
-            ' ' | '\t' if !in_quotes => {
-                if !current.is_empty() {
-                    result.push(current.clone());
-                    current.clear();
-                }
-            }
-            _ => current.push(c),
-        }
-    }
+// ============================================
+// Device Commands
+// ============================================
+
+#[tauri::command]
+pub async fn list_devices(state: State<'_, AppState>) -> Result<IpcResult<Vec<HidDevice>>, String> {
+    let result = {
+        let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        hid.list_devices()
+    };
 
-    if !current.is_empty() {
-        result.push(current);
+    match result {
+        Ok(mut devices) => {
+            remember_devices(&state, &devices)?;
+            append_disconnected_devices(&state, &mut devices)?;
+            Ok(IpcResult::ok(devices))
+        }
+        Err(e) => Ok(IpcResult::from_error(&e)),
     }
+}
 
-    result
+/// Add a `Disconnected` entry for each bound device that isn't plugged in,
+/// so the UI keeps showing it
+fn append_disconnected_devices(state: &AppState, devices: &mut Vec<HidDevice>) -> Result<(), String> {
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    let disconnected = config.disconnected_devices(devices);
+    devices.extend(disconnected);
+    Ok(())
 }
 
-// ============================================
-// Device Commands
-// ============================================
+/// Add scanned devices to the known-device history used by `find_stale_bindings`
+fn remember_devices(state: &AppState, devices: &[HidDevice]) -> Result<(), String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    if let Err(e) = config.remember_devices(devices.iter().map(|d| d.id.as_str())) {
+        log::error!("Failed to save known devices: {}", e);
+    }
+    Ok(())
+}
 
+/// Like `list_devices`, but returns the last enumerated list without rescanning USB.
+/// Results may be stale until `refresh_devices` is called.
 #[tauri::command]
-pub async fn list_devices(state: State<'_, AppState>) -> Result<IpcResult<Vec<HidDevice>>, String> {
-    let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
-    
-    match hid.list_devices() {
-        Ok(devices) => Ok(IpcResult::ok(devices)),
-        Err(e) => Ok(IpcResult::err(e.to_string())),
+pub async fn list_devices_cached(state: State<'_, AppState>) -> Result<IpcResult<Vec<HidDevice>>, String> {
+    let result = {
+        let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        hid.list_devices_cached()
+    };
+
+    match result {
+        Ok(mut devices) => {
+            append_disconnected_devices(&state, &mut devices)?;
+            Ok(IpcResult::ok(devices))
+        }
+        Err(e) => Ok(IpcResult::from_error(&e)),
     }
 }
 
@@ -63,6 +84,21 @@ pub async fn refresh_devices(
                 Some("HID".to_string()),
             );
 
+            // Emit events for newly connected devices
+            for device_id in &result.connected_ids {
+                config.add_log(
+                    LogEntryLevel::Info,
+                    format!("Device connected: {}", device_id),
+                    Some(device_id.clone()),
+                );
+
+                if let Err(e) = app.emit("device-connected", serde_json::json!({
+                    "deviceId": device_id
+                })) {
+                    log::error!("Failed to emit device-connected event: {}", e);
+                }
+            }
+
             // Emit events for disconnected devices
             for device_id in &result.disconnected_ids {
                 config.add_log(
@@ -79,9 +115,246 @@ pub async fn refresh_devices(
                 }
             }
 
-            Ok(IpcResult::ok(result.devices))
+            // Apply the reenable_on_reconnect setting to returning devices
+            let mut rearmed = false;
+            for device_id in &result.reconnected_ids {
+                if let Err(e) = config.handle_device_reconnected(device_id) {
+                    log::error!("Failed to update bindings for reconnected device {}: {}", device_id, e);
+                }
+
+                // Re-confirm the device's binding (matched by serial when IDs carry one)
+                if let Some(binding) = config.get_binding(device_id) {
+                    hid.set_device_configured(&binding.device_id);
+                    rearmed = true;
+                    config.add_log(
+                        LogEntryLevel::Info,
+                        format!("Configured device reconnected: {} (binding for {})", device_id, binding.device_id),
+                        Some(device_id.clone()),
+                    );
+                }
+            }
+
+            if let Err(e) = config.remember_devices(result.devices.iter().map(|d| d.id.as_str())) {
+                log::error!("Failed to save known devices: {}", e);
+            }
+
+            // Statuses were worked out before re-arming
+            let mut devices = if rearmed {
+                hid.list_devices_cached().unwrap_or(result.devices)
+            } else {
+                result.devices
+            };
+            let disconnected = config.disconnected_devices(&devices);
+            devices.extend(disconnected);
+            Ok(IpcResult::ok(devices))
+        }
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+/// Bindings whose device is neither connected now nor was ever seen by a
+/// scan, so the UI can offer to remove them. Unplugged devices aren't stale.
+#[tauri::command]
+pub async fn find_stale_bindings(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<Vec<DeviceBinding>>, String> {
+    let devices = {
+        let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        match hid.list_devices_cached() {
+            Ok(devices) => devices,
+            Err(e) => return Ok(IpcResult::from_error(&e)),
+        }
+    };
+    remember_devices(&state, &devices)?;
+
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    let connected: Vec<String> = devices.into_iter().map(|d| d.id).collect();
+    Ok(IpcResult::ok(config.find_stale_bindings(&connected)))
+}
+
+/// Full, non-deduplicated device list (every interface) for support reports
+#[tauri::command]
+pub async fn export_device_list(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<Vec<HidInterfaceInfo>>, String> {
+    let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+
+    match hid.export_device_list() {
+        Ok(interfaces) => Ok(IpcResult::ok(interfaces)),
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+/// Rebuild HidManager's configured-device set from the saved bindings.
+/// Call after anything that replaces bindings wholesale (import, reload).
+pub(crate) fn resync_configured_devices(state: &AppState) -> Result<usize, String> {
+    let device_ids = {
+        let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+        config.get_configured_device_ids()
+    };
+    let count = device_ids.len();
+
+    let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+    hid.resync_configured_devices(device_ids);
+    Ok(count)
+}
+
+#[tauri::command]
+pub async fn resync_hid_configured(state: State<'_, AppState>) -> Result<IpcResult<()>, String> {
+    match resync_configured_devices(&state) {
+        Ok(count) => {
+            log::info!("Resynced configured devices from {} binding(s)", count);
+            Ok(IpcResult::ok_empty())
+        }
+        Err(e) => Ok(IpcResult::err(e)),
+    }
+}
+
+/// Rebuild the configured-device set from the bindings and return the
+/// device list with fresh statuses, e.g. after a `device-connected` event
+#[tauri::command]
+pub async fn rescan_configured_devices(state: State<'_, AppState>) -> Result<IpcResult<Vec<HidDevice>>, String> {
+    let count = match resync_configured_devices(&state) {
+        Ok(count) => count,
+        Err(e) => return Ok(IpcResult::err(e)),
+    };
+
+    let devices = {
+        let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        match hid.refresh_devices() {
+            Ok(devices) => devices,
+            Err(e) => return Ok(IpcResult::from_error(&e)),
+        }
+    };
+
+    let configured = devices.iter().filter(|d| d.status == DeviceStatus::Configured).count();
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    config.add_log(
+        LogEntryLevel::Info,
+        format!(
+            "Rescanned devices: {} of {} connected device(s) configured ({} bound device ID(s))",
+            configured,
+            devices.len(),
+            count
+        ),
+        Some("HID".to_string()),
+    );
+    Ok(IpcResult::ok(devices))
+}
+
+/// Default and maximum listening window for `detect_active_interface`
+const DETECT_INTERFACE_DEFAULT_MS: u64 = 3000;
+const DETECT_INTERFACE_MAX_MS: u64 = 10_000;
+
+#[tauri::command]
+pub async fn detect_active_interface(
+    state: State<'_, AppState>,
+    device_id: String,
+    window_ms: Option<u64>,
+) -> Result<IpcResult<Vec<InterfaceActivity>>, String> {
+    let window_ms = window_ms
+        .unwrap_or(DETECT_INTERFACE_DEFAULT_MS)
+        .min(DETECT_INTERFACE_MAX_MS);
+    let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+
+    match hid.detect_active_interface(&device_id, std::time::Duration::from_millis(window_ms)) {
+        Ok(activity) => Ok(IpcResult::ok(activity)),
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+/// Default and maximum capture length for `capture_hid_report`
+const CAPTURE_REPORT_DEFAULT_MS: u64 = 5000;
+const CAPTURE_REPORT_MAX_MS: u64 = 30_000;
+
+/// Raw input reports a device sends while the user presses its buttons, as
+/// hex, for working out what a device that isn't recognized actually sends
+#[tauri::command]
+pub async fn capture_hid_report(
+    state: State<'_, AppState>,
+    device_id: String,
+    duration_ms: Option<u64>,
+) -> Result<IpcResult<Vec<CapturedReport>>, String> {
+    let duration_ms = duration_ms
+        .unwrap_or(CAPTURE_REPORT_DEFAULT_MS)
+        .min(CAPTURE_REPORT_MAX_MS);
+    let result = {
+        let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        hid.capture_reports(&device_id, std::time::Duration::from_millis(duration_ms))
+    };
+
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    match result {
+        Ok(reports) => {
+            config.add_log(
+                LogEntryLevel::Info,
+                format!("Captured {} report(s) in {}ms", reports.len(), duration_ms),
+                Some(device_id),
+            );
+            Ok(IpcResult::ok(reports))
+        }
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+/// Write an output or feature report to a device, e.g. to drive its LEDs
+/// or send the init command that unlocks extra buttons. `report_hex` is the
+/// payload; `report_id` defaults to 0x00 for devices without numbered reports.
+#[tauri::command]
+pub async fn send_hid_report(
+    state: State<'_, AppState>,
+    device_id: String,
+    report_hex: String,
+    report_id: Option<u8>,
+    kind: Option<HidReportKind>,
+) -> Result<IpcResult<usize>, String> {
+    let report = match crate::hid::parse_report_hex(&report_hex, report_id) {
+        Ok(report) => report,
+        Err(e) => return Ok(IpcResult::err(e)),
+    };
+    let kind = kind.unwrap_or_default();
+    let result = {
+        let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        hid.send_report(&device_id, &report, kind)
+    };
+
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    let hex = crate::hid::format_report_hex(&report, report.len());
+    match result {
+        Ok(written) => {
+            config.add_log(
+                LogEntryLevel::Info,
+                format!("Sent {:?} report: {}", kind, hex),
+                Some(device_id),
+            );
+            Ok(IpcResult::ok(written))
+        }
+        Err(e) => {
+            config.add_log(
+                LogEntryLevel::Warn,
+                format!("Failed to send {:?} report {}: {}", kind, hex, e),
+                Some(device_id),
+            );
+            Ok(IpcResult::from_error(&e))
+        }
+    }
+}
+
+/// Every HID device the OS's Raw Input list has, including ones hidapi
+/// can't open (e.g. keyboards held exclusively by another program)
+#[tauri::command]
+pub async fn list_raw_input_devices() -> Result<IpcResult<Vec<HidDevice>>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        match crate::rawinput::list_raw_input_devices() {
+            Ok(devices) => Ok(IpcResult::ok(devices)),
+            Err(e) => Ok(IpcResult::err(e)),
         }
-        Err(e) => Ok(IpcResult::err(e.to_string())),
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Ok(IpcResult::err("Raw Input devices are only listed on Windows".to_string()))
     }
 }
 
@@ -94,7 +367,7 @@ pub async fn get_device_info(
     
     match hid.get_device_info(&device_id) {
         Ok(device) => Ok(IpcResult::ok(device)),
-        Err(e) => Ok(IpcResult::err(e.to_string())),
+        Err(e) => Ok(IpcResult::from_error(&e)),
     }
 }
 
@@ -115,8 +388,16 @@ pub async fn start_monitoring(
         "Started 'Find by Press' monitoring - press any button on your device".to_string(),
         Some("Input".to_string()),
     );
+    let use_serial = config.get_settings().use_serial_in_device_id;
     drop(config); // Release lock early
 
+    // Clients polling get_monitoring_state read the result from here
+    let detected_slot = {
+        let hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        hid.clear_detected_device();
+        hid.detected_device_slot()
+    };
+
     // On Windows, use BOTH Raw Input API and HID API in parallel
     #[cfg(windows)]
     {
@@ -146,7 +427,19 @@ pub async fn start_monitoring(
         std::thread::spawn(move || {
             println!("🔵 [RUST] Parallel monitor listener thread started");
 
-            if let Ok(detected_device) = rx.recv() {
+            if let Ok(mut detected_device) = rx.recv() {
+                // Raw input reports VID:PID; match the ID list_devices would give it
+                detected_device.id = crate::config::endpoint_device_id(
+                    crate::config::build_device_id(
+                        &detected_device.vendor_id,
+                        &detected_device.product_id,
+                        detected_device.serial_number.as_deref(),
+                        use_serial,
+                    ),
+                    detected_device.interface_number,
+                    detected_device.total_interfaces,
+                );
+                crate::hid::record_detected_device(&detected_slot, &detected_device);
                 println!("🔥 [RUST] DEVICE DETECTED!");
                 println!("   {} ({}:{})", detected_device.name, detected_device.vendor_id, detected_device.product_id);
 
@@ -173,16 +466,118 @@ pub async fn start_monitoring(
         Ok(IpcResult::ok_empty())
     }
 
-    // On non-Windows platforms, fall back to HID monitoring
-    #[cfg(not(windows))]
+    // On macOS, IOHIDManager reports which device each press came from
+    #[cfg(target_os = "macos")]
+    {
+        use crate::input_monitor::InputMonitor;
+        use crate::macos_input::MacInputMonitor;
+
+        let hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        hid.start_monitoring().map_err(|e| e.to_string())?;
+        // Shares the HID flag so stop_monitoring also ends this monitor
+        let mut monitor = MacInputMonitor::with_flag(hid.monitoring_flag());
+        drop(hid);
+
+        let rx = monitor.start_monitoring();
+        let app_clone = app.clone();
+
+        std::thread::spawn(move || {
+            if let Ok(mut detected_device) = rx.recv() {
+                detected_device.id = crate::config::endpoint_device_id(
+                    crate::config::build_device_id(
+                        &detected_device.vendor_id,
+                        &detected_device.product_id,
+                        detected_device.serial_number.as_deref(),
+                        use_serial,
+                    ),
+                    detected_device.interface_number,
+                    detected_device.total_interfaces,
+                );
+                crate::hid::record_detected_device(&detected_slot, &detected_device);
+                log::info!(
+                    "Device detected: {} ({}:{}) - Press recognized!",
+                    detected_device.name,
+                    detected_device.vendor_id,
+                    detected_device.product_id
+                );
+
+                if let Err(e) = app_clone.emit("monitoring-detected", serde_json::json!({
+                    "device": detected_device
+                })) {
+                    log::error!("Failed to emit event: {}", e);
+                }
+            }
+        });
+
+        Ok(IpcResult::ok_empty())
+    }
+
+    // On Linux, read key events from the evdev nodes
+    #[cfg(target_os = "linux")]
+    {
+        use crate::input_monitor::InputMonitor;
+        use crate::linux_input::LinuxInputMonitor;
+
+        let hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        // Surface missing /dev/input permissions instead of silently detecting nothing
+        let mut monitor = match LinuxInputMonitor::open(hid.monitoring_flag()) {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                drop(hid);
+                let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+                config.add_log(LogEntryLevel::Error, e.to_string(), Some("Input".to_string()));
+                return Ok(IpcResult::from_error(&e));
+            }
+        };
+        hid.start_monitoring().map_err(|e| e.to_string())?;
+        drop(hid);
+
+        let rx = monitor.start_monitoring();
+        let app_clone = app.clone();
+
+        std::thread::spawn(move || {
+            if let Ok(mut detected_device) = rx.recv() {
+                detected_device.id = crate::config::endpoint_device_id(
+                    crate::config::build_device_id(
+                        &detected_device.vendor_id,
+                        &detected_device.product_id,
+                        detected_device.serial_number.as_deref(),
+                        use_serial,
+                    ),
+                    detected_device.interface_number,
+                    detected_device.total_interfaces,
+                );
+                crate::hid::record_detected_device(&detected_slot, &detected_device);
+                log::info!(
+                    "Device detected: {} ({}:{}) - Press recognized!",
+                    detected_device.name,
+                    detected_device.vendor_id,
+                    detected_device.product_id
+                );
+
+                if let Err(e) = app_clone.emit("monitoring-detected", serde_json::json!({
+                    "device": detected_device
+                })) {
+                    log::error!("Failed to emit event: {}", e);
+                }
+            }
+        });
+
+        Ok(IpcResult::ok_empty())
+    }
+
+    // On other platforms, fall back to HID monitoring
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
+        let _ = use_serial; // monitor_for_input builds serial IDs itself
         let hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
 
         match hid.start_monitoring() {
             Ok(_) => {
                 let app_clone = app.clone();
 
-                hid.monitor_for_input(move |detected_device| {
+                hid.monitor_for_input(None, move |detected_device| {
+                    crate::hid::record_detected_device(&detected_slot, &detected_device);
                     println!("🔥 [RUST] DEVICE DETECTED CALLBACK FIRED!");
                     log::info!(
                         "⚡ Device detected: {} ({}:{}, Interface {}) - Press recognized!",
@@ -202,7 +597,7 @@ pub async fn start_monitoring(
 
                 Ok(IpcResult::ok_empty())
             }
-            Err(e) => Ok(IpcResult::err(e.to_string())),
+            Err(e) => Ok(IpcResult::from_error(&e)),
         }
     }
 }
@@ -242,6 +637,45 @@ pub async fn get_all_bindings(
     Ok(IpcResult::ok(config.get_all_bindings()))
 }
 
+/// Bindings grouped per device, joined against the last device scan so
+/// devices that aren't plugged in come back as `Disconnected`
+#[tauri::command]
+pub async fn get_bindings_grouped(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<Vec<DeviceBindingGroup>>, String> {
+    let devices = {
+        let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        match hid.list_devices_cached() {
+            Ok(devices) => devices,
+            Err(e) => return Ok(IpcResult::from_error(&e)),
+        }
+    };
+
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(config.get_bindings_grouped(&devices)))
+}
+
+/// Render bindings as Markdown. Also writes it to `path` when one is given.
+#[tauri::command]
+pub async fn export_bindings_markdown(
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<IpcResult<String>, String> {
+    let markdown = {
+        let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+        summary::bindings_to_markdown(&config.get_all_bindings())
+    };
+
+    if let Some(path) = path {
+        if let Err(e) = std::fs::write(&path, &markdown) {
+            return Ok(IpcResult::err(format!("Failed to write {}: {}", path, e)));
+        }
+        log::info!("Exported binding summary to {}", path);
+    }
+
+    Ok(IpcResult::ok(markdown))
+}
+
 #[tauri::command]
 pub async fn get_binding(
     state: State<'_, AppState>,
@@ -275,159 +709,694 @@ pub async fn save_binding(
             
             Ok(IpcResult::ok(saved))
         }
-        Err(e) => Ok(IpcResult::err(e.to_string())),
+        Err(e) => {
+            config.add_log(
+                LogEntryLevel::Warn,
+                format!("Configuration not saved: {}", e),
+                Some("Config".to_string()),
+            );
+            Ok(IpcResult::from_error(&e))
+        }
     }
 }
 
 #[tauri::command]
-pub async fn delete_binding(
+pub async fn set_binding_trigger(
     state: State<'_, AppState>,
     binding_id: String,
-) -> Result<IpcResult<()>, String> {
+    trigger_type: TriggerType,
+) -> Result<IpcResult<DeviceBinding>, String> {
     let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
-    
-    // Get the binding before deleting to update HID manager
-    if let Some(binding) = config.get_binding_by_id(&binding_id) {
-        let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
-        hid.set_device_unconfigured(&binding.device_id);
-    }
-    
-    match config.delete_binding(&binding_id) {
-        Ok(_) => {
+
+    match config.set_binding_trigger(&binding_id, trigger_type) {
+        Ok(updated) => {
             config.add_log(
                 LogEntryLevel::Info,
-                "Configuration deleted".to_string(),
+                format!("Trigger for {} set to {:?}", updated.device_id, updated.trigger_type),
                 Some("Config".to_string()),
             );
-            Ok(IpcResult::ok_empty())
+            Ok(IpcResult::ok(updated))
         }
-        Err(e) => Ok(IpcResult::err(e.to_string())),
+        Err(e) => Ok(IpcResult::from_error(&e)),
     }
 }
 
-// ============================================
-// Settings Commands
-// ============================================
-
-#[tauri::command]
-pub async fn get_settings(state: State<'_, AppState>) -> Result<IpcResult<AppSettings>, String> {
-    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
-    Ok(IpcResult::ok(config.get_settings()))
-}
-
 #[tauri::command]
-pub async fn save_settings(
+pub async fn set_binding_enabled(
     state: State<'_, AppState>,
-    settings: AppSettings,
-) -> Result<IpcResult<AppSettings>, String> {
+    binding_id: String,
+    enabled: bool,
+) -> Result<IpcResult<DeviceBinding>, String> {
     let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
-    
-    match config.save_settings(settings) {
-        Ok(saved) => {
+
+    match config.set_binding_enabled(&binding_id, enabled) {
+        Ok(updated) => {
             config.add_log(
-                LogEntryLevel::Success,
-                "Settings saved".to_string(),
-                Some("System".to_string()),
+                LogEntryLevel::Info,
+                format!(
+                    "Binding for {} {}",
+                    updated.device_id,
+                    if updated.enabled { "enabled" } else { "disabled" }
+                ),
+                Some("Config".to_string()),
             );
-            Ok(IpcResult::ok(saved))
+            Ok(IpcResult::ok(updated))
         }
-        Err(e) => Ok(IpcResult::err(e.to_string())),
+        Err(e) => Ok(IpcResult::from_error(&e)),
     }
 }
 
-// ============================================
-// Action Commands
-// ============================================
+/// Unsaved, disabled binding for a device found by press, for the editor to fill in
+#[tauri::command]
+pub async fn create_binding_template(device: HidDevice) -> Result<IpcResult<DeviceBinding>, String> {
+    Ok(IpcResult::ok(crate::config::binding_template(&device)))
+}
 
+/// Find-and-replace a path prefix across all bindings' actions. With
+/// `dry_run` only the number of bindings that would change is returned.
 #[tauri::command]
-pub async fn test_action(
+pub async fn rewrite_action_paths(
     state: State<'_, AppState>,
-    action: ActionConfig,
-) -> Result<IpcResult<()>, String> {
+    from_prefix: String,
+    to_prefix: String,
+    dry_run: Option<bool>,
+) -> Result<IpcResult<usize>, String> {
     let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
-    
-    config.add_log(
-        LogEntryLevel::Info,
-        format!("Testing action: {} {}", action.executable_path, action.arguments),
-        Some("Test".to_string()),
-    );
-    
-    // Execute the action based on type
-    let result = match action.r#type {
-        crate::types::ActionType::LaunchApp | crate::types::ActionType::RunScript => {
-            if cfg!(target_os = "windows") {
-                Command::new("cmd")
-                    .args(["/C", &action.executable_path])
-                    .args(parse_arguments(&action.arguments))
-                    .spawn()
-            } else {
-                Command::new(&action.executable_path)
-                    .args(parse_arguments(&action.arguments))
-                    .spawn()
+    let dry_run = dry_run.unwrap_or(false);
+
+    match config.rewrite_action_paths(&from_prefix, &to_prefix, dry_run) {
+        Ok(changed) => {
+            if !dry_run {
+                config.add_log(
+                    LogEntryLevel::Info,
+                    format!("Rewrote {} -> {} in {} binding(s)", from_prefix, to_prefix, changed),
+                    Some("Config".to_string()),
+                );
             }
+            Ok(IpcResult::ok(changed))
         }
-        crate::types::ActionType::SystemCommand => {
-            if cfg!(target_os = "windows") {
-                Command::new("cmd")
-                    .args(["/C", &action.executable_path])
-                    .args(parse_arguments(&action.arguments))
-                    .spawn()
-            } else {
-                Command::new("sh")
-                    .args(["-c", &format!("{} {}", action.executable_path, action.arguments)])
-                    .spawn()
-            }
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_binding(
+    state: State<'_, AppState>,
+    binding_id: String,
+) -> Result<IpcResult<()>, String> {
+    remove_binding(&state, &binding_id)
+}
+
+fn remove_binding(state: &AppState, binding_id: &str) -> Result<IpcResult<()>, String> {
+    {
+        let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+        if let Err(e) = config.delete_binding(binding_id) {
+            return Ok(IpcResult::from_error(&e));
         }
-        crate::types::ActionType::Hotkey => {
-            // Execute hotkey using Windows SendInput API
-            #[cfg(target_os = "windows")]
-            {
-                match crate::hotkey::execute_hotkey(&action.executable_path) {
-                    Ok(_) => {
-                        config.add_log(
-                            LogEntryLevel::Success,
-                            format!("Hotkey executed: {}", action.executable_path),
-                            Some("Test".to_string()),
-                        );
-                        return Ok(IpcResult::ok_empty());
-                    }
-                    Err(e) => {
-                        config.add_log(
-                            LogEntryLevel::Error,
-                            format!("Hotkey failed: {}", e),
-                            Some("Test".to_string()),
-                        );
-                        return Ok(IpcResult::err(e));
-                    }
-                }
+        config.add_log(
+            LogEntryLevel::Info,
+            "Configuration deleted".to_string(),
+            Some("Config".to_string()),
+        );
+    }
+
+    // The device stays configured while any of its other bindings remain
+    match resync_configured_devices(state) {
+        Ok(_) => Ok(IpcResult::ok_empty()),
+        Err(e) => Ok(IpcResult::err(e)),
+    }
+}
+
+// ============================================
+// Settings Commands
+// ============================================
+
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<IpcResult<AppSettings>, String> {
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(config.get_settings()))
+}
+
+#[tauri::command]
+pub async fn save_settings(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: AppSettings,
+) -> Result<IpcResult<AppSettings>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    let was_remapping = config.is_remapping_enabled();
+    
+    match config.save_settings(settings) {
+        Ok(saved) => {
+            if saved.remapping_enabled != was_remapping {
+                config.add_log(
+                    LogEntryLevel::Info,
+                    remapping_transition_message(saved.remapping_enabled).to_string(),
+                    Some("System".to_string()),
+                );
             }
-            #[cfg(not(target_os = "windows"))]
-            {
-                return Ok(IpcResult::err("Hotkey simulation only supported on Windows".to_string()));
+
+            // Device IDs change shape when serials are switched on or off
+            let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+            hid.set_use_serial_in_device_id(saved.use_serial_in_device_id);
+            drop(hid);
+
+            // A request in flight may be waiting on the config lock, so
+            // release it while the HTTP API restarts
+            drop(config);
+            let http_api_result = crate::http_api::sync_http_api(&app, &saved);
+            let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+            if let Err(e) = http_api_result {
+                config.add_log(LogEntryLevel::Warn, e, Some("System".to_string()));
+            }
+
+            if let Err(e) = autostart::sync_autostart(saved.start_with_windows) {
+                config.add_log(
+                    LogEntryLevel::Warn,
+                    format!("Settings saved, but auto-start couldn't be updated: {}", e),
+                    Some("System".to_string()),
+                );
+                return Ok(IpcResult::ok(saved));
             }
+
+            config.add_log(
+                LogEntryLevel::Success,
+                "Settings saved".to_string(),
+                Some("System".to_string()),
+            );
+            Ok(IpcResult::ok(saved))
         }
-    };
-    
-    match result {
-        Ok(_) => {
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+/// Log line for pausing or resuming all bindings
+pub fn remapping_transition_message(enabled: bool) -> &'static str {
+    if enabled {
+        "Remapping resumed"
+    } else {
+        "Remapping paused - no bindings will run"
+    }
+}
+
+/// Turn raw-report logging on or off for one device (for debugging odd hardware)
+#[tauri::command]
+pub async fn set_device_verbose(
+    state: State<'_, AppState>,
+    device_id: String,
+    verbose: bool,
+) -> Result<IpcResult<()>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.set_device_verbose(&device_id, verbose) {
+        Ok(()) => Ok(IpcResult::ok_empty()),
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+/// The resolved config directory (the default `<config>/usb-configurator`
+/// or wherever `move_config_dir` put it) and the config and log files in it
+#[tauri::command]
+pub async fn get_config_dir(state: State<'_, AppState>) -> Result<IpcResult<ConfigPaths>, String> {
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(config.paths()))
+}
+
+/// Show the config directory in the OS file manager
+#[tauri::command]
+pub async fn open_config_dir(state: State<'_, AppState>) -> Result<IpcResult<()>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    let dir = config.config_dir().to_path_buf();
+    match crate::launch::open_in_file_manager(&dir) {
+        Ok(()) => Ok(IpcResult::ok_empty()),
+        Err(e) => {
+            config.add_log(LogEntryLevel::Warn, e.clone(), Some("System".to_string()));
+            Ok(IpcResult::err(e))
+        }
+    }
+}
+
+/// Move config.json/logs.json to `new_path` and keep using them from there,
+/// including after a restart
+#[tauri::command]
+pub async fn move_config_dir(
+    state: State<'_, AppState>,
+    new_path: String,
+    remove_old: Option<bool>,
+) -> Result<IpcResult<String>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    let new_dir = std::path::PathBuf::from(&new_path);
+
+    if let Err(e) = config.move_to(new_dir.clone(), remove_old.unwrap_or(false)) {
+        return Ok(IpcResult::err(format!("Failed to move config: {}", e)).with_code(e.code()));
+    }
+
+    // --config-dir and BUTTONREMAP_CONFIG_DIR win over the pointer on restart
+    if let Some((override_dir, source)) = crate::config::config_dir_override() {
+        config.add_log(
+            LogEntryLevel::Warn,
+            format!(
+                "Config moved to {}, but the next start will use {} ({})",
+                new_path,
+                override_dir.display(),
+                source
+            ),
+            Some("System".to_string()),
+        );
+        return Ok(IpcResult::ok(config.config_dir().to_string_lossy().into_owned()));
+    }
+
+    let pointer = ConfigManager::default_dir()
+        .and_then(|default_dir| ConfigManager::write_dir_pointer(&default_dir, &new_dir));
+    if let Err(e) = pointer {
+        config.add_log(
+            LogEntryLevel::Warn,
+            format!("Config moved, but the new location won't be remembered after restart: {}", e),
+            Some("System".to_string()),
+        );
+    } else {
+        config.add_log(
+            LogEntryLevel::Success,
+            format!("Config moved to {}", new_path),
+            Some("System".to_string()),
+        );
+    }
+
+    Ok(IpcResult::ok(config.config_dir().to_string_lossy().into_owned()))
+}
+
+/// Write bindings, settings and plugins to a JSON file at `path`
+#[tauri::command]
+pub async fn export_config(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<IpcResult<usize>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.export_config(std::path::Path::new(&path)) {
+        Ok(count) => {
             config.add_log(
                 LogEntryLevel::Success,
-                "Action executed successfully".to_string(),
-                Some("Test".to_string()),
+                format!("Exported {} binding(s) to {}", count, path),
+                Some("Config".to_string()),
             );
-            Ok(IpcResult::ok_empty())
+            Ok(IpcResult::ok(count))
+        }
+        Err(e) => Ok(IpcResult::err(format!("Failed to export config: {}", e)).with_code(e.code())),
+    }
+}
+
+/// Load a file written by `export_config`, either merging its bindings into
+/// the current ones or replacing the whole configuration
+#[tauri::command]
+pub async fn import_config(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    replace: bool,
+) -> Result<IpcResult<usize>, String> {
+    let (count, settings) = {
+        let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+        let count = match config.import_config(std::path::Path::new(&path), replace) {
+            Ok(count) => count,
+            Err(e) => return Ok(IpcResult::err(format!("Failed to import config: {}", e)).with_code(e.code())),
+        };
+
+        config.add_log(
+            LogEntryLevel::Success,
+            format!(
+                "Imported {} binding(s) from {} ({})",
+                count,
+                path,
+                if replace { "replaced" } else { "merged" }
+            ),
+            Some("Config".to_string()),
+        );
+        (count, config.get_settings())
+    };
+
+    // Not while holding the config lock: refresh_devices takes hid, then config
+    if replace {
+        let mut hid = state.hid_manager.lock().map_err(|e| e.to_string())?;
+        hid.set_use_serial_in_device_id(settings.use_serial_in_device_id);
+    }
+
+    // Device list status comes from HidManager's configured set
+    if let Err(e) = resync_configured_devices(&state) {
+        return Ok(IpcResult::err(e));
+    }
+
+    // Replacing brings in the file's HTTP API settings
+    if replace {
+        if let Err(e) = crate::http_api::sync_http_api(&app, &settings) {
+            let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+            config.add_log(LogEntryLevel::Warn, e, Some("System".to_string()));
+        }
+    }
+
+    Ok(IpcResult::ok(count))
+}
+
+// ============================================
+// Kiosk Lock Commands
+// ============================================
+
+#[tauri::command]
+pub async fn get_lock_status(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<ConfigLockStatus>, String> {
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(config.lock_status()))
+}
+
+#[tauri::command]
+pub async fn lock(
+    state: State<'_, AppState>,
+    passphrase: Option<String>,
+) -> Result<IpcResult<ConfigLockStatus>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.lock(passphrase.as_deref()) {
+        Ok(()) => {
+            config.add_log(
+                LogEntryLevel::Info,
+                "Configuration locked".to_string(),
+                Some("System".to_string()),
+            );
+            Ok(IpcResult::ok(config.lock_status()))
+        }
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+#[tauri::command]
+pub async fn unlock(
+    state: State<'_, AppState>,
+    passphrase: String,
+) -> Result<IpcResult<ConfigLockStatus>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.unlock(&passphrase) {
+        Ok(()) => {
+            config.add_log(
+                LogEntryLevel::Info,
+                "Configuration unlocked".to_string(),
+                Some("System".to_string()),
+            );
+            Ok(IpcResult::ok(config.lock_status()))
         }
         Err(e) => {
             config.add_log(
-                LogEntryLevel::Error,
-                format!("Action failed: {}", e),
-                Some("Test".to_string()),
+                LogEntryLevel::Warn,
+                format!("Unlock failed: {}", e),
+                Some("System".to_string()),
             );
-            Ok(IpcResult::err(e.to_string()))
+            Ok(IpcResult::from_error(&e))
         }
     }
 }
 
+#[tauri::command]
+pub async fn remove_lock(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<ConfigLockStatus>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.remove_lock() {
+        Ok(()) => {
+            config.add_log(
+                LogEntryLevel::Info,
+                "Configuration lock removed".to_string(),
+                Some("System".to_string()),
+            );
+            Ok(IpcResult::ok(config.lock_status()))
+        }
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+// ============================================
+// Action Commands
+// ============================================
+
+#[tauri::command]
+pub async fn test_action(
+    state: State<'_, AppState>,
+    action: ActionConfig,
+) -> Result<IpcResult<()>, String> {
+    let executor = ActionExecutor::new(state.config_manager.clone());
+    // Delays and shell captures wait, so keep them off the async runtime
+    let result = tauri::async_runtime::spawn_blocking(move || executor.test_action(&action))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(match result {
+        Ok(()) => IpcResult::ok_empty(),
+        Err(e) => IpcResult::err(e),
+    })
+}
+
+/// Run a saved binding's action through the same executor as the background
+/// listener (working directory, elevation, sequence steps), reporting each
+/// step's outcome
+#[tauri::command]
+pub async fn test_binding(
+    state: State<'_, AppState>,
+    binding_id: String,
+) -> Result<IpcResult<BindingTestResult>, String> {
+    let binding = {
+        let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+        let Some(binding) = config.get_binding_by_id(&binding_id) else {
+            return Ok(IpcResult::from_error(&ConfigError::BindingNotFound(binding_id)));
+        };
+        config.add_log(
+            LogEntryLevel::Info,
+            format!("Testing binding for {}", binding.device_id),
+            Some("Test".to_string()),
+        );
+        binding
+    };
+
+    let executor = ActionExecutor::new(state.config_manager.clone());
+    // Sequence delays sleep, so keep them off the async runtime
+    let result = tauri::async_runtime::spawn_blocking(move || executor.run_binding(&binding, "Test"))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(result))
+}
+
+/// Resolve a hotkey string to key names and virtual-key codes without sending it
+#[tauri::command]
+pub async fn preview_hotkey(hotkey: String) -> Result<IpcResult<HotkeyPreview>, String> {
+    match crate::hotkey::preview_hotkey(&hotkey) {
+        Ok(preview) => Ok(IpcResult::ok(preview)),
+        Err(e) => Ok(IpcResult::err(e)),
+    }
+}
+
+/// Advisory check of saved hotkey actions against shortcuts Windows reserves
+#[tauri::command]
+pub async fn check_hotkey_conflicts(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<Vec<HotkeyConflict>>, String> {
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(crate::hotkey::find_hotkey_conflicts(&config.get_all_bindings())))
+}
+
+/// Advisory warnings for one action (e.g. executable on a removable drive)
+#[tauri::command]
+pub async fn validate_action(action: ActionConfig) -> Result<IpcResult<Vec<String>>, String> {
+    Ok(IpcResult::ok(crate::validation::action_warnings(
+        &action,
+        crate::validation::system_drive_kind,
+    )))
+}
+
+/// Advisory warnings for every saved binding
+#[tauri::command]
+pub async fn validate_all_bindings(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<Vec<BindingWarning>>, String> {
+    let bindings = {
+        let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+        config.get_all_bindings()
+    };
+    Ok(IpcResult::ok(crate::validation::binding_warnings(
+        &bindings,
+        crate::validation::system_drive_kind,
+    )))
+}
+
+/// Every binding that could match `device_id`, in the order the listener
+/// tries them, with each condition resolved against the current state
+#[tauri::command]
+pub async fn explain_device(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<IpcResult<DeviceExplanation>, String> {
+    let (bindings, active_layers, needs_manufacturer) = {
+        let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+        (
+            config.get_all_bindings(),
+            config.current_layers(),
+            config.has_manufacturer_constraint(&device_id),
+        )
+    };
+
+    let context = crate::explain::ExplainContext {
+        manufacturer: if needs_manufacturer {
+            crate::hid::lookup_manufacturer(&device_id)
+        } else {
+            None
+        },
+        active_layers,
+        power_source: crate::power::current_power_source(),
+    };
+    Ok(IpcResult::ok(crate::explain::explain_device(&bindings, &device_id, &context)))
+}
+
+// ============================================
+// Plugin Commands
+// ============================================
+
+#[tauri::command]
+pub async fn get_plugins(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<Vec<PluginRegistration>>, String> {
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(config.get_plugins()))
+}
+
+#[tauri::command]
+pub async fn register_plugin(
+    state: State<'_, AppState>,
+    plugin: PluginRegistration,
+) -> Result<IpcResult<PluginRegistration>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.register_plugin(plugin) {
+        Ok(saved) => {
+            config.add_log(
+                LogEntryLevel::Success,
+                format!("Plugin registered: {}", saved.name),
+                Some("Config".to_string()),
+            );
+            Ok(IpcResult::ok(saved))
+        }
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+#[tauri::command]
+pub async fn unregister_plugin(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<IpcResult<()>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.unregister_plugin(&name) {
+        Ok(_) => Ok(IpcResult::ok_empty()),
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+// ============================================
+// Profile Commands
+// ============================================
+
+#[tauri::command]
+pub async fn list_profiles(state: State<'_, AppState>) -> Result<IpcResult<Vec<ProfileSummary>>, String> {
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(config.get_profiles()))
+}
+
+#[tauri::command]
+pub async fn create_profile(state: State<'_, AppState>, name: String) -> Result<IpcResult<Profile>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.create_profile(&name) {
+        Ok(profile) => {
+            config.add_log(
+                LogEntryLevel::Success,
+                format!("Profile created: {}", profile.name),
+                Some("Config".to_string()),
+            );
+            Ok(IpcResult::ok(profile))
+        }
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+/// Activate a profile and point HID monitoring at its devices
+#[tauri::command]
+pub async fn switch_profile(state: State<'_, AppState>, profile_id: String) -> Result<IpcResult<()>, String> {
+    {
+        let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+        if let Err(e) = config.switch_profile(&profile_id) {
+            return Ok(IpcResult::from_error(&e));
+        }
+        config.add_log(
+            LogEntryLevel::Info,
+            format!("Switched to profile {}", profile_id),
+            Some("Config".to_string()),
+        );
+    }
+
+    // Device list status comes from HidManager's configured set
+    match resync_configured_devices(&state) {
+        Ok(_) => Ok(IpcResult::ok_empty()),
+        Err(e) => Ok(IpcResult::err(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_profile(state: State<'_, AppState>, profile_id: String) -> Result<IpcResult<()>, String> {
+    {
+        let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+        if let Err(e) = config.delete_profile(&profile_id) {
+            return Ok(IpcResult::from_error(&e));
+        }
+        config.add_log(
+            LogEntryLevel::Info,
+            format!("Profile deleted: {}", profile_id),
+            Some("Config".to_string()),
+        );
+    }
+
+    // Deleting the active profile activates another one
+    match resync_configured_devices(&state) {
+        Ok(_) => Ok(IpcResult::ok_empty()),
+        Err(e) => Ok(IpcResult::err(e)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_profile_rules(state: State<'_, AppState>) -> Result<IpcResult<Vec<ProfileRule>>, String> {
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(config.profile_rules().to_vec()))
+}
+
+/// Replace the foreground-app rules (only applied on Windows)
+#[tauri::command]
+pub async fn save_profile_rules(
+    state: State<'_, AppState>,
+    rules: Vec<ProfileRule>,
+) -> Result<IpcResult<Vec<ProfileRule>>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.save_profile_rules(rules) {
+        Ok(saved) => {
+            config.add_log(
+                LogEntryLevel::Success,
+                format!("Saved {} profile rule(s)", saved.len()),
+                Some("Config".to_string()),
+            );
+            Ok(IpcResult::ok(saved))
+        }
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
 // ============================================
 // Log Commands
 // ============================================
@@ -436,9 +1405,10 @@ pub async fn test_action(
 pub async fn get_logs(
     state: State<'_, AppState>,
     limit: Option<usize>,
+    order: Option<LogOrder>,
 ) -> Result<IpcResult<Vec<LogEntry>>, String> {
     let config = state.config_manager.lock().map_err(|e| e.to_string())?;
-    Ok(IpcResult::ok(config.get_logs(limit)))
+    Ok(IpcResult::ok(config.get_logs(limit, order.unwrap_or_default())))
 }
 
 #[tauri::command]
@@ -447,7 +1417,40 @@ pub async fn clear_logs(state: State<'_, AppState>) -> Result<IpcResult<()>, Str
     
     match config.clear_logs() {
         Ok(_) => Ok(IpcResult::ok_empty()),
-        Err(e) => Ok(IpcResult::err(e.to_string())),
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+// ============================================
+// Usage Stats Commands
+// ============================================
+
+#[tauri::command]
+pub async fn get_usage_stats(state: State<'_, AppState>) -> Result<IpcResult<UsageStats>, String> {
+    let config = state.config_manager.lock().map_err(|e| e.to_string())?;
+    Ok(IpcResult::ok(config.get_usage_stats()))
+}
+
+#[tauri::command]
+pub async fn reset_usage_stats(state: State<'_, AppState>) -> Result<IpcResult<()>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.reset_usage_stats() {
+        Ok(_) => Ok(IpcResult::ok_empty()),
+        Err(e) => Ok(IpcResult::from_error(&e)),
+    }
+}
+
+/// Rewrite logs.json trimmed and consistently formatted, reporting bytes reclaimed
+#[tauri::command]
+pub async fn compact_logs(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<CompactLogsResult>, String> {
+    let mut config = state.config_manager.lock().map_err(|e| e.to_string())?;
+
+    match config.compact_logs() {
+        Ok(result) => Ok(IpcResult::ok(result)),
+        Err(e) => Ok(IpcResult::from_error(&e)),
     }
 }
 
@@ -489,7 +1492,84 @@ pub async fn open_file_dialog(
     }
 }
 
+/// Current master volume of the default playback device (0-100)
+#[tauri::command]
+pub async fn get_system_volume() -> Result<IpcResult<u8>, String> {
+    match crate::volume::get_volume_percent() {
+        Ok(percent) => Ok(IpcResult::ok(percent)),
+        Err(e) => Ok(IpcResult::err(e)),
+    }
+}
+
 #[tauri::command]
 pub async fn get_app_version() -> Result<IpcResult<String>, String> {
     Ok(IpcResult::ok(env!("CARGO_PKG_VERSION").to_string()))
 }
+
+/// Whether this process holds the single-instance lock, and whether another one does
+#[tauri::command]
+pub async fn get_instance_status(
+    state: State<'_, AppState>,
+) -> Result<IpcResult<InstanceStatus>, String> {
+    let mut lock = state.instance_lock.lock().map_err(|e| e.to_string())?;
+    let other_instance_running = lock.other_instance_running();
+
+    Ok(IpcResult::ok(InstanceStatus {
+        is_primary: lock.is_primary(),
+        other_instance_running,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ActionType;
+    use std::sync::{Arc, Mutex};
+
+    fn delay(ms: &str) -> ActionConfig {
+        ActionConfig {
+            r#type: ActionType::Delay,
+            executable_path: ms.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_delete_one_of_two_bindings_keeps_device_configured() {
+        // Needs the HID API; skip where it's unavailable
+        let Ok(hid) = crate::hid::HidManager::new() else {
+            return;
+        };
+        let dir = std::env::temp_dir().join(format!("usb-configurator-test-{}", uuid::Uuid::new_v4()));
+        let state = AppState {
+            config_manager: Arc::new(Mutex::new(ConfigManager::with_dir(dir.clone()).unwrap())),
+            hid_manager: Mutex::new(hid),
+            instance_lock: Mutex::new(crate::instance::InstanceLock::acquire(&dir.join("instance.lock")).unwrap()),
+            http_api: Mutex::new(None),
+        };
+
+        let ids: Vec<String> = [0x41u16, 0x42]
+            .into_iter()
+            .map(|code| {
+                let mut binding = DeviceBinding::new(
+                    "1234:5678".to_string(),
+                    "1234".to_string(),
+                    "5678".to_string(),
+                    TriggerType::SinglePress,
+                    delay("10"),
+                );
+                binding.button_code = Some(code);
+                state.config_manager.lock().unwrap().save_binding(binding).unwrap().id
+            })
+            .collect();
+        resync_configured_devices(&state).unwrap();
+
+        assert!(remove_binding(&state, &ids[0]).unwrap().success);
+        assert!(state.hid_manager.lock().unwrap().is_configured("1234:5678"));
+
+        assert!(remove_binding(&state, &ids[1]).unwrap().success);
+        assert!(!state.hid_manager.lock().unwrap().is_configured("1234:5678"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,7 +1,20 @@
-use crate::types::{AppSettings, DeviceBinding, LogEntry, LogEntryLevel};
+use crate::hotkey::parse_hotkey;
+use crate::kiosk::ConfigLock;
+use crate::layers::{self, LayerState};
+use crate::plugins::PluginRegistration;
+use crate::summary::serde_label;
+use crate::types::{
+    ActionConfig, ActionType, AppSettings, CodedError, CompactLogsResult, ConfigLockStatus, ConfigPaths, DeviceBinding,
+    DeviceBindingGroup, DeviceStatus, ErrorCode, HidDevice, LogEntry, LogEntryLevel, LogOrder, ProfileSummary,
+    TriggerType, UsageStats,
+};
 use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,27 +25,496 @@ pub enum ConfigError {
     IoError(#[from] std::io::Error),
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
+    #[error("Invalid binding: {0}")]
+    InvalidBinding(String),
+    #[error("Binding not found: {0}")]
+    BindingNotFound(String),
+    #[error("Conflicting binding: {0}")]
+    BindingConflict(String),
+    #[error("Configuration is locked")]
+    Locked,
+    #[error("Incorrect passphrase")]
+    IncorrectPassphrase,
+    #[error("Invalid import file: {0}")]
+    InvalidImport(String),
+    #[error("Cannot migrate config: {0}")]
+    MigrationFailed(String),
+    #[error("Invalid settings: {0}")]
+    InvalidSettings(String),
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+    #[error("Invalid profile: {0}")]
+    InvalidProfile(String),
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct ConfigData {
+impl CodedError for ConfigError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            ConfigError::NoConfigDir => ErrorCode::NoConfigDir,
+            ConfigError::IoError(_) => ErrorCode::Io,
+            ConfigError::JsonError(_) => ErrorCode::InvalidJson,
+            ConfigError::InvalidBinding(_) => ErrorCode::InvalidBinding,
+            ConfigError::BindingNotFound(_) => ErrorCode::BindingNotFound,
+            ConfigError::BindingConflict(_) => ErrorCode::BindingConflict,
+            ConfigError::Locked => ErrorCode::ConfigLocked,
+            ConfigError::IncorrectPassphrase => ErrorCode::IncorrectPassphrase,
+            ConfigError::InvalidImport(_) => ErrorCode::InvalidImport,
+            ConfigError::MigrationFailed(_) => ErrorCode::MigrationFailed,
+            ConfigError::InvalidSettings(_) => ErrorCode::InvalidSettings,
+            ConfigError::ProfileNotFound(_) => ErrorCode::ProfileNotFound,
+            ConfigError::InvalidProfile(_) => ErrorCode::InvalidProfile,
+        }
+    }
+}
+
+/// What a device input looked like, for picking a binding
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BindingQuery<'a> {
+    pub device_id: &'a str,
+    pub manufacturer: Option<&'a str>,
+    pub consumer_usage: Option<&'a str>,
+    pub button_code: Option<u16>,
+    /// Prefer a binding for this trigger when a device has several
+    pub trigger_type: Option<&'a TriggerType>,
+    /// Active layers; `None` means none are. Bindings for an inactive layer
+    /// don't match.
+    pub layers: Option<&'a LayerState>,
+}
+
+impl<'a> BindingQuery<'a> {
+    pub fn device(device_id: &'a str) -> Self {
+        Self {
+            device_id,
+            ..Default::default()
+        }
+    }
+}
+
+/// A named set of bindings. Only the active profile's bindings are matched
+/// against device input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
     pub bindings: Vec<DeviceBinding>,
+}
+
+/// Activates a profile while a process is in the foreground (Windows)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileRule {
+    pub process_name: String, // Executable name, e.g. "Photoshop.exe"
+    pub profile_id: String,
+}
+
+/// ID of the profile that pre-profile bindings are migrated into
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+impl Profile {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            bindings: Vec::new(),
+        }
+    }
+
+    fn default_profile() -> Self {
+        Self {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: "Default".to_string(),
+            bindings: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfigData {
+    /// Shape of this file; see `CONFIG_VERSION` and `migrate`
+    #[serde(default)]
+    pub version: u32,
+    pub profiles: Vec<Profile>,
+    pub active_profile_id: String,
+    /// Foreground-app rules; the first one matching wins
+    #[serde(default)]
+    pub profile_rules: Vec<ProfileRule>,
     pub settings: AppSettings,
+    #[serde(default)]
+    pub active_layers: Vec<String>,
+    #[serde(default)]
+    pub plugins: Vec<PluginRegistration>,
+    #[serde(default)]
+    pub config_lock: ConfigLock,
+    /// Every VID:PID a device scan has ever found, to tell unplugged devices
+    /// from ones that are gone for good
+    #[serde(default)]
+    pub known_devices: Vec<String>,
+}
+
+impl Default for ConfigData {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            profiles: vec![Profile::default_profile()],
+            active_profile_id: DEFAULT_PROFILE_ID.to_string(),
+            profile_rules: Vec::new(),
+            settings: AppSettings::default(),
+            active_layers: Vec::new(),
+            plugins: Vec::new(),
+            config_lock: ConfigLock::default(),
+            known_devices: Vec::new(),
+        }
+    }
+}
+
+impl ConfigData {
+    fn active_profile_index(&self) -> Option<usize> {
+        self.profiles.iter().position(|p| p.id == self.active_profile_id)
+    }
+
+    /// The active profile's bindings
+    pub fn bindings(&self) -> &[DeviceBinding] {
+        self.active_profile_index()
+            .map(|i| self.profiles[i].bindings.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn bindings_mut(&mut self) -> &mut Vec<DeviceBinding> {
+        self.ensure_active_profile();
+        let index = self.active_profile_index().unwrap_or_default();
+        &mut self.profiles[index].bindings
+    }
+
+    /// Keep at least one profile and point `active_profile_id` at one that
+    /// exists, so a hand-edited file can't leave the listener with nothing.
+    /// A missing active profile falls back to Default, or to the first
+    /// profile if Default was edited out too.
+    fn ensure_active_profile(&mut self) {
+        if self.profiles.is_empty() {
+            self.profiles.push(Profile::default_profile());
+        }
+        if self.active_profile_index().is_none() {
+            let fallback = self
+                .profiles
+                .iter()
+                .find(|p| p.id == DEFAULT_PROFILE_ID)
+                .unwrap_or(&self.profiles[0]);
+            self.active_profile_id = fallback.id.clone();
+        }
+    }
+}
+
+/// Current `ConfigData::version`. Files from before versioning count as 0.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Upgrade steps, indexed by the version they upgrade from. Add one (and bump
+/// `CONFIG_VERSION`) whenever a change to the saved shape can't be covered
+/// by a serde default.
+const MIGRATIONS: &[fn(&mut serde_json::Value)] = &[migrate_v0_to_v1, migrate_v1_to_v2];
+
+/// v0 only lacked the version field; everything added since has a default
+fn migrate_v0_to_v1(_data: &mut serde_json::Value) {}
+
+/// v2 moved the top-level `bindings` into a "Default" profile
+fn migrate_v1_to_v2(data: &mut serde_json::Value) {
+    let Some(object) = data.as_object_mut() else {
+        return;
+    };
+    let bindings = object.remove("bindings").unwrap_or_else(|| serde_json::json!([]));
+    if object.contains_key("profiles") {
+        return;
+    }
+
+    object.insert(
+        "profiles".to_string(),
+        serde_json::json!([{ "id": DEFAULT_PROFILE_ID, "name": "Default", "bindings": bindings }]),
+    );
+    object.insert("active_profile_id".to_string(), DEFAULT_PROFILE_ID.into());
+}
+
+/// Upgrade a config.json document of any older version to the current shape
+pub fn migrate(mut data: serde_json::Value) -> Result<ConfigData, ConfigError> {
+    let from = data.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if from > CONFIG_VERSION as u64 {
+        return Err(ConfigError::MigrationFailed(format!(
+            "version {} is newer than this app supports ({})",
+            from, CONFIG_VERSION
+        )));
+    }
+
+    for step in &MIGRATIONS[from as usize..] {
+        step(&mut data);
+    }
+
+    let object = data
+        .as_object_mut()
+        .ok_or_else(|| ConfigError::MigrationFailed("not a JSON object".to_string()))?;
+    object.insert("version".to_string(), CONFIG_VERSION.into());
+
+    Ok(serde_json::from_value(data)?)
+}
+
+/// Parse a config.json the user or another instance wrote while we're
+/// running. Unlike `load_config_data` this never backs up, renames or resets
+/// anything: a file that doesn't parse is an error and the current config stays.
+fn parse_config_data(content: &str) -> Result<ConfigData, ConfigError> {
+    let value: serde_json::Value = serde_json::from_str(content)?;
+    match value.get("version").and_then(|v| v.as_u64()) {
+        Some(version) if version >= CONFIG_VERSION as u64 => Ok(serde_json::from_value(value)?),
+        _ => migrate(value),
+    }
+}
+
+/// Fingerprint of config.json contents, to tell our own writes from changes
+/// made by someone else
+fn content_hash(content: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Write `content` to a temp file next to `path`, then rename it into place.
+/// The rename is atomic on the same filesystem, so a crash mid-write leaves
+/// either the old file or the new one, never a truncated mix.
+fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+
+    let result = (|| {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// One activity.log line: `<timestamp> <LEVEL> [<source>] <message>`
+fn format_activity_line(entry: &LogEntry) -> String {
+    format!(
+        "{} {} [{}] {}\n",
+        entry.timestamp,
+        serde_label(&entry.level).to_uppercase(),
+        entry.source.as_deref().unwrap_or("-"),
+        entry.message.replace(['\r', '\n'], " ")
+    )
+}
+
+/// Append a line to activity.log, first rotating it to activity.log.1
+/// (replacing any older one) once it has reached `max_bytes`
+fn append_activity_line(path: &Path, line: &str, max_bytes: u64) -> std::io::Result<()> {
+    if fs::metadata(path).is_ok_and(|m| m.len() >= max_bytes) {
+        let mut rotated = path.as_os_str().to_os_string();
+        rotated.push(".1");
+        fs::rename(path, rotated)?;
+    }
+
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())
+}
+
+/// Parse config.json, migrating it if it's from an older version or no longer
+/// deserializes. The original is copied to config.json.bak before migrating;
+/// a file that can't be read at all (e.g. truncated by a crash mid-write) is
+/// renamed to config.corrupt.<timestamp>.json so the next save can't
+/// overwrite it.
+fn load_config_data(config_path: &Path, content: &str) -> Result<ConfigData, ConfigError> {
+    if let Ok(data) = serde_json::from_str::<ConfigData>(content) {
+        if data.version > CONFIG_VERSION {
+            log::warn!(
+                "Config version {} is newer than this app supports ({})",
+                data.version, CONFIG_VERSION
+            );
+        }
+        if data.version >= CONFIG_VERSION {
+            return Ok(data);
+        }
+    }
+
+    let backup = config_path.with_extension("json.bak");
+    if let Err(e) = fs::write(&backup, content) {
+        log::warn!("Failed to back up {}: {}", config_path.display(), e);
+    }
+
+    match serde_json::from_str(content).map_err(ConfigError::from).and_then(migrate) {
+        Ok(data) => {
+            log::info!("Migrated config to version {} (original kept in {})", CONFIG_VERSION, backup.display());
+            Ok(data)
+        }
+        Err(e) => {
+            let corrupt = config_path.with_file_name(format!(
+                "config.corrupt.{}.json",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S")
+            ));
+            fs::rename(config_path, &corrupt)?;
+            log::warn!(
+                "Failed to load config ({}); starting with defaults. The unreadable file was moved to {}",
+                e,
+                corrupt.display()
+            );
+            Ok(ConfigData::default())
+        }
+    }
 }
 
 pub struct ConfigManager {
+    config_dir: PathBuf,
     config_path: PathBuf,
     logs_path: PathBuf,
     data: ConfigData,
     logs: Vec<LogEntry>,
+    usage_stats: UsageStats,
+    current_layers: Vec<String>, // The listener's active layers, persisted or not
+    unlocked: bool, // Kiosk lock lifted for this session only
+    synced_hash: Option<u64>, // config.json as last read or written here; see `reload_if_changed`
+    unflushed_logs: usize, // Entries added since logs.json was last written
+    last_log_flush: Instant,
+}
+
+/// `add_log` writes logs.json at most this often...
+pub const LOG_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// ...unless this many entries are waiting
+const LOG_FLUSH_BATCH: usize = 50;
+
+/// Version of the `export_config` file format. Imports of any other version
+/// are rejected; bump this when `ConfigExport` changes incompatibly.
+pub const CONFIG_SCHEMA_VERSION: u32 = 1;
+
+/// A configuration backup written by `export_config`. The kiosk lock and
+/// device history stay machine-local and aren't included.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigExport {
+    pub schema_version: u32,
+    pub exported_at: String,
+    pub app_version: String,
+    pub bindings: Vec<DeviceBinding>,
+    pub settings: AppSettings,
+    #[serde(default)]
+    pub plugins: Vec<PluginRegistration>,
+}
+
+/// File in the default config directory that points at a relocated one
+const CONFIG_DIR_POINTER: &str = "config-dir.txt";
+
+/// Opt-in usage counters, kept apart from config so resetting never touches bindings
+const USAGE_STATS_FILE: &str = "usage-stats.json";
+
+/// Accepted `multi_press_window_ms`: shorter can't be hit by hand, longer
+/// makes every single press feel laggy to the next one
+const MULTI_PRESS_WINDOW_RANGE_MS: std::ops::RangeInclusive<u64> = 100..=2000;
+
+/// Append-only, human-readable copy of the log for `tail -f`
+const ACTIVITY_LOG_FILE: &str = "activity.log";
+
+/// Environment variable that puts config and logs in another directory,
+/// e.g. next to a portable install
+pub const CONFIG_DIR_ENV: &str = "BUTTONREMAP_CONFIG_DIR";
+
+/// Set from `--config-dir`; wins over `CONFIG_DIR_ENV`
+static CONFIG_DIR_ARG: OnceLock<PathBuf> = OnceLock::new();
+
+/// What picked the config directory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigDirSource {
+    Argument,
+    Environment,
+    Pointer,
+    Default,
+}
+
+impl std::fmt::Display for ConfigDirSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ConfigDirSource::Argument => "from --config-dir",
+            ConfigDirSource::Environment => "from BUTTONREMAP_CONFIG_DIR",
+            ConfigDirSource::Pointer => "moved with move_config_dir",
+            ConfigDirSource::Default => "default",
+        })
+    }
+}
+
+/// Use `dir` for config and logs in this process. Call before the first
+/// `ConfigManager::new`; later calls are ignored.
+pub fn set_config_dir_arg(dir: PathBuf) {
+    let _ = CONFIG_DIR_ARG.set(dir);
+}
+
+/// The `--config-dir` or `BUTTONREMAP_CONFIG_DIR` directory, if either is set
+pub fn config_dir_override() -> Option<(PathBuf, ConfigDirSource)> {
+    let (dir, source) = choose_config_dir_override(CONFIG_DIR_ARG.get().cloned(), std::env::var_os(CONFIG_DIR_ENV))?;
+    // Relative to where we were started, and stays put if the app changes directory later
+    Some((std::path::absolute(&dir).unwrap_or(dir), source))
+}
+
+/// The argument wins over the environment variable; an empty variable counts as unset
+fn choose_config_dir_override(arg: Option<PathBuf>, env: Option<OsString>) -> Option<(PathBuf, ConfigDirSource)> {
+    if let Some(dir) = arg {
+        return Some((dir, ConfigDirSource::Argument));
+    }
+    env.filter(|value| !value.is_empty())
+        .map(|value| (PathBuf::from(value), ConfigDirSource::Environment))
 }
 
 impl ConfigManager {
     pub fn new() -> Result<Self, ConfigError> {
-        let config_dir = dirs::config_dir()
+        let (dir, source) = Self::resolve_dir()?;
+        log::info!("Config directory: {} ({})", dir.display(), source);
+        Self::with_dir(dir)
+    }
+
+    /// The directory `new` loads from: `--config-dir`, then
+    /// `BUTTONREMAP_CONFIG_DIR`, then wherever `move_config_dir` moved it,
+    /// then `default_dir`. `with_dir` creates it if it's missing.
+    pub fn resolve_dir() -> Result<(PathBuf, ConfigDirSource), ConfigError> {
+        if let Some(chosen) = config_dir_override() {
+            return Ok(chosen);
+        }
+        let default_dir = Self::default_dir()?;
+
+        // Follow a pointer left by move_config_dir
+        let pointed = fs::read_to_string(default_dir.join(CONFIG_DIR_POINTER))
+            .ok()
+            .map(|p| PathBuf::from(p.trim()))
+            .filter(|p| p.is_dir());
+
+        Ok(match pointed {
+            Some(dir) => (dir, ConfigDirSource::Pointer),
+            None => (default_dir, ConfigDirSource::Default),
+        })
+    }
+
+    pub fn default_dir() -> Result<PathBuf, ConfigError> {
+        Ok(dirs::config_dir()
             .ok_or(ConfigError::NoConfigDir)?
-            .join("usb-configurator");
-        
+            .join("usb-configurator"))
+    }
+
+    /// Record where config lives so the next `new()` finds it. Moving back to
+    /// the default directory removes the pointer.
+    pub fn write_dir_pointer(default_dir: &Path, config_dir: &Path) -> Result<(), ConfigError> {
+        let pointer = default_dir.join(CONFIG_DIR_POINTER);
+        if default_dir == config_dir {
+            if pointer.exists() {
+                fs::remove_file(pointer)?;
+            }
+        } else {
+            fs::create_dir_all(default_dir)?;
+            fs::write(pointer, config_dir.to_string_lossy().as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Load (or create) config and logs from a specific directory
+    pub fn with_dir(config_dir: PathBuf) -> Result<Self, ConfigError> {
         // Ensure config directory exists
         fs::create_dir_all(&config_dir)?;
         
@@ -40,12 +522,15 @@ impl ConfigManager {
         let logs_path = config_dir.join("logs.json");
         
         // Load existing config or create default
-        let data = if config_path.exists() {
+        let mut synced_hash = None;
+        let mut data = if config_path.exists() {
             let content = fs::read_to_string(&config_path)?;
-            serde_json::from_str(&content).unwrap_or_default()
+            synced_hash = Some(content_hash(&content));
+            load_config_data(&config_path, &content)?
         } else {
             ConfigData::default()
         };
+        data.ensure_active_profile();
         
         // Load logs or start fresh
         let logs = if logs_path.exists() {
@@ -54,116 +539,2621 @@ impl ConfigManager {
         } else {
             Vec::new()
         };
+
+        let current_layers = if data.settings.persist_layers {
+            data.active_layers.clone()
+        } else {
+            Vec::new()
+        };
+
+        let usage_stats = fs::read_to_string(config_dir.join(USAGE_STATS_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
         
         Ok(Self {
+            config_dir,
             config_path,
             logs_path,
             data,
             logs,
+            usage_stats,
+            current_layers,
+            unlocked: false,
+            synced_hash,
+            unflushed_logs: 0,
+            last_log_flush: Instant::now(),
         })
     }
 
-    fn save_config(&self) -> Result<(), ConfigError> {
+    pub fn config_dir(&self) -> &Path {
+        &self.config_dir
+    }
+
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+
+    /// Reload config.json if it no longer holds what this manager last read
+    /// or wrote, e.g. after a hand edit or an import by another instance.
+    /// Returns whether anything was reloaded. A file that doesn't parse is an
+    /// error and leaves the current config in place; it isn't retried until
+    /// it changes again.
+    pub fn reload_if_changed(&mut self) -> Result<bool, ConfigError> {
+        let content = match fs::read_to_string(&self.config_path) {
+            Ok(content) => content,
+            // Deleted or mid-rename; the next save writes it again
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+        let hash = content_hash(&content);
+        if self.synced_hash == Some(hash) {
+            return Ok(false);
+        }
+        self.synced_hash = Some(hash);
+
+        let mut data = parse_config_data(&content)?;
+        data.ensure_active_profile();
+        self.data = data;
+        log::info!("Reloaded {} after it changed on disk", self.config_path.display());
+        Ok(true)
+    }
+
+    /// The config directory and the files in it users may want to open
+    pub fn paths(&self) -> ConfigPaths {
+        let display = |path: &Path| path.to_string_lossy().into_owned();
+        ConfigPaths {
+            config_dir: display(&self.config_dir),
+            config_file: display(&self.config_path),
+            logs_file: display(&self.logs_path),
+            activity_log: display(&self.config_dir.join(ACTIVITY_LOG_FILE)),
+        }
+    }
+
+    /// Move config and logs to `new_dir` and use it from now on. The new
+    /// directory is checked for writability first; if anything fails, files
+    /// written there are removed and the old location stays in use.
+    pub fn move_to(&mut self, new_dir: PathBuf, remove_old: bool) -> Result<(), ConfigError> {
+        self.ensure_unlocked()?;
+        if new_dir == self.config_dir {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&new_dir)?;
+        let probe = new_dir.join(".write-test");
+        fs::write(&probe, b"ok")?;
+        fs::remove_file(&probe)?;
+
+        let old = (
+            self.config_dir.clone(),
+            self.config_path.clone(),
+            self.logs_path.clone(),
+        );
+        self.config_dir = new_dir.clone();
+        self.config_path = new_dir.join("config.json");
+        self.logs_path = new_dir.join("logs.json");
+
+        // Write the in-memory state, which is at least as new as the old files
+        if let Err(e) = self
+            .save_config()
+            .and_then(|_| self.save_logs())
+            .and_then(|_| {
+                // Don't create a stats file for users who never opted in
+                if self.usage_stats == UsageStats::default() {
+                    Ok(())
+                } else {
+                    self.save_usage_stats()
+                }
+            })
+        {
+            let _ = fs::remove_file(&self.config_path);
+            let _ = fs::remove_file(&self.logs_path);
+            let _ = fs::remove_file(new_dir.join(USAGE_STATS_FILE));
+            (self.config_dir, self.config_path, self.logs_path) = old;
+            return Err(e);
+        }
+
+        if remove_old {
+            let old_stats = old.0.join(USAGE_STATS_FILE);
+            for path in [&old.1, &old.2, &old_stats] {
+                if !path.exists() {
+                    continue;
+                }
+                if let Err(e) = fs::remove_file(path) {
+                    log::warn!("Failed to remove old {}: {}", path.display(), e);
+                }
+            }
+        }
+
+        log::info!("Config moved from {} to {}", old.0.display(), new_dir.display());
+        Ok(())
+    }
+
+    fn save_config(&mut self) -> Result<(), ConfigError> {
         let content = serde_json::to_string_pretty(&self.data)?;
-        fs::write(&self.config_path, content)?;
+        write_atomic(&self.config_path, content.as_bytes())?;
+        self.synced_hash = Some(content_hash(&content));
         Ok(())
     }
 
     fn save_logs(&self) -> Result<(), ConfigError> {
         let content = serde_json::to_string_pretty(&self.logs)?;
-        fs::write(&self.logs_path, content)?;
+        write_atomic(&self.logs_path, content.as_bytes())?;
+        Ok(())
+    }
+
+    fn save_usage_stats(&self) -> Result<(), ConfigError> {
+        let content = serde_json::to_string_pretty(&self.usage_stats)?;
+        write_atomic(&self.config_dir.join(USAGE_STATS_FILE), content.as_bytes())?;
         Ok(())
     }
 
     // --- Bindings ---
 
     pub fn get_all_bindings(&self) -> Vec<DeviceBinding> {
-        self.data.bindings.clone()
+        self.data.bindings().to_vec()
     }
 
+    /// Binding for a device ID. An interface ID falls back to a binding for
+    /// its device, and a `VID:PID:SERIAL` ID to one saved against the plain
+    /// `VID:PID`.
     pub fn get_binding(&self, device_id: &str) -> Option<DeviceBinding> {
-        self.data.bindings
-            .iter()
-            .find(|b| b.device_id == device_id)
+        [Some(device_id), parent_device_id(device_id), shared_device_id(device_id)]
+            .into_iter()
+            .flatten()
+            .find_map(|id| self.data.bindings().iter().find(|b| b.device_id == id))
             .cloned()
     }
 
+    /// Binding for an input, honouring manufacturer and consumer-usage
+    /// constraints (see `select_binding` for precedence)
+    pub fn find_binding(&self, query: &BindingQuery) -> Option<DeviceBinding> {
+        select_binding(self.data.bindings(), query).cloned()
+    }
+
+    /// Whether matching this device needs its manufacturer string looked up
+    pub fn has_manufacturer_constraint(&self, device_id: &str) -> bool {
+        self.data.bindings()
+            .iter()
+            .any(|b| binding_applies(&b.device_id, device_id) && b.manufacturer.is_some())
+    }
+
     pub fn save_binding(&mut self, binding: DeviceBinding) -> Result<DeviceBinding, ConfigError> {
-        // Update existing or add new
-        if let Some(pos) = self.data.bindings.iter().position(|b| b.device_id == binding.device_id) {
-            self.data.bindings[pos] = binding.clone();
-        } else {
-            self.data.bindings.push(binding.clone());
+        self.ensure_unlocked()?;
+        validate_binding(&binding)?;
+
+        // Update the binding with this ID, else the one with the same
+        // identity, else add it. Bindings for one device coexist when their
+        // triggers, buttons, manufacturers, usages or layers differ.
+        let bindings = self.data.bindings();
+        let pos = bindings
+            .iter()
+            .position(|b| b.id == binding.id)
+            .or_else(|| bindings.iter().position(|b| same_identity(b, &binding)));
+        check_conflict(bindings, &binding, pos)?;
+
+        match pos {
+            Some(pos) => self.data.bindings_mut()[pos] = binding.clone(),
+            None => self.data.bindings_mut().push(binding.clone()),
         }
         
         self.save_config()?;
         Ok(binding)
     }
 
+    /// Change only a binding's trigger type, leaving the rest untouched
+    pub fn set_binding_trigger(
+        &mut self,
+        binding_id: &str,
+        trigger_type: TriggerType,
+    ) -> Result<DeviceBinding, ConfigError> {
+        self.ensure_unlocked()?;
+        let pos = self.binding_position(binding_id)?;
+        let mut candidate = self.data.bindings()[pos].clone();
+        candidate.trigger_type = trigger_type.clone();
+        check_conflict(self.data.bindings(), &candidate, Some(pos))?;
+
+        let binding = &mut self.data.bindings_mut()[pos];
+        binding.trigger_type = trigger_type;
+        binding.updated_at = chrono::Utc::now().to_rfc3339();
+        let updated = binding.clone();
+
+        self.save_config()?;
+        Ok(updated)
+    }
+
+    /// Switch one binding on or off without resending the whole binding
+    pub fn set_binding_enabled(&mut self, binding_id: &str, enabled: bool) -> Result<DeviceBinding, ConfigError> {
+        self.ensure_unlocked()?;
+        let pos = self.binding_position(binding_id)?;
+        let mut candidate = self.data.bindings()[pos].clone();
+        candidate.enabled = enabled;
+        check_conflict(self.data.bindings(), &candidate, Some(pos))?;
+
+        let binding = &mut self.data.bindings_mut()[pos];
+        binding.enabled = enabled;
+        binding.updated_at = chrono::Utc::now().to_rfc3339();
+        let updated = binding.clone();
+
+        self.save_config()?;
+        Ok(updated)
+    }
+
+    /// Replace `from_prefix` with `to_prefix` in every action's executable
+    /// path and working directory (including power-condition alternates), in
+    /// every profile. Saves once; with `dry_run` nothing changes. Returns bindings affected.
+    pub fn rewrite_action_paths(
+        &mut self,
+        from_prefix: &str,
+        to_prefix: &str,
+        dry_run: bool,
+    ) -> Result<usize, ConfigError> {
+        if from_prefix.trim().is_empty() {
+            return Err(ConfigError::InvalidBinding("path prefix is empty".to_string()));
+        }
+        if !dry_run {
+            self.ensure_unlocked()?;
+        }
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut changed = 0;
+        for binding in self.data.profiles.iter_mut().flat_map(|p| p.bindings.iter_mut()) {
+            let mut actions = vec![&mut binding.action];
+            if let Some(alternate) = binding.power_condition.as_mut().and_then(|c| c.otherwise.as_mut()) {
+                actions.push(alternate);
+            }
+
+            let mut binding_changed = false;
+            for action in actions {
+                binding_changed |= rewrite_paths_in_action(action, from_prefix, to_prefix, dry_run);
+            }
+
+            if binding_changed {
+                if !dry_run {
+                    binding.updated_at = now.clone();
+                }
+                changed += 1;
+            }
+        }
+
+        if changed > 0 && !dry_run {
+            self.save_config()?;
+        }
+        Ok(changed)
+    }
+
     pub fn delete_binding(&mut self, binding_id: &str) -> Result<(), ConfigError> {
-        self.data.bindings.retain(|b| b.id != binding_id);
+        self.ensure_unlocked()?;
+        self.data.bindings_mut().retain(|b| b.id != binding_id);
         self.save_config()?;
         Ok(())
     }
 
+    fn binding_position(&self, binding_id: &str) -> Result<usize, ConfigError> {
+        self.data
+            .bindings()
+            .iter()
+            .position(|b| b.id == binding_id)
+            .ok_or_else(|| ConfigError::BindingNotFound(binding_id.to_string()))
+    }
+
     pub fn get_binding_by_id(&self, binding_id: &str) -> Option<DeviceBinding> {
-        self.data.bindings
+        self.data.bindings()
             .iter()
             .find(|b| b.id == binding_id)
             .cloned()
     }
 
+    // --- Profiles ---
+
+    pub fn get_profiles(&self) -> Vec<ProfileSummary> {
+        self.data
+            .profiles
+            .iter()
+            .map(|p| ProfileSummary {
+                id: p.id.clone(),
+                name: p.name.clone(),
+                binding_count: p.bindings.len(),
+                active: p.id == self.data.active_profile_id,
+            })
+            .collect()
+    }
+
+    pub fn active_profile_id(&self) -> &str {
+        &self.data.active_profile_id
+    }
+
+    /// Add an empty profile. Names must be unique, ignoring case.
+    pub fn create_profile(&mut self, name: &str) -> Result<Profile, ConfigError> {
+        self.ensure_unlocked()?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ConfigError::InvalidProfile("name is empty".to_string()));
+        }
+        if self.data.profiles.iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+            return Err(ConfigError::InvalidProfile(format!("a profile named '{}' already exists", name)));
+        }
+
+        let profile = Profile::new(name.to_string());
+        self.data.profiles.push(profile.clone());
+        self.save_config()?;
+        Ok(profile)
+    }
+
+    /// Make a profile active; the listener matches its bindings from the next input on
+    pub fn switch_profile(&mut self, profile_id: &str) -> Result<(), ConfigError> {
+        self.ensure_unlocked()?;
+        self.activate_profile(profile_id)
+    }
+
+    /// `switch_profile` without the kiosk check, for switches made by
+    /// foreground-app rules (which could only be set up while unlocked)
+    pub fn activate_profile(&mut self, profile_id: &str) -> Result<(), ConfigError> {
+        if !self.data.profiles.iter().any(|p| p.id == profile_id) {
+            return Err(ConfigError::ProfileNotFound(profile_id.to_string()));
+        }
+        self.data.active_profile_id = profile_id.to_string();
+        self.save_config()
+    }
+
+    /// Delete a profile and its bindings. The last profile can't be deleted;
+    /// deleting the active one activates the first remaining profile.
+    pub fn delete_profile(&mut self, profile_id: &str) -> Result<(), ConfigError> {
+        self.ensure_unlocked()?;
+        if !self.data.profiles.iter().any(|p| p.id == profile_id) {
+            return Err(ConfigError::ProfileNotFound(profile_id.to_string()));
+        }
+        if self.data.profiles.len() == 1 {
+            return Err(ConfigError::InvalidProfile("the last profile can't be deleted".to_string()));
+        }
+
+        self.data.profiles.retain(|p| p.id != profile_id);
+        self.data.profile_rules.retain(|r| r.profile_id != profile_id);
+        self.data.ensure_active_profile();
+        self.save_config()
+    }
+
+    pub fn profile_rules(&self) -> &[ProfileRule] {
+        &self.data.profile_rules
+    }
+
+    /// Replace the foreground-app rules. Every rule needs a process name and
+    /// an existing profile.
+    pub fn save_profile_rules(&mut self, rules: Vec<ProfileRule>) -> Result<Vec<ProfileRule>, ConfigError> {
+        self.ensure_unlocked()?;
+        let mut saved = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let process_name = rule.process_name.trim().to_string();
+            if process_name.is_empty() {
+                return Err(ConfigError::InvalidProfile("rule has no process name".to_string()));
+            }
+            if !self.data.profiles.iter().any(|p| p.id == rule.profile_id) {
+                return Err(ConfigError::ProfileNotFound(rule.profile_id));
+            }
+            saved.push(ProfileRule { process_name, profile_id: rule.profile_id });
+        }
+
+        self.data.profile_rules = saved.clone();
+        self.save_config()?;
+        Ok(saved)
+    }
+
     // --- Settings ---
 
     pub fn get_settings(&self) -> AppSettings {
         self.data.settings.clone()
     }
 
-    pub fn save_settings(&mut self, settings: AppSettings) -> Result<AppSettings, ConfigError> {
+    pub fn save_settings(&mut self, mut settings: AppSettings) -> Result<AppSettings, ConfigError> {
+        self.ensure_unlocked()?;
+        if !MULTI_PRESS_WINDOW_RANGE_MS.contains(&settings.multi_press_window_ms) {
+            return Err(ConfigError::InvalidSettings(format!(
+                "multi-press window must be {}-{}ms, got {}ms",
+                MULTI_PRESS_WINDOW_RANGE_MS.start(),
+                MULTI_PRESS_WINDOW_RANGE_MS.end(),
+                settings.multi_press_window_ms
+            )));
+        }
+        if settings.enable_http_api && settings.http_api_port == 0 {
+            return Err(ConfigError::InvalidSettings("HTTP API port can't be 0".to_string()));
+        }
+        // An enabled HTTP API is never left open without a token
+        if settings.enable_http_api && settings.http_api_token.trim().is_empty() {
+            settings.http_api_token = uuid::Uuid::new_v4().simple().to_string();
+        }
         self.data.settings = settings.clone();
         self.save_config()?;
         Ok(settings)
     }
 
-    // --- Logs ---
+    pub fn is_remapping_enabled(&self) -> bool {
+        self.data.settings.remapping_enabled
+    }
 
-    pub fn get_logs(&self, limit: Option<usize>) -> Vec<LogEntry> {
-        let max_entries = self.data.settings.max_log_entries as usize;
-        let effective_limit = limit.unwrap_or(max_entries).min(max_entries);
-        
-        self.logs
-            .iter()
-            .take(effective_limit)
-            .cloned()
-            .collect()
+    /// Pause or resume all bindings, persisting the choice
+    pub fn set_remapping_enabled(&mut self, enabled: bool) -> Result<(), ConfigError> {
+        let mut settings = self.data.settings.clone();
+        settings.remapping_enabled = enabled;
+        self.save_settings(settings)?;
+        Ok(())
     }
 
-    pub fn add_log(&mut self, level: LogEntryLevel, message: String, source: Option<String>) {
-        let entry = LogEntry::new(level, message, source);
-        self.logs.insert(0, entry);
-        
-        // Trim to max entries
-        let max = self.data.settings.max_log_entries as usize;
-        if self.logs.len() > max {
-            self.logs.truncate(max);
+    /// Whether raw reports from this device should be logged
+    pub fn is_verbose_device(&self, device_id: &str) -> bool {
+        let id = base_device_id(device_id);
+        self.data.settings.verbose_devices.iter().any(|d| base_device_id(d) == id)
+    }
+
+    pub fn set_device_verbose(&mut self, device_id: &str, verbose: bool) -> Result<(), ConfigError> {
+        self.ensure_unlocked()?;
+        let id = base_device_id(device_id);
+        self.data.settings.verbose_devices.retain(|d| base_device_id(d) != id);
+        if verbose {
+            self.data.settings.verbose_devices.push(id);
         }
-        
-        // Save logs (ignore errors for performance)
-        let _ = self.save_logs();
+        self.save_config()
     }
 
-    pub fn clear_logs(&mut self) -> Result<(), ConfigError> {
-        self.logs.clear();
-        self.save_logs()?;
+    // --- Kiosk lock ---
+
+    pub fn lock_status(&self) -> ConfigLockStatus {
+        ConfigLockStatus {
+            locked: self.data.config_lock.locked,
+            unlocked: self.unlocked,
+            has_passphrase: self.data.config_lock.has_passphrase(),
+        }
+    }
+
+    fn ensure_unlocked(&self) -> Result<(), ConfigError> {
+        if self.data.config_lock.locked && !self.unlocked {
+            return Err(ConfigError::Locked);
+        }
         Ok(())
     }
 
-    // --- Device state tracking ---
+    /// Lock the configuration (or re-lock it after `unlock`), replacing the
+    /// passphrase. Changing an existing lock requires it to be unlocked.
+    pub fn lock(&mut self, passphrase: Option<&str>) -> Result<(), ConfigError> {
+        self.ensure_unlocked()?;
+        self.data.config_lock = ConfigLock::new(passphrase);
+        self.unlocked = false;
+        self.save_config()
+    }
 
-    pub fn get_configured_device_ids(&self) -> Vec<String> {
-        self.data.bindings
-            .iter()
-            .map(|b| b.device_id.clone())
-            .collect()
+    /// Lift the lock until the next `lock` call or restart
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), ConfigError> {
+        if self.data.config_lock.locked && !self.data.config_lock.verify(passphrase) {
+            return Err(ConfigError::IncorrectPassphrase);
+        }
+        self.unlocked = true;
+        Ok(())
+    }
+
+    /// Remove the lock entirely (must be unlocked first)
+    pub fn remove_lock(&mut self) -> Result<(), ConfigError> {
+        self.ensure_unlocked()?;
+        self.data.config_lock = ConfigLock::default();
+        self.unlocked = false;
+        self.save_config()
+    }
+
+    // --- Layers ---
+
+    /// Layers restored on startup (only meaningful when `persist_layers` is on)
+    pub fn get_active_layers(&self) -> Vec<String> {
+        if self.data.settings.persist_layers {
+            self.data.active_layers.clone()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Layers active in the listener right now (as last reported through
+    /// `save_active_layers`)
+    pub fn current_layers(&self) -> Vec<String> {
+        self.current_layers.clone()
+    }
+
+    /// Persist the active layers if `persist_layers` is on; otherwise they
+    /// are only kept in memory
+    pub fn save_active_layers(&mut self, layers: Vec<String>) -> Result<(), ConfigError> {
+        self.current_layers = layers.clone();
+        if !self.data.settings.persist_layers {
+            return Ok(());
+        }
+        self.data.active_layers = layers;
+        self.save_config()
+    }
+
+    // --- Plugins ---
+
+    pub fn get_plugins(&self) -> Vec<PluginRegistration> {
+        self.data.plugins.clone()
+    }
+
+    /// Register a plugin, replacing any existing one with the same name
+    pub fn register_plugin(&mut self, plugin: PluginRegistration) -> Result<PluginRegistration, ConfigError> {
+        self.ensure_unlocked()?;
+        if plugin.name.trim().is_empty() || plugin.executable_path.trim().is_empty() {
+            return Err(ConfigError::InvalidBinding(
+                "plugin name and executable path are required".to_string(),
+            ));
+        }
+
+        self.data.plugins.retain(|p| !p.name.eq_ignore_ascii_case(&plugin.name));
+        self.data.plugins.push(plugin.clone());
+        self.save_config()?;
+        Ok(plugin)
+    }
+
+    pub fn unregister_plugin(&mut self, name: &str) -> Result<(), ConfigError> {
+        self.ensure_unlocked()?;
+        self.data.plugins.retain(|p| !p.name.eq_ignore_ascii_case(name));
+        self.save_config()?;
+        Ok(())
+    }
+
+    // --- Logs ---
+
+    /// The newest `limit` stored entries (all of them by default).
+    /// `max_log_entries` only bounds what `add_log` keeps, not what's returned.
+    ///
+    /// Logs are stored newest-first; `order` is applied after the limit, so
+    /// `OldestFirst` with a limit returns the same entries, reversed.
+    pub fn get_logs(&self, limit: Option<usize>, order: LogOrder) -> Vec<LogEntry> {
+        let mut logs: Vec<LogEntry> = self.logs
+            .iter()
+            .take(limit.unwrap_or(self.logs.len()))
+            .cloned()
+            .collect();
+
+        if order == LogOrder::OldestFirst {
+            logs.reverse();
+        }
+
+        logs
+    }
+
+    pub fn add_log(&mut self, level: LogEntryLevel, message: String, source: Option<String>) {
+        let entry = LogEntry::new(level, message, source);
+        let _ = append_activity_line(
+            &self.config_dir.join(ACTIVITY_LOG_FILE),
+            &format_activity_line(&entry),
+            self.data.settings.max_log_file_bytes,
+        );
+        self.logs.insert(0, entry);
+        
+        // Trim to max entries
+        let max = self.data.settings.max_log_entries as usize;
+        if self.logs.len() > max {
+            self.logs.truncate(max);
+        }
+        
+        // Batch writes so a burst of presses isn't a burst of file rewrites.
+        // The in-memory list stays authoritative for get_logs.
+        self.unflushed_logs += 1;
+        if self.unflushed_logs >= LOG_FLUSH_BATCH || self.last_log_flush.elapsed() >= LOG_FLUSH_INTERVAL {
+            let _ = self.flush_logs();
+        }
+    }
+
+    /// Write any log entries `add_log` has buffered. Called periodically and
+    /// on shutdown so the tail of a burst isn't lost.
+    pub fn flush_logs(&mut self) -> Result<(), ConfigError> {
+        if self.unflushed_logs == 0 {
+            return Ok(());
+        }
+        self.save_logs()?;
+        self.unflushed_logs = 0;
+        self.last_log_flush = Instant::now();
+        Ok(())
+    }
+
+    // --- Usage stats ---
+
+    pub fn get_usage_stats(&self) -> UsageStats {
+        self.usage_stats.clone()
+    }
+
+    /// Count an executed action, if the user opted in
+    pub fn record_usage(&mut self, action_type: &ActionType, trigger_type: &TriggerType) {
+        if !self.data.settings.usage_stats_enabled {
+            return;
+        }
+        self.usage_stats.record(&serde_label(action_type), &serde_label(trigger_type));
+
+        // Save stats (ignore errors for performance)
+        let _ = self.save_usage_stats();
+    }
+
+    pub fn reset_usage_stats(&mut self) -> Result<(), ConfigError> {
+        self.usage_stats = UsageStats::default();
+        self.save_usage_stats()
+    }
+
+    pub fn clear_logs(&mut self) -> Result<(), ConfigError> {
+        self.logs.clear();
+        self.save_logs()?;
+        Ok(())
+    }
+
+    /// Rewrite logs.json trimmed to `max_log_entries`, with overlong messages
+    /// truncated to `max_log_message_length`, and report the bytes reclaimed
+    pub fn compact_logs(&mut self) -> Result<CompactLogsResult, ConfigError> {
+        // Measure against what add_log has buffered, not a stale file
+        self.flush_logs()?;
+        let bytes_before = fs::metadata(&self.logs_path).map(|m| m.len()).unwrap_or(0);
+
+        let (entries_removed, messages_truncated) = compact_entries(
+            &mut self.logs,
+            self.data.settings.max_log_entries as usize,
+            self.data.settings.max_log_message_length as usize,
+        );
+        self.save_logs()?;
+
+        let bytes_after = fs::metadata(&self.logs_path).map(|m| m.len()).unwrap_or(0);
+
+        Ok(CompactLogsResult {
+            entries_removed,
+            messages_truncated,
+            bytes_before,
+            bytes_after,
+            bytes_reclaimed: bytes_before.saturating_sub(bytes_after),
+        })
+    }
+
+    // --- Device state tracking ---
+
+    /// Apply the `reenable_on_reconnect` setting to a device that just reconnected.
+    /// Bindings in every profile are re-enabled, not just the active one.
+    /// Returns how many bindings were re-enabled (always 0 when the setting is off).
+    pub fn handle_device_reconnected(&mut self, device_id: &str) -> Result<usize, ConfigError> {
+        if !self.data.settings.reenable_on_reconnect {
+            self.add_log(
+                LogEntryLevel::Info,
+                format!("Device reconnected: {} (bindings left unchanged)", device_id),
+                Some(device_id.to_string()),
+            );
+            return Ok(0);
+        }
+
+        let mut reenabled = 0;
+        for binding in self.data.profiles.iter_mut().flat_map(|p| p.bindings.iter_mut()) {
+            if binding_applies(&binding.device_id, device_id) && !binding.enabled {
+                binding.enabled = true;
+                binding.updated_at = chrono::Utc::now().to_rfc3339();
+                reenabled += 1;
+            }
+        }
+
+        if reenabled > 0 {
+            self.save_config()?;
+        }
+
+        self.add_log(
+            LogEntryLevel::Info,
+            format!("Device reconnected: {} (re-enabled {} binding(s))", device_id, reenabled),
+            Some(device_id.to_string()),
+        );
+        Ok(reenabled)
+    }
+
+    /// Add newly seen devices to the known-device history.
+    /// Returns how many weren't known before.
+    pub fn remember_devices<'a>(&mut self, device_ids: impl IntoIterator<Item = &'a str>) -> Result<usize, ConfigError> {
+        let mut added = 0;
+        for device_id in device_ids {
+            let id = base_device_id(device_id);
+            if !self.data.known_devices.contains(&id) {
+                self.data.known_devices.push(id);
+                added += 1;
+            }
+        }
+
+        if added > 0 {
+            self.save_config()?;
+        }
+        Ok(added)
+    }
+
+    /// Bindings for devices that are neither connected nor in the known-device history
+    pub fn find_stale_bindings(&self, connected_ids: &[String]) -> Vec<DeviceBinding> {
+        stale_bindings(self.data.bindings(), connected_ids, &self.data.known_devices)
+    }
+
+    /// Entries for bound devices that aren't in `present`, so the device list
+    /// can keep showing them while unplugged
+    pub fn disconnected_devices(&self, present: &[HidDevice]) -> Vec<HidDevice> {
+        disconnected_devices(self.data.bindings(), present)
+    }
+
+    /// The active profile's bindings grouped by device ID, in saved order,
+    /// each joined with its device in `connected` if it's plugged in
+    pub fn get_bindings_grouped(&self, connected: &[HidDevice]) -> Vec<DeviceBindingGroup> {
+        group_bindings(self.data.bindings(), connected)
+    }
+
+    pub fn get_configured_device_ids(&self) -> Vec<String> {
+        self.data.bindings()
+            .iter()
+            .map(|b| b.device_id.clone())
+            .collect()
+    }
+
+    // --- Export / import ---
+
+    /// Write the active profile's bindings, settings and plugins to `path` as
+    /// a `ConfigExport`.
+    /// Returns how many bindings were written.
+    pub fn export_config(&self, path: &Path) -> Result<usize, ConfigError> {
+        let export = ConfigExport {
+            schema_version: CONFIG_SCHEMA_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            bindings: self.data.bindings().to_vec(),
+            settings: self.data.settings.clone(),
+            plugins: self.data.plugins.clone(),
+        };
+        fs::write(path, serde_json::to_string_pretty(&export)?)?;
+        Ok(export.bindings.len())
+    }
+
+    /// Load a file written by `export_config`. With `replace`, its bindings,
+    /// settings and plugins replace the current ones; otherwise bindings are
+    /// merged by ID and plugins by name, and settings are left alone. Bindings
+    /// always go into the active profile. Returns how many bindings were imported.
+    pub fn import_config(&mut self, path: &Path, replace: bool) -> Result<usize, ConfigError> {
+        self.ensure_unlocked()?;
+        let export = parse_config_export(&fs::read_to_string(path)?)?;
+        let imported = export.bindings.len();
+
+        if replace {
+            *self.data.bindings_mut() = export.bindings;
+            self.data.settings = export.settings;
+            self.data.plugins = export.plugins;
+        } else {
+            for binding in export.bindings {
+                match self.data.bindings().iter().position(|b| b.id == binding.id) {
+                    Some(pos) => self.data.bindings_mut()[pos] = binding,
+                    None => self.data.bindings_mut().push(binding),
+                }
+            }
+            for plugin in export.plugins {
+                self.data.plugins.retain(|p| !p.name.eq_ignore_ascii_case(&plugin.name));
+                self.data.plugins.push(plugin);
+            }
+        }
+
+        self.save_config()?;
+        Ok(imported)
+    }
+}
+
+/// Parse and check an export file: the schema version must match exactly and
+/// every binding needs a valid device ID
+fn parse_config_export(content: &str) -> Result<ConfigExport, ConfigError> {
+    let value: serde_json::Value = serde_json::from_str(content)?;
+    match value.get("schemaVersion").and_then(|v| v.as_u64()) {
+        Some(version) if version == CONFIG_SCHEMA_VERSION as u64 => {}
+        Some(version) => {
+            return Err(ConfigError::InvalidImport(format!(
+                "schema version {} is not supported (expected {})",
+                version, CONFIG_SCHEMA_VERSION
+            )))
+        }
+        None => {
+            return Err(ConfigError::InvalidImport(
+                "no schemaVersion; not a configuration export".to_string(),
+            ))
+        }
+    }
+
+    let export: ConfigExport = serde_json::from_value(value)?;
+    for binding in &export.bindings {
+        validate_device_id(&binding.device_id)
+            .map_err(|e| ConfigError::InvalidImport(format!("binding {}: {}", binding.id, e)))?;
+    }
+    Ok(export)
+}
+
+/// Unsaved starting point for a detected device: IDs filled in, single press,
+/// an empty LaunchApp action, and disabled until the user finishes editing
+pub fn binding_template(device: &HidDevice) -> DeviceBinding {
+    let mut binding = DeviceBinding::new(
+        device.id.clone(),
+        device.vendor_id.clone(),
+        device.product_id.clone(),
+        TriggerType::SinglePress,
+        ActionConfig {
+            r#type: ActionType::LaunchApp,
+            executable_path: String::new(),
+            ..Default::default()
+        },
+    );
+    binding.enabled = false;
+    binding.consumer_usage = device.consumer_usage.map(crate::consumer::consumer_usage_id);
+    binding.button_code = device.button_code;
+    binding
+}
+
+/// Reject a binding that can never run: a bad device ID, an action with an
+/// empty executable path, or a hotkey that doesn't parse. The power-condition
+/// alternate and sequence steps are checked the same way.
+pub fn validate_binding(binding: &DeviceBinding) -> Result<(), ConfigError> {
+    validate_device_id(&binding.device_id).map_err(ConfigError::InvalidBinding)?;
+    validate_action(&binding.action).map_err(ConfigError::InvalidBinding)?;
+    if let Some(alternate) = binding.power_condition.as_ref().and_then(|c| c.otherwise.as_ref()) {
+        validate_action(alternate).map_err(|e| ConfigError::InvalidBinding(format!("power alternate: {}", e)))?;
+    }
+    Ok(())
+}
+
+fn validate_action(action: &ActionConfig) -> Result<(), String> {
+    match action.r#type {
+        ActionType::Hotkey => parse_hotkey(&action.executable_path)
+            .map(|_| ())
+            .map_err(|e| format!("invalid hotkey '{}': {}", action.executable_path, e)),
+        ActionType::Sequence => {
+            let steps = action.sequence.as_ref().map(|s| s.steps.as_slice()).unwrap_or_default();
+            for (index, step) in steps.iter().enumerate() {
+                validate_action(step).map_err(|e| format!("step {}: {}", index + 1, e))?;
+            }
+            Ok(())
+        }
+        _ if action.executable_path.trim().is_empty() => Err(format!(
+            "executable path is empty for a {} action",
+            serde_label(&action.r#type)
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Check a device ID is `VID:PID` (4 hex digits each), optionally followed by
+/// an interface number or serial, e.g. `AF88:6688`, `AF88:6688:1` or
+/// `AF88:6688:SN0042`
+pub fn validate_device_id(device_id: &str) -> Result<(), String> {
+    if device_id.trim().is_empty() {
+        return Err("device ID is empty".to_string());
+    }
+
+    let parts: Vec<&str> = device_id.split(':').collect();
+    if parts.len() != 2 && parts.len() != 3 {
+        return Err(format!(
+            "device ID '{}' must be VID:PID, VID:PID:INTERFACE or VID:PID:SERIAL",
+            device_id
+        ));
+    }
+
+    for (label, part) in [("vendor", parts[0]), ("product", parts[1])] {
+        if part.len() != 4 || !part.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "{} ID '{}' in device ID '{}' must be 4 hex digits",
+                label, part, device_id
+            ));
+        }
+    }
+
+    if let Some(suffix) = parts.get(2) {
+        if suffix.is_empty() || suffix.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(format!(
+                "interface or serial '{}' in device ID '{}' must be non-empty without spaces",
+                suffix, device_id
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Device ID for an enumerated device: `VID:PID`, or `VID:PID:SERIAL` when
+/// `use_serial` is on and the device reports a serial that fits in an ID
+pub fn build_device_id(vendor_id: &str, product_id: &str, serial: Option<&str>, use_serial: bool) -> String {
+    let serial = serial
+        .map(str::trim)
+        .filter(|s| use_serial && !s.is_empty() && !s.chars().any(|c| c == ':' || c.is_whitespace() || c.is_control()));
+
+    match serial {
+        Some(serial) => format!("{}:{}:{}", vendor_id, product_id, serial),
+        None => format!("{}:{}", vendor_id, product_id),
+    }
+}
+
+/// Marks one interface of a composite device, after the `MI_xx` component
+/// of Windows device paths
+const INTERFACE_MARKER: &str = "MI_";
+
+/// Device ID for one interface of a composite device: `VID:PID:MI_01`, or
+/// `VID:PID:SERIAL&MI_01` when the ID already carries a serial
+pub fn interface_device_id(device_id: &str, interface_number: u8) -> String {
+    let separator = if shared_device_id(device_id).is_some() { '&' } else { ':' };
+    format!("{}{}{}{:02X}", device_id, separator, INTERFACE_MARKER, interface_number)
+}
+
+/// ID of the input endpoint a device reports from. Composite devices get an
+/// ID per interface so each can be bound separately.
+pub fn endpoint_device_id(device_id: String, interface_number: u8, total_interfaces: u8) -> String {
+    if total_interfaces > 1 {
+        interface_device_id(&device_id, interface_number)
+    } else {
+        device_id
+    }
+}
+
+/// The device ID an interface ID belongs to, or `None` if the ID doesn't
+/// name an interface
+pub fn parent_device_id(device_id: &str) -> Option<&str> {
+    let marker = format!("&{}", INTERFACE_MARKER);
+    if let Some(i) = device_id.rfind(&marker) {
+        return Some(&device_id[..i]);
+    }
+    let shared = shared_device_id(device_id)?;
+    device_id[shared.len() + 1..].starts_with(INTERFACE_MARKER).then_some(shared)
+}
+
+/// The `VID:PID` a suffixed device ID (`VID:PID:SERIAL`) shares with its
+/// identical siblings, or `None` if the ID has no suffix
+pub fn shared_device_id(device_id: &str) -> Option<&str> {
+    let mut colons = device_id.match_indices(':').map(|(i, _)| i);
+    colons.next()?;
+    colons.next().map(|end| &device_id[..end])
+}
+
+/// Whether a binding saved for `binding_device_id` applies to input from
+/// `device_id`: exactly, via the device an interface belongs to, or via the
+/// shared `VID:PID`
+pub fn binding_applies(binding_device_id: &str, device_id: &str) -> bool {
+    binding_device_id == device_id
+        || parent_device_id(device_id) == Some(binding_device_id)
+        || shared_device_id(device_id) == Some(binding_device_id)
+}
+
+/// Pick the binding for an input. Every constraint a binding sets
+/// (manufacturer, consumer usage, button, layer) must match the input, so a
+/// constrained binding never matches a different or unknown value. Among the
+/// matches the first in `evaluation_order` wins, so a button's binding for
+/// an active layer takes over from its unlayered one. With a trigger in the
+/// query, an enabled binding for that trigger is preferred, then any binding
+/// for it, then the first match (so callers can report a trigger mismatch).
+fn select_binding<'a>(bindings: &'a [DeviceBinding], query: &BindingQuery) -> Option<&'a DeviceBinding> {
+    let matches: Vec<&DeviceBinding> = evaluation_order(bindings, query.device_id)
+        .into_iter()
+        .filter(|binding| {
+            constraint_matches(binding.manufacturer.as_deref(), query.manufacturer)
+                && constraint_matches(binding.consumer_usage.as_deref(), query.consumer_usage)
+                && binding.button_code.map_or(true, |code| query.button_code == Some(code))
+                && query.layers.map_or(layers::required_layer(binding).is_none(), |l| l.binding_allowed(binding))
+        })
+        .collect();
+
+    let Some(trigger) = query.trigger_type else {
+        return matches.first().copied();
+    };
+    matches
+        .iter()
+        .find(|b| &b.trigger_type == trigger && b.enabled)
+        .or_else(|| matches.iter().find(|b| &b.trigger_type == trigger))
+        .or(matches.first())
+        .copied()
+}
+
+/// A device's bindings in the order `select_binding` tries them: bindings
+/// for the exact ID, then its device (for an interface ID), then the shared
+/// `VID:PID`; within each, most constrained first, layer bindings ahead of
+/// unlayered ones, then in saved order
+pub fn evaluation_order<'a>(bindings: &'a [DeviceBinding], device_id: &str) -> Vec<&'a DeviceBinding> {
+    let mut candidates: Vec<&DeviceBinding> = bindings
+        .iter()
+        .filter(|b| binding_applies(&b.device_id, device_id))
+        .collect();
+    // Stable sort keeps saved order among equally specific bindings
+    let closeness = |b: &DeviceBinding| {
+        if b.device_id == device_id {
+            0
+        } else if parent_device_id(device_id) == Some(b.device_id.as_str()) {
+            1
+        } else {
+            2
+        }
+    };
+    let unlayered = |b: &DeviceBinding| layers::required_layer(b).is_none();
+    candidates.sort_by_key(|b| (closeness(b), std::cmp::Reverse(specificity(b)), unlayered(b)));
+    candidates
+}
+
+/// How many input constraints a binding sets
+fn specificity(binding: &DeviceBinding) -> usize {
+    binding.manufacturer.is_some() as usize
+        + binding.consumer_usage.is_some() as usize
+        + binding.button_code.is_some() as usize
+}
+
+/// An unset constraint matches anything; a set one needs an equal value
+pub fn constraint_matches(constraint: Option<&str>, actual: Option<&str>) -> bool {
+    match constraint {
+        None => true,
+        Some(c) => actual.is_some_and(|a| c.trim().eq_ignore_ascii_case(a.trim())),
+    }
+}
+
+/// Whether two bindings answer the same input: device, trigger, button,
+/// layer and constraints all equal
+fn same_identity(a: &DeviceBinding, b: &DeviceBinding) -> bool {
+    a.device_id == b.device_id
+        && a.trigger_type == b.trigger_type
+        && a.button_code == b.button_code
+        && same_constraint(layers::required_layer(a), layers::required_layer(b))
+        && same_constraint(a.manufacturer.as_deref(), b.manufacturer.as_deref())
+        && same_constraint(a.consumer_usage.as_deref(), b.consumer_usage.as_deref())
+}
+
+/// Fail if `binding` is enabled and another enabled binding (other than the
+/// one at `replacing`) has the same identity, since only one of them could
+/// ever run
+fn check_conflict(bindings: &[DeviceBinding], binding: &DeviceBinding, replacing: Option<usize>) -> Result<(), ConfigError> {
+    if !binding.enabled {
+        return Ok(());
+    }
+    let conflict = bindings
+        .iter()
+        .enumerate()
+        .find(|(i, b)| Some(*i) != replacing && b.enabled && same_identity(b, binding));
+    match conflict {
+        Some((_, other)) => {
+            let button = binding.button_code.map(|c| format!(" button 0x{:02X}", c)).unwrap_or_default();
+            let layer = layers::required_layer(binding).map(|l| format!(" in layer \"{}\"", l)).unwrap_or_default();
+            Err(ConfigError::BindingConflict(format!(
+                "{} on {}{}{} is already bound by binding {}",
+                serde_label(&binding.trigger_type),
+                binding.device_id,
+                button,
+                layer,
+                other.id
+            )))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Whether two bindings' constraints are the same (so one replaces the other)
+fn same_constraint(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.trim().eq_ignore_ascii_case(b.trim()),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Rewrite an action's executable and working-directory prefixes, including
+/// those of its sequence steps. Returns whether anything matched.
+fn rewrite_paths_in_action(action: &mut ActionConfig, from_prefix: &str, to_prefix: &str, dry_run: bool) -> bool {
+    let mut changed = false;
+    let paths = std::iter::once(&mut action.executable_path).chain(action.working_directory.as_mut());
+    for path in paths {
+        if let Some(rewritten) = rewrite_path_prefix(path, from_prefix, to_prefix) {
+            if !dry_run {
+                *path = rewritten;
+            }
+            changed = true;
+        }
+    }
+
+    for step in action.sequence.iter_mut().flat_map(|s| s.steps.iter_mut()) {
+        changed |= rewrite_paths_in_action(step, from_prefix, to_prefix, dry_run);
+    }
+    changed
+}
+
+/// `VID:PID` part of a device ID (drops any interface or serial suffix), uppercased
+fn base_device_id(device_id: &str) -> String {
+    device_id
+        .split(':')
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(":")
+        .to_ascii_uppercase()
+}
+
+/// One `Disconnected` entry per bound device ID with no device in `present`,
+/// filled in from what the bindings store (bindings keep no product name)
+fn disconnected_devices(bindings: &[DeviceBinding], present: &[HidDevice]) -> Vec<HidDevice> {
+    let mut devices: Vec<HidDevice> = Vec::new();
+    for binding in bindings {
+        if devices.iter().any(|d| d.id == binding.device_id)
+            || present.iter().any(|d| binding_applies(&binding.device_id, &d.id))
+        {
+            continue;
+        }
+
+        // A `VID:PID:SERIAL` ID carries the serial, also with an `&MI_xx`
+        // interface suffix; `VID:PID:MI_xx` doesn't
+        let owner = parent_device_id(&binding.device_id).unwrap_or(&binding.device_id);
+        let serial_number = shared_device_id(owner)
+            .map(|shared| owner[shared.len() + 1..].to_string());
+
+        devices.push(HidDevice {
+            id: binding.device_id.clone(),
+            name: "Disconnected Device".to_string(),
+            vendor_id: binding.vendor_id.clone(),
+            product_id: binding.product_id.clone(),
+            interface_number: 0,
+            total_interfaces: 1,
+            status: DeviceStatus::Disconnected,
+            manufacturer: binding.manufacturer.clone(),
+            serial_number,
+            consumer_usage: None,
+            raw_report: Vec::new(),
+            is_repeat: false,
+            button_code: None,
+        });
+    }
+    devices
+}
+
+fn group_bindings(bindings: &[DeviceBinding], connected: &[HidDevice]) -> Vec<DeviceBindingGroup> {
+    let mut groups: Vec<DeviceBindingGroup> = Vec::new();
+    for binding in bindings {
+        if let Some(group) = groups.iter_mut().find(|g| g.device_id == binding.device_id) {
+            group.bindings.push(binding.clone());
+            continue;
+        }
+
+        let device = connected.iter().find(|d| binding_applies(&binding.device_id, &d.id)).cloned();
+        groups.push(DeviceBindingGroup {
+            device_id: binding.device_id.clone(),
+            status: if device.is_some() { DeviceStatus::Configured } else { DeviceStatus::Disconnected },
+            device,
+            bindings: vec![binding.clone()],
+        });
+    }
+    groups
+}
+
+fn stale_bindings(bindings: &[DeviceBinding], connected_ids: &[String], known_ids: &[String]) -> Vec<DeviceBinding> {
+    let seen = |id: &str| {
+        let id = base_device_id(id);
+        connected_ids.iter().chain(known_ids).any(|seen| base_device_id(seen) == id)
+    };
+
+    bindings.iter().filter(|b| !seen(&b.device_id)).cloned().collect()
+}
+
+/// `path` with `from` swapped for `to`, if it starts with `from`. Matching is
+/// case-insensitive (Windows paths) and only on whole path components, so
+/// `C:\Tools` doesn't match `C:\Toolbox`.
+fn rewrite_path_prefix(path: &str, from: &str, to: &str) -> Option<String> {
+    let from = from.trim_end_matches(['\\', '/']);
+    let head = path.get(..from.len())?;
+    let rest = &path[from.len()..];
+
+    if !head.eq_ignore_ascii_case(from) || !(rest.is_empty() || rest.starts_with(['\\', '/'])) {
+        return None;
+    }
+    Some(format!("{}{}", to.trim_end_matches(['\\', '/']), rest))
+}
+
+/// Trim logs to `max_entries` (newest first) and truncate messages longer than
+/// `max_message_len` characters. Returns (entries removed, messages truncated).
+fn compact_entries(logs: &mut Vec<LogEntry>, max_entries: usize, max_message_len: usize) -> (usize, usize) {
+    let entries_removed = logs.len().saturating_sub(max_entries);
+    logs.truncate(max_entries);
+
+    let mut messages_truncated = 0;
+    for entry in logs.iter_mut() {
+        if entry.message.chars().count() > max_message_len {
+            entry.message = entry.message.chars().take(max_message_len).collect::<String>() + "…";
+            messages_truncated += 1;
+        }
+    }
+
+    (entries_removed, messages_truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_manager() -> (ConfigManager, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("usb-configurator-test-{}", uuid::Uuid::new_v4()));
+        let manager = ConfigManager::with_dir(dir.clone()).unwrap();
+        (manager, dir)
+    }
+
+    fn test_binding(device_id: &str) -> DeviceBinding {
+        DeviceBinding::new(
+            device_id.to_string(),
+            "1234".to_string(),
+            "5678".to_string(),
+            TriggerType::SinglePress,
+            ActionConfig {
+                r#type: ActionType::LaunchApp,
+                executable_path: "notepad.exe".to_string(),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn test_device(id: &str) -> HidDevice {
+        HidDevice {
+            id: id.to_string(),
+            name: "Foot Pedal".to_string(),
+            vendor_id: "AF88".to_string(),
+            product_id: "6688".to_string(),
+            interface_number: 1,
+            total_interfaces: 2,
+            status: DeviceStatus::Connected,
+            manufacturer: None,
+            serial_number: None,
+            consumer_usage: None,
+            raw_report: Vec::new(),
+            is_repeat: false,
+            button_code: None,
+        }
+    }
+
+    #[test]
+    fn test_reconnect_leaves_bindings_when_setting_off() {
+        let (mut manager, dir) = temp_manager();
+        let mut binding = test_binding("1234:5678");
+        binding.enabled = false; // Disabled while unplugged
+        manager.save_binding(binding).unwrap();
+
+        let reenabled = manager.handle_device_reconnected("1234:5678").unwrap();
+        assert_eq!(reenabled, 0);
+        assert!(!manager.get_binding("1234:5678").unwrap().enabled);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_reconnect_reenables_bindings_when_setting_on() {
+        let (mut manager, dir) = temp_manager();
+        let mut settings = manager.get_settings();
+        settings.reenable_on_reconnect = true;
+        manager.save_settings(settings).unwrap();
+
+        let mut binding = test_binding("1234:5678");
+        binding.enabled = false;
+        manager.save_binding(binding).unwrap();
+        manager.save_binding(test_binding("AAAA:BBBB")).unwrap();
+
+        let reenabled = manager.handle_device_reconnected("1234:5678").unwrap();
+        assert_eq!(reenabled, 1);
+        assert!(manager.get_binding("1234:5678").unwrap().enabled);
+
+        // Persisted, not just in memory
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert!(reloaded.get_binding("1234:5678").unwrap().enabled);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_compact_entries_trims_count_and_long_messages() {
+        let mut logs: Vec<LogEntry> = (0..5)
+            .map(|i| LogEntry::new(LogEntryLevel::Info, format!("entry {}", i), None))
+            .collect();
+        logs[1].message = "x".repeat(50);
+
+        let (removed, truncated) = compact_entries(&mut logs, 3, 10);
+        assert_eq!(removed, 2);
+        assert_eq!(truncated, 1);
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs[0].message, "entry 0");
+        assert_eq!(logs[1].message, format!("{}…", "x".repeat(10)));
+    }
+
+    #[test]
+    fn test_compact_logs_reports_reclaimed_bytes() {
+        let (mut manager, dir) = temp_manager();
+        let mut settings = manager.get_settings();
+        settings.max_log_message_length = 20;
+        manager.save_settings(settings).unwrap();
+
+        manager.add_log(LogEntryLevel::Info, "y".repeat(5000), None);
+        let result = manager.compact_logs().unwrap();
+
+        assert_eq!(result.messages_truncated, 1);
+        assert!(result.bytes_after < result.bytes_before);
+        assert_eq!(result.bytes_reclaimed, result.bytes_before - result.bytes_after);
+        assert_eq!(
+            result.bytes_after,
+            fs::metadata(dir.join("logs.json")).unwrap().len()
+        );
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_validate_device_id_valid() {
+        assert!(validate_device_id("AF88:6688").is_ok());
+        assert!(validate_device_id("af88:6688").is_ok());
+        assert!(validate_device_id("AF88:6688:1").is_ok());
+        assert!(validate_device_id("AF88:6688:SN0042").is_ok());
+    }
+
+    #[test]
+    fn test_validate_device_id_empty() {
+        assert!(validate_device_id("").is_err());
+        assert!(validate_device_id("   ").is_err());
+    }
+
+    #[test]
+    fn test_validate_device_id_malformed() {
+        assert!(validate_device_id("AF88").is_err());
+        assert!(validate_device_id("AF88:66").is_err());
+        assert!(validate_device_id("GGGG:6688").is_err());
+        assert!(validate_device_id("AF88:6688:").is_err());
+        assert!(validate_device_id("AF88:6688:S N").is_err());
+        assert!(validate_device_id("AF88:6688:1:2").is_err());
+        assert!(validate_device_id("dev-1").is_err());
+    }
+
+    #[test]
+    fn test_save_binding_rejects_invalid_device_id() {
+        let (mut manager, dir) = temp_manager();
+
+        let err = manager.save_binding(test_binding("")).unwrap_err();
+        assert!(err.to_string().contains("empty"));
+        assert!(manager.get_all_bindings().is_empty());
+        assert!(!dir.join("config.json").exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_save_binding_rejects_empty_executable() {
+        let (mut manager, dir) = temp_manager();
+
+        let mut binding = test_binding("1234:5678");
+        binding.action.executable_path = "  ".to_string();
+        let err = manager.save_binding(binding).unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidBinding(_)));
+        assert!(err.to_string().contains("launch-app"));
+        assert!(manager.get_all_bindings().is_empty());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_save_binding_rejects_unparseable_hotkey() {
+        let (mut manager, dir) = temp_manager();
+
+        let mut binding = test_binding("1234:5678");
+        binding.action.r#type = ActionType::Hotkey;
+        binding.action.executable_path = "Ctrl+Bogus".to_string();
+        let err = manager.save_binding(binding).unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidBinding(_)));
+        assert!(err.to_string().contains("Ctrl+Bogus"));
+
+        let mut binding = test_binding("1234:5678");
+        binding.action.r#type = ActionType::Hotkey;
+        binding.action.executable_path = String::new();
+        assert!(manager.save_binding(binding).is_err());
+        assert!(manager.get_all_bindings().is_empty());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_save_binding_checks_alternate_and_sequence_steps() {
+        let (mut manager, dir) = temp_manager();
+
+        let mut empty = test_binding("1234:5678").action;
+        empty.executable_path = String::new();
+
+        let mut binding = test_binding("1234:5678");
+        binding.power_condition = Some(crate::types::PowerCondition {
+            run_on: crate::types::PowerSource::Ac,
+            otherwise: Some(empty.clone()),
+        });
+        let err = manager.save_binding(binding).unwrap_err();
+        assert!(err.to_string().contains("power alternate"));
+
+        let mut binding = test_binding("1234:5678");
+        binding.action.r#type = ActionType::Sequence;
+        binding.action.executable_path = String::new();
+        binding.action.sequence = Some(crate::types::ActionSequence {
+            steps: vec![test_binding("1234:5678").action, empty],
+            delay_ms: 0,
+            continue_on_error: false,
+        });
+        let err = manager.save_binding(binding).unwrap_err();
+        assert!(err.to_string().contains("step 2"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_save_binding_accepts_valid_hotkey() {
+        let (mut manager, dir) = temp_manager();
+
+        let mut binding = test_binding("1234:5678");
+        binding.action.r#type = ActionType::Hotkey;
+        binding.action.executable_path = "Ctrl+Shift+V".to_string();
+        let saved = manager.save_binding(binding).unwrap();
+        assert_eq!(manager.get_binding_by_id(&saved.id).unwrap().action.executable_path, "Ctrl+Shift+V");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_select_binding_by_button_code() {
+        let any_key = test_binding("1234:5678");
+        let mut f13 = test_binding("1234:5678");
+        f13.button_code = Some(0x7C);
+        let mut f14 = test_binding("1234:5678");
+        f14.button_code = Some(0x7D);
+        let bindings = vec![any_key.clone(), f13.clone(), f14.clone()];
+
+        let query = |button_code| BindingQuery {
+            button_code,
+            ..BindingQuery::device("1234:5678")
+        };
+
+        assert_eq!(select_binding(&bindings, &query(Some(0x7C))).unwrap().id, f13.id);
+        assert_eq!(select_binding(&bindings, &query(Some(0x7D))).unwrap().id, f14.id);
+        // Other keys, or input without a key code, fall back to the device-wide binding
+        assert_eq!(select_binding(&bindings, &query(Some(0x41))).unwrap().id, any_key.id);
+        assert_eq!(select_binding(&bindings, &query(None)).unwrap().id, any_key.id);
+
+        // Without a device-wide binding, other keys don't match at all
+        let per_key = vec![f13, f14];
+        assert!(select_binding(&per_key, &query(Some(0x41))).is_none());
+    }
+
+    #[test]
+    fn test_layer_binding_takes_over_button_while_active() {
+        let (mut manager, dir) = temp_manager();
+        let mut base = test_binding("1234:5678");
+        base.button_code = Some(0x7C);
+        let base = manager.save_binding(base).unwrap();
+        let mut layered = test_binding("1234:5678");
+        layered.button_code = Some(0x7C);
+        layered.required_layer = Some("fn".to_string());
+        let layered = manager.save_binding(layered).unwrap();
+
+        // Both are kept: the layer is part of the binding's identity
+        assert_eq!(manager.get_all_bindings().len(), 2);
+
+        fn query(button_code: u16, layers: Option<&LayerState>) -> BindingQuery<'_> {
+            BindingQuery {
+                button_code: Some(button_code),
+                trigger_type: Some(&TriggerType::SinglePress),
+                layers,
+                ..BindingQuery::device("1234:5678")
+            }
+        }
+        let mut layers = LayerState::new();
+        assert_eq!(manager.find_binding(&query(0x7C, None)).unwrap().id, base.id);
+        assert_eq!(manager.find_binding(&query(0x7C, Some(&layers))).unwrap().id, base.id);
+
+        layers.set("fn");
+        assert_eq!(manager.find_binding(&query(0x7C, Some(&layers))).unwrap().id, layered.id);
+
+        // A layer-only button doesn't fire outside its layer
+        let mut other = test_binding("1234:5678");
+        other.button_code = Some(0x7D);
+        other.required_layer = Some("fn".to_string());
+        let other = manager.save_binding(other).unwrap();
+        assert_eq!(manager.find_binding(&query(0x7D, Some(&layers))).unwrap().id, other.id);
+        layers.clear("fn");
+        assert!(manager.find_binding(&query(0x7D, Some(&layers))).is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_build_device_id_with_serial() {
+        assert_eq!(build_device_id("AF88", "6688", Some("SN0042"), true), "AF88:6688:SN0042");
+        assert_eq!(build_device_id("AF88", "6688", Some("SN0042"), false), "AF88:6688");
+        // Missing or unusable serials fall back to VID:PID
+        assert_eq!(build_device_id("AF88", "6688", None, true), "AF88:6688");
+        assert_eq!(build_device_id("AF88", "6688", Some("  "), true), "AF88:6688");
+        assert_eq!(build_device_id("AF88", "6688", Some("A:B"), true), "AF88:6688");
+
+        assert_eq!(shared_device_id("AF88:6688:SN0042"), Some("AF88:6688"));
+        assert_eq!(shared_device_id("AF88:6688"), None);
+    }
+
+    #[test]
+    fn test_interface_device_ids() {
+        assert_eq!(interface_device_id("AF88:6688", 1), "AF88:6688:MI_01");
+        assert_eq!(interface_device_id("AF88:6688:SN0042", 2), "AF88:6688:SN0042&MI_02");
+        assert_eq!(endpoint_device_id("AF88:6688".to_string(), 0, 1), "AF88:6688");
+        assert_eq!(endpoint_device_id("AF88:6688".to_string(), 0, 2), "AF88:6688:MI_00");
+        assert!(validate_device_id("AF88:6688:SN0042&MI_02").is_ok());
+
+        assert_eq!(parent_device_id("AF88:6688:MI_01"), Some("AF88:6688"));
+        assert_eq!(parent_device_id("AF88:6688:SN0042&MI_02"), Some("AF88:6688:SN0042"));
+        assert_eq!(parent_device_id("AF88:6688:SN0042"), None);
+        assert_eq!(parent_device_id("AF88:6688"), None);
+
+        // Device-wide bindings keep applying to every interface
+        assert!(binding_applies("AF88:6688", "AF88:6688:MI_01"));
+        assert!(binding_applies("AF88:6688:SN0042", "AF88:6688:SN0042&MI_02"));
+        assert!(binding_applies("AF88:6688", "AF88:6688:SN0042&MI_02"));
+        assert!(!binding_applies("AF88:6688:MI_00", "AF88:6688:MI_01"));
+    }
+
+    #[test]
+    fn test_serial_device_falls_back_to_shared_binding() {
+        let shared = test_binding("AF88:6688");
+        let left = test_binding("AF88:6688:LEFT");
+        let bindings = vec![shared.clone(), left.clone()];
+
+        let query = |id| BindingQuery::device(id);
+        assert_eq!(select_binding(&bindings, &query("AF88:6688:LEFT")).unwrap().id, left.id);
+        assert_eq!(select_binding(&bindings, &query("AF88:6688:RIGHT")).unwrap().id, shared.id);
+        assert_eq!(select_binding(&bindings, &query("AF88:6688")).unwrap().id, shared.id);
+
+        let (mut manager, dir) = temp_manager();
+        manager.save_binding(left.clone()).unwrap();
+        assert!(manager.get_binding("AF88:6688:RIGHT").is_none());
+        manager.save_binding(shared.clone()).unwrap();
+        assert_eq!(manager.get_binding("AF88:6688:LEFT").unwrap().id, left.id);
+        assert_eq!(manager.get_binding("AF88:6688:RIGHT").unwrap().id, shared.id);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_save_binding_keeps_bindings_per_button() {
+        let (mut manager, dir) = temp_manager();
+        let mut first = test_binding("1234:5678");
+        first.button_code = Some(0x7C);
+        let mut second = test_binding("1234:5678");
+        second.button_code = Some(0x7D);
+
+        manager.save_binding(first).unwrap();
+        manager.save_binding(second).unwrap();
+        assert_eq!(manager.get_all_bindings().len(), 2);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_single_and_double_press_coexist() {
+        let (mut manager, dir) = temp_manager();
+        let single = manager.save_binding(test_binding("1234:5678")).unwrap();
+        let mut double = test_binding("1234:5678");
+        double.trigger_type = TriggerType::DoublePress;
+        let double = manager.save_binding(double).unwrap();
+        assert_eq!(manager.get_all_bindings().len(), 2);
+
+        let query = |trigger_type| BindingQuery {
+            trigger_type,
+            ..BindingQuery::device("1234:5678")
+        };
+        assert_eq!(manager.find_binding(&query(Some(&TriggerType::SinglePress))).unwrap().id, single.id);
+        assert_eq!(manager.find_binding(&query(Some(&TriggerType::DoublePress))).unwrap().id, double.id);
+        // No triple-press binding: the first match comes back so the caller
+        // can report the mismatch
+        assert_eq!(manager.find_binding(&query(Some(&TriggerType::TriplePress))).unwrap().id, single.id);
+
+        // Saving the same identity again still replaces
+        let mut replacement = test_binding("1234:5678");
+        replacement.trigger_type = TriggerType::DoublePress;
+        manager.save_binding(replacement.clone()).unwrap();
+        assert_eq!(manager.get_all_bindings().len(), 2);
+        assert!(manager.get_binding_by_id(&double.id).is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_conflicting_enabled_bindings_rejected() {
+        let (mut manager, dir) = temp_manager();
+        let single = manager.save_binding(test_binding("1234:5678")).unwrap();
+        let mut double = test_binding("1234:5678");
+        double.trigger_type = TriggerType::DoublePress;
+        let double = manager.save_binding(double).unwrap();
+
+        // Editing the double-press binding into a second single-press one
+        let err = manager.set_binding_trigger(&double.id, TriggerType::SinglePress).unwrap_err();
+        assert!(matches!(err, ConfigError::BindingConflict(_)));
+        assert_eq!(err.code(), ErrorCode::BindingConflict);
+        assert!(err.to_string().contains(&single.id));
+        let mut edited = double.clone();
+        edited.trigger_type = TriggerType::SinglePress;
+        assert!(matches!(manager.save_binding(edited.clone()), Err(ConfigError::BindingConflict(_))));
+        assert_eq!(
+            manager.get_binding_by_id(&double.id).unwrap().trigger_type,
+            TriggerType::DoublePress
+        );
+
+        // A disabled duplicate is fine, but can't be re-enabled while the other is
+        manager.set_binding_enabled(&single.id, false).unwrap();
+        manager.save_binding(edited).unwrap();
+        assert_eq!(manager.get_all_bindings().len(), 2);
+        assert!(matches!(
+            manager.set_binding_enabled(&single.id, true),
+            Err(ConfigError::BindingConflict(_))
+        ));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_set_binding_trigger() {
+        let (mut manager, dir) = temp_manager();
+        let binding = manager.save_binding(test_binding("1234:5678")).unwrap();
+        let other = manager.save_binding(test_binding("AAAA:BBBB")).unwrap();
+
+        let updated = manager.set_binding_trigger(&binding.id, TriggerType::DoublePress).unwrap();
+        assert_eq!(updated.trigger_type, TriggerType::DoublePress);
+        assert_eq!(updated.action.executable_path, binding.action.executable_path);
+
+        // Persisted, and other bindings are untouched
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert_eq!(reloaded.get_binding_by_id(&binding.id).unwrap().trigger_type, TriggerType::DoublePress);
+        assert_eq!(reloaded.get_binding_by_id(&other.id).unwrap().trigger_type, other.trigger_type);
+
+        assert!(matches!(
+            manager.set_binding_trigger("missing", TriggerType::SinglePress),
+            Err(ConfigError::BindingNotFound(_))
+        ));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_set_binding_enabled() {
+        let (mut manager, dir) = temp_manager();
+        let binding = manager.save_binding(test_binding("1234:5678")).unwrap();
+        assert!(binding.enabled);
+
+        let updated = manager.set_binding_enabled(&binding.id, false).unwrap();
+        assert!(!updated.enabled);
+        assert_eq!(updated.action, binding.action);
+
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert!(!reloaded.get_binding_by_id(&binding.id).unwrap().enabled);
+        assert!(manager.set_binding_enabled(&binding.id, true).unwrap().enabled);
+
+        assert!(matches!(
+            manager.set_binding_enabled("missing", true),
+            Err(ConfigError::BindingNotFound(_))
+        ));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_usage_stats_are_opt_in() {
+        let (mut manager, dir) = temp_manager();
+
+        manager.record_usage(&ActionType::LaunchApp, &TriggerType::SinglePress);
+        assert_eq!(manager.get_usage_stats(), UsageStats::default());
+        assert!(!dir.join(USAGE_STATS_FILE).exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_multi_press_window_range() {
+        let (mut manager, dir) = temp_manager();
+        let with_window = |ms| AppSettings {
+            multi_press_window_ms: ms,
+            ..AppSettings::default()
+        };
+
+        assert!(matches!(manager.save_settings(with_window(50)), Err(ConfigError::InvalidSettings(_))));
+        assert!(matches!(manager.save_settings(with_window(2001)), Err(ConfigError::InvalidSettings(_))));
+        assert_eq!(manager.get_settings().multi_press_window_ms, 400);
+
+        manager.save_settings(with_window(100)).unwrap();
+        manager.save_settings(with_window(2000)).unwrap();
+        assert_eq!(manager.get_settings().multi_press_window_ms, 2000);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_http_api_token_generated_on_enable() {
+        let (mut manager, dir) = temp_manager();
+
+        let saved = manager.save_settings(AppSettings::default()).unwrap();
+        assert!(saved.http_api_token.is_empty());
+
+        let saved = manager
+            .save_settings(AppSettings {
+                enable_http_api: true,
+                ..AppSettings::default()
+            })
+            .unwrap();
+        assert_eq!(saved.http_api_token.len(), 32);
+        assert_eq!(manager.get_settings().http_api_token, saved.http_api_token);
+
+        // A user-chosen token is kept
+        let saved = manager
+            .save_settings(AppSettings {
+                enable_http_api: true,
+                http_api_token: "secret".to_string(),
+                ..AppSettings::default()
+            })
+            .unwrap();
+        assert_eq!(saved.http_api_token, "secret");
+
+        assert!(matches!(
+            manager.save_settings(AppSettings {
+                enable_http_api: true,
+                http_api_port: 0,
+                ..AppSettings::default()
+            }),
+            Err(ConfigError::InvalidSettings(_))
+        ));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_remapping_toggle_persists() {
+        let (mut manager, dir) = temp_manager();
+        assert!(manager.is_remapping_enabled());
+
+        manager.set_remapping_enabled(false).unwrap();
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert!(!reloaded.is_remapping_enabled());
+
+        // Settings saved before the switch existed default to enabled
+        let settings: AppSettings = serde_json::from_str(
+            r#"{"startMinimized":false,"startWithWindows":false,"showInTray":true,"theme":"system","logLevel":"info","maxLogEntries":100}"#,
+        )
+        .unwrap();
+        assert!(settings.remapping_enabled);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_usage_stats_count_and_reset() {
+        let (mut manager, dir) = temp_manager();
+        manager
+            .save_settings(AppSettings {
+                usage_stats_enabled: true,
+                ..AppSettings::default()
+            })
+            .unwrap();
+
+        manager.record_usage(&ActionType::LaunchApp, &TriggerType::SinglePress);
+        manager.record_usage(&ActionType::LaunchApp, &TriggerType::DoublePress);
+        manager.record_usage(&ActionType::Hotkey, &TriggerType::SinglePress);
+
+        let stats = manager.get_usage_stats();
+        assert!(stats.since.is_some());
+        assert_eq!(stats.action_types["launch-app"], 2);
+        assert_eq!(stats.action_types["hotkey"], 1);
+        assert_eq!(stats.trigger_types["single-press"], 2);
+        assert_eq!(stats.trigger_types["double-press"], 1);
+
+        // Counts survive a restart
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert_eq!(reloaded.get_usage_stats(), stats);
+
+        manager.reset_usage_stats().unwrap();
+        assert_eq!(manager.get_usage_stats(), UsageStats::default());
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert_eq!(reloaded.get_usage_stats(), UsageStats::default());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rewrite_path_prefix() {
+        assert_eq!(
+            rewrite_path_prefix(r"C:\Tools\app.exe", r"C:\Tools", r"D:\Tools").as_deref(),
+            Some(r"D:\Tools\app.exe")
+        );
+        // Case-insensitive, trailing separators ignored
+        assert_eq!(
+            rewrite_path_prefix(r"c:\tools\bin\x.exe", r"C:\Tools\", r"D:\Apps\").as_deref(),
+            Some(r"D:\Apps\bin\x.exe")
+        );
+        assert_eq!(rewrite_path_prefix(r"C:\Tools", r"C:\Tools", r"D:\Tools").as_deref(), Some(r"D:\Tools"));
+
+        assert_eq!(rewrite_path_prefix(r"C:\Toolbox\app.exe", r"C:\Tools", r"D:\Tools"), None);
+        assert_eq!(rewrite_path_prefix(r"E:\Tools\app.exe", r"C:\Tools", r"D:\Tools"), None);
+        assert_eq!(rewrite_path_prefix("C:", r"C:\Tools", r"D:\Tools"), None);
+    }
+
+    #[test]
+    fn test_rewrite_action_paths() {
+        let (mut manager, dir) = temp_manager();
+        let mut moved = test_binding("1234:5678");
+        moved.action.executable_path = r"C:\Tools\app.exe".to_string();
+        moved.action.working_directory = Some(r"C:\Tools".to_string());
+        let moved = manager.save_binding(moved).unwrap();
+        let mut elsewhere = test_binding("AAAA:BBBB");
+        elsewhere.action.executable_path = r"C:\Program Files\app.exe".to_string();
+        let elsewhere = manager.save_binding(elsewhere).unwrap();
+
+        // Dry run reports without changing anything
+        assert_eq!(manager.rewrite_action_paths(r"C:\Tools", r"D:\Tools", true).unwrap(), 1);
+        assert_eq!(manager.get_binding_by_id(&moved.id).unwrap().action, moved.action);
+
+        assert_eq!(manager.rewrite_action_paths(r"C:\Tools", r"D:\Tools", false).unwrap(), 1);
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        let updated = reloaded.get_binding_by_id(&moved.id).unwrap();
+        assert_eq!(updated.action.executable_path, r"D:\Tools\app.exe");
+        assert_eq!(updated.action.working_directory.as_deref(), Some(r"D:\Tools"));
+        assert_eq!(reloaded.get_binding_by_id(&elsewhere.id).unwrap().action, elsewhere.action);
+
+        // Nothing left to match
+        assert_eq!(manager.rewrite_action_paths(r"C:\Tools", r"D:\Tools", false).unwrap(), 0);
+        assert!(manager.rewrite_action_paths("  ", r"D:\Tools", true).is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_export_import_merge_and_replace() {
+        let (mut source, source_dir) = temp_manager();
+        let exported = source.save_binding(test_binding("1234:5678")).unwrap();
+        let mut settings = source.get_settings();
+        settings.max_log_entries = 42;
+        source.save_settings(settings).unwrap();
+        let file = source_dir.join("export.json");
+        assert_eq!(source.export_config(&file).unwrap(), 1);
+
+        // Merge keeps local bindings and settings
+        let (mut target, target_dir) = temp_manager();
+        let local = target.save_binding(test_binding("AAAA:BBBB")).unwrap();
+        assert_eq!(target.import_config(&file, false).unwrap(), 1);
+        assert_eq!(target.get_all_bindings().len(), 2);
+        assert!(target.get_binding_by_id(&local.id).is_some());
+        assert_ne!(target.get_settings().max_log_entries, 42);
+
+        // Importing again overwrites by ID instead of duplicating
+        assert_eq!(target.import_config(&file, false).unwrap(), 1);
+        assert_eq!(target.get_all_bindings().len(), 2);
+
+        // Replace drops local bindings and takes the exported settings
+        target.import_config(&file, true).unwrap();
+        let reloaded = ConfigManager::with_dir(target_dir.clone()).unwrap();
+        let ids: Vec<_> = reloaded.get_all_bindings().into_iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![exported.id]);
+        assert_eq!(reloaded.get_settings().max_log_entries, 42);
+
+        let _ = fs::remove_dir_all(source_dir);
+        let _ = fs::remove_dir_all(target_dir);
+    }
+
+    #[test]
+    fn test_migrate_unversioned_config() {
+        let (mut manager, dir) = temp_manager();
+        let binding = manager.save_binding(test_binding("1234:5678")).unwrap();
+
+        // Strip the version to look like a file from before versioning
+        let config_path = dir.join("config.json");
+        let mut value: serde_json::Value = serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        value.as_object_mut().unwrap().remove("version");
+        let old = serde_json::to_string(&value).unwrap();
+        fs::write(&config_path, &old).unwrap();
+
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert_eq!(reloaded.data.version, CONFIG_VERSION);
+        assert!(reloaded.get_binding_by_id(&binding.id).is_some());
+        assert_eq!(fs::read_to_string(dir.join("config.json.bak")).unwrap(), old);
+
+        assert!(matches!(migrate(serde_json::json!([])), Err(ConfigError::MigrationFailed(_))));
+        assert!(matches!(
+            migrate(serde_json::json!({ "version": CONFIG_VERSION + 1 })),
+            Err(ConfigError::MigrationFailed(_))
+        ));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_migrate_v1_bindings_into_default_profile() {
+        let binding = test_binding("1234:5678");
+        let v1 = serde_json::json!({
+            "version": 1,
+            "bindings": [binding],
+            "settings": AppSettings::default(),
+        });
+
+        let data = migrate(v1).unwrap();
+        assert_eq!(data.version, CONFIG_VERSION);
+        assert_eq!(data.active_profile_id, DEFAULT_PROFILE_ID);
+        assert_eq!(data.profiles.len(), 1);
+        assert_eq!(data.profiles[0].name, "Default");
+        assert_eq!(data.bindings().len(), 1);
+        assert_eq!(data.bindings()[0].id, binding.id);
+    }
+
+    #[test]
+    fn test_profiles_keep_separate_bindings() {
+        let (mut manager, dir) = temp_manager();
+        let default_binding = manager.save_binding(test_binding("1234:5678")).unwrap();
+
+        let games = manager.create_profile(" Games ").unwrap();
+        assert_eq!(games.name, "Games");
+        assert!(matches!(manager.create_profile("games"), Err(ConfigError::InvalidProfile(_))));
+        assert!(matches!(manager.create_profile("  "), Err(ConfigError::InvalidProfile(_))));
+
+        // Switching changes which bindings are matched
+        manager.switch_profile(&games.id).unwrap();
+        assert!(manager.find_binding(&BindingQuery::device("1234:5678")).is_none());
+        let games_binding = manager.save_binding(test_binding("AAAA:BBBB")).unwrap();
+        assert_eq!(manager.get_configured_device_ids(), vec!["AAAA:BBBB".to_string()]);
+        assert!(matches!(manager.switch_profile("missing"), Err(ConfigError::ProfileNotFound(_))));
+        assert_eq!(manager.switch_profile("missing").unwrap_err().code(), ErrorCode::ProfileNotFound);
+
+        // The active profile and its bindings persist
+        let mut reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert_eq!(reloaded.active_profile_id(), games.id);
+        assert!(reloaded.get_binding_by_id(&games_binding.id).is_some());
+        assert!(reloaded.get_binding_by_id(&default_binding.id).is_none());
+
+        // Deleting the active profile falls back to the remaining one
+        reloaded.delete_profile(&games.id).unwrap();
+        assert_eq!(reloaded.active_profile_id(), DEFAULT_PROFILE_ID);
+        assert!(reloaded.get_binding_by_id(&default_binding.id).is_some());
+        assert!(matches!(
+            reloaded.delete_profile(DEFAULT_PROFILE_ID),
+            Err(ConfigError::InvalidProfile(_))
+        ));
+
+        let profiles = reloaded.get_profiles();
+        assert_eq!(profiles.len(), 1);
+        assert!(profiles[0].active);
+        assert_eq!(profiles[0].binding_count, 1);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_startup_restores_active_profile_or_falls_back_to_default() {
+        let (mut manager, dir) = temp_manager();
+        manager.save_binding(test_binding("1234:5678")).unwrap();
+        let games = manager.create_profile("Games").unwrap();
+        manager.switch_profile(&games.id).unwrap();
+        let games_binding = manager.save_binding(test_binding("AAAA:BBBB")).unwrap();
+
+        // Reopens in the profile used last, matching its bindings
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert_eq!(reloaded.active_profile_id(), games.id);
+        assert!(reloaded.find_binding(&BindingQuery::device("AAAA:BBBB")).is_some());
+
+        // The stored profile was deleted by hand, and Default isn't listed first
+        let path = dir.join("config.json");
+        let mut data: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        data["profiles"].as_array_mut().unwrap().retain(|p| p["id"] != games.id.as_str());
+        data["profiles"].as_array_mut().unwrap().insert(0, serde_json::json!({ "id": "other", "name": "Other", "bindings": [] }));
+        data["active_profile_id"] = games.id.clone().into();
+        fs::write(&path, serde_json::to_string_pretty(&data).unwrap()).unwrap();
+
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert_eq!(reloaded.active_profile_id(), DEFAULT_PROFILE_ID);
+        assert!(reloaded.find_binding(&BindingQuery::device("1234:5678")).is_some());
+        assert!(reloaded.get_binding_by_id(&games_binding.id).is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_profile_rules_validated_and_dropped_with_profile() {
+        let (mut manager, dir) = temp_manager();
+        let photo = manager.create_profile("Photo").unwrap();
+        let rule = |process_name: &str, profile_id: &str| ProfileRule {
+            process_name: process_name.to_string(),
+            profile_id: profile_id.to_string(),
+        };
+
+        assert!(matches!(
+            manager.save_profile_rules(vec![rule("  ", &photo.id)]),
+            Err(ConfigError::InvalidProfile(_))
+        ));
+        assert!(matches!(
+            manager.save_profile_rules(vec![rule("Photoshop.exe", "missing")]),
+            Err(ConfigError::ProfileNotFound(_))
+        ));
+
+        let saved = manager.save_profile_rules(vec![rule(" Photoshop.exe ", &photo.id)]).unwrap();
+        assert_eq!(saved, vec![rule("Photoshop.exe", &photo.id)]);
+        assert_eq!(ConfigManager::with_dir(dir.clone()).unwrap().profile_rules(), &saved[..]);
+
+        manager.delete_profile(&photo.id).unwrap();
+        assert!(manager.profile_rules().is_empty());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_truncated_config_is_preserved() {
+        let (mut manager, dir) = temp_manager();
+        manager.save_binding(test_binding("1234:5678")).unwrap();
+        let config_path = dir.join("config.json");
+        let full = fs::read_to_string(&config_path).unwrap();
+        let truncated = &full[..full.len() / 2];
+        fs::write(&config_path, truncated).unwrap();
+
+        let mut recovered = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert!(recovered.get_all_bindings().is_empty());
+
+        // Saving defaults must not clobber what was on disk
+        recovered.save_binding(test_binding("AAAA:BBBB")).unwrap();
+        let corrupt: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("config.corrupt."))
+            .collect();
+        assert_eq!(corrupt.len(), 1);
+        assert_eq!(fs::read_to_string(corrupt[0].path()).unwrap(), truncated);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_failed_write_keeps_previous_config() {
+        let (mut manager, dir) = temp_manager();
+        let kept = manager.save_binding(test_binding("1234:5678")).unwrap();
+        let config_path = dir.join("config.json");
+        let before = fs::read_to_string(&config_path).unwrap();
+        assert!(!dir.join("config.json.tmp").exists());
+
+        // Make the temp file impossible to create, so the write dies halfway
+        fs::create_dir(dir.join("config.json.tmp")).unwrap();
+        assert!(manager.save_binding(test_binding("AAAA:BBBB")).is_err());
+
+        let after = fs::read_to_string(&config_path).unwrap();
+        assert!(!after.is_empty());
+        assert_eq!(after, before);
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert_eq!(reloaded.get_all_bindings().len(), 1);
+        assert!(reloaded.get_binding_by_id(&kept.id).is_some());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_log_writes_are_batched() {
+        let (mut manager, dir) = temp_manager();
+        let on_disk = |dir: &PathBuf| {
+            fs::read_to_string(dir.join("logs.json"))
+                .ok()
+                .and_then(|c| serde_json::from_str::<Vec<LogEntry>>(&c).ok())
+                .map_or(0, |logs| logs.len())
+        };
+
+        manager.last_log_flush = Instant::now();
+        manager.add_log(LogEntryLevel::Info, "first".to_string(), None);
+        assert_eq!(manager.get_logs(None, LogOrder::NewestFirst).len(), 1);
+        assert_eq!(on_disk(&dir), 0);
+
+        manager.flush_logs().unwrap();
+        assert_eq!(on_disk(&dir), 1);
+
+        // A full batch is written without waiting for the interval
+        manager.last_log_flush = Instant::now();
+        for i in 0..LOG_FLUSH_BATCH {
+            manager.add_log(LogEntryLevel::Info, format!("press {}", i), None);
+        }
+        assert_eq!(on_disk(&dir), LOG_FLUSH_BATCH + 1);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_import_rejects_bad_files() {
+        let (mut manager, dir) = temp_manager();
+        let file = dir.join("export.json");
+        manager.export_config(&file).unwrap();
+
+        let content = fs::read_to_string(&file).unwrap();
+        let newer = content.replacen(
+            &format!("\"schemaVersion\": {}", CONFIG_SCHEMA_VERSION),
+            "\"schemaVersion\": 99",
+            1,
+        );
+        fs::write(&file, newer).unwrap();
+        assert!(matches!(manager.import_config(&file, true), Err(ConfigError::InvalidImport(_))));
+
+        fs::write(&file, "{\"bindings\": []}").unwrap();
+        assert!(matches!(manager.import_config(&file, true), Err(ConfigError::InvalidImport(_))));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_stale_bindings() {
+        let connected = test_binding("AF88:6688");
+        let unplugged = test_binding("1234:5678:1");
+        let gone = test_binding("DEAD:BEEF");
+        let bindings = vec![connected, unplugged.clone(), gone.clone()];
+
+        let stale = stale_bindings(&bindings, &["af88:6688".to_string()], &["1234:5678".to_string()]);
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].id, gone.id);
+
+        // With no history, an unplugged device looks stale too
+        let stale = stale_bindings(&bindings, &["AF88:6688".to_string()], &[]);
+        let ids: Vec<_> = stale.iter().map(|b| b.id.clone()).collect();
+        assert_eq!(ids, vec![unplugged.id, gone.id]);
+    }
+
+    #[test]
+    fn test_disconnected_devices_from_bindings() {
+        let present = test_binding("AF88:6688");
+        let mut serial = test_binding("1234:5678:SN0042");
+        serial.manufacturer = Some("Acme".to_string());
+        let mut serial_double = serial.clone();
+        serial_double.trigger_type = TriggerType::DoublePress;
+        let bindings = vec![present, serial, serial_double];
+
+        let devices = disconnected_devices(&bindings, &[test_device(&interface_device_id("AF88:6688", 1))]);
+        assert_eq!(devices.len(), 1);
+        let device = &devices[0];
+        assert_eq!(device.id, "1234:5678:SN0042");
+        assert_eq!(device.status, DeviceStatus::Disconnected);
+        assert_eq!((device.vendor_id.as_str(), device.product_id.as_str()), ("1234", "5678"));
+        assert_eq!(device.serial_number.as_deref(), Some("SN0042"));
+        assert_eq!(device.manufacturer.as_deref(), Some("Acme"));
+
+        assert!(disconnected_devices(&[test_binding("AF88:6688:MI_01")], &[])[0].serial_number.is_none());
+        let interface = test_binding(&interface_device_id("1234:5678:SN0042", 1));
+        assert_eq!(interface.device_id, "1234:5678:SN0042&MI_01");
+        let devices = disconnected_devices(&[interface], &[]);
+        assert_eq!(devices[0].id, "1234:5678:SN0042&MI_01");
+        assert_eq!(devices[0].serial_number.as_deref(), Some("SN0042"));
+    }
+
+    #[test]
+    fn test_group_bindings_flags_disconnected_devices() {
+        let pedal_single = test_binding("AF88:6688");
+        let mut pedal_double = test_binding("AF88:6688");
+        pedal_double.trigger_type = TriggerType::DoublePress;
+        let unplugged = test_binding("1234:5678");
+        let bindings = vec![pedal_single.clone(), unplugged.clone(), pedal_double.clone()];
+
+        // The connected device is one interface of the bound pedal
+        let interface = test_device(&interface_device_id("AF88:6688", 1));
+        let groups = group_bindings(&bindings, std::slice::from_ref(&interface));
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].device_id, "AF88:6688");
+        assert_eq!(groups[0].status, DeviceStatus::Configured);
+        assert_eq!(groups[0].device.as_ref().unwrap().id, interface.id);
+        let ids: Vec<_> = groups[0].bindings.iter().map(|b| b.id.clone()).collect();
+        assert_eq!(ids, vec![pedal_single.id, pedal_double.id]);
+
+        assert_eq!(groups[1].device_id, "1234:5678");
+        assert_eq!(groups[1].status, DeviceStatus::Disconnected);
+        assert!(groups[1].device.is_none());
+        assert_eq!(groups[1].bindings[0].id, unplugged.id);
+    }
+
+    #[test]
+    fn test_binding_template() {
+        let device = HidDevice {
+            id: "AF88:6688".to_string(),
+            name: "Foot Pedal".to_string(),
+            vendor_id: "AF88".to_string(),
+            product_id: "6688".to_string(),
+            interface_number: 0,
+            total_interfaces: 1,
+            status: DeviceStatus::Connected,
+            manufacturer: None,
+            serial_number: None,
+            consumer_usage: None,
+            raw_report: Vec::new(),
+            is_repeat: false,
+            button_code: None,
+        };
+
+        let template = binding_template(&device);
+        assert!(uuid::Uuid::parse_str(&template.id).is_ok());
+        assert_eq!(template.device_id, "AF88:6688");
+        assert_eq!(template.vendor_id, "AF88");
+        assert_eq!(template.product_id, "6688");
+        assert_eq!(template.trigger_type, TriggerType::SinglePress);
+        assert_eq!(template.action.r#type, ActionType::LaunchApp);
+        assert!(template.action.executable_path.is_empty());
+        assert!(!template.enabled);
+        assert_eq!(template.consumer_usage, None);
+
+        // Each template is a new binding
+        assert_ne!(binding_template(&device).id, template.id);
+
+        // A media key press pre-fills the usage
+        let media_key = HidDevice {
+            consumer_usage: Some(0x00CD),
+            ..device
+        };
+        assert_eq!(binding_template(&media_key).consumer_usage.as_deref(), Some("play-pause"));
+    }
+
+    #[test]
+    fn test_set_device_verbose() {
+        let (mut manager, dir) = temp_manager();
+
+        manager.set_device_verbose("af88:6688", true).unwrap();
+        assert!(manager.is_verbose_device("AF88:6688:1"));
+        assert!(!manager.is_verbose_device("1234:5678"));
+
+        // Turning it on twice keeps one entry; off removes it
+        manager.set_device_verbose("AF88:6688", true).unwrap();
+        assert_eq!(manager.get_settings().verbose_devices, vec!["AF88:6688"]);
+        manager.set_device_verbose("AF88:6688", false).unwrap();
+        assert!(!manager.is_verbose_device("AF88:6688"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_remember_devices() {
+        let (mut manager, dir) = temp_manager();
+
+        assert_eq!(manager.remember_devices(["af88:6688", "1234:5678:1"]).unwrap(), 2);
+        assert_eq!(manager.remember_devices(["AF88:6688", "1234:5678:0"]).unwrap(), 0);
+
+        manager.save_binding(test_binding("1234:5678")).unwrap();
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert!(reloaded.find_stale_bindings(&[]).is_empty());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_select_binding_prefers_matching_manufacturer() {
+        let generic = test_binding("1234:5678");
+        let mut acme = test_binding("1234:5678");
+        acme.manufacturer = Some("Acme".to_string());
+        let bindings = vec![generic.clone(), acme.clone()];
+
+        let query = |manufacturer| BindingQuery {
+            manufacturer,
+            ..BindingQuery::device("1234:5678")
+        };
+
+        let selected = select_binding(&bindings, &query(Some("ACME "))).unwrap();
+        assert_eq!(selected.id, acme.id);
+
+        // Other or unknown manufacturers fall back to the unconstrained binding
+        let selected = select_binding(&bindings, &query(Some("Globex"))).unwrap();
+        assert_eq!(selected.id, generic.id);
+        let selected = select_binding(&bindings, &query(None)).unwrap();
+        assert_eq!(selected.id, generic.id);
+    }
+
+    #[test]
+    fn test_select_binding_constrained_only() {
+        let mut acme = test_binding("1234:5678");
+        acme.manufacturer = Some("Acme".to_string());
+        let bindings = vec![acme];
+
+        let query = |device_id, manufacturer| BindingQuery {
+            manufacturer,
+            ..BindingQuery::device(device_id)
+        };
+
+        assert!(select_binding(&bindings, &query("1234:5678", Some("Acme"))).is_some());
+        assert!(select_binding(&bindings, &query("1234:5678", Some("Globex"))).is_none());
+        assert!(select_binding(&bindings, &query("1234:5678", None)).is_none());
+        assert!(select_binding(&bindings, &query("AAAA:BBBB", Some("Acme"))).is_none());
+    }
+
+    #[test]
+    fn test_select_binding_by_consumer_usage() {
+        let any_key = test_binding("1234:5678");
+        let mut play = test_binding("1234:5678");
+        play.consumer_usage = Some("play-pause".to_string());
+        let mut next = test_binding("1234:5678");
+        next.consumer_usage = Some("next-track".to_string());
+        let bindings = vec![any_key.clone(), play.clone(), next.clone()];
+
+        let query = |consumer_usage| BindingQuery {
+            consumer_usage,
+            ..BindingQuery::device("1234:5678")
+        };
+
+        assert_eq!(select_binding(&bindings, &query(Some("play-pause"))).unwrap().id, play.id);
+        assert_eq!(select_binding(&bindings, &query(Some("next-track"))).unwrap().id, next.id);
+        // Unmapped media keys and plain keyboard input use the device-wide binding
+        assert_eq!(select_binding(&bindings, &query(Some("mute"))).unwrap().id, any_key.id);
+        assert_eq!(select_binding(&bindings, &query(None)).unwrap().id, any_key.id);
+    }
+
+    #[test]
+    fn test_save_binding_keeps_bindings_per_manufacturer() {
+        let (mut manager, dir) = temp_manager();
+        let mut acme = test_binding("1234:5678");
+        acme.manufacturer = Some("Acme".to_string());
+        manager.save_binding(test_binding("1234:5678")).unwrap();
+        manager.save_binding(acme.clone()).unwrap();
+        assert_eq!(manager.get_all_bindings().len(), 2);
+        assert!(manager.has_manufacturer_constraint("1234:5678"));
+
+        // Same device and manufacturer replaces the existing binding
+        let mut replacement = test_binding("1234:5678");
+        replacement.manufacturer = Some("acme".to_string());
+        manager.save_binding(replacement.clone()).unwrap();
+        assert_eq!(manager.get_all_bindings().len(), 2);
+        let query = BindingQuery {
+            manufacturer: Some("Acme"),
+            ..BindingQuery::device("1234:5678")
+        };
+        assert_eq!(manager.find_binding(&query).unwrap().id, replacement.id);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_locked_config_rejects_mutations() {
+        let (mut manager, dir) = temp_manager();
+        let binding = manager.save_binding(test_binding("1234:5678")).unwrap();
+        manager.lock(Some("secret")).unwrap();
+
+        assert!(matches!(manager.save_binding(test_binding("AAAA:BBBB")), Err(ConfigError::Locked)));
+        assert!(matches!(manager.delete_binding(&binding.id), Err(ConfigError::Locked)));
+        assert!(matches!(manager.save_settings(AppSettings::default()), Err(ConfigError::Locked)));
+
+        // Reads still work
+        assert_eq!(manager.get_all_bindings().len(), 1);
+        assert!(manager.get_binding("1234:5678").is_some());
+
+        // The lock survives a restart
+        let reloaded = ConfigManager::with_dir(dir.clone()).unwrap();
+        assert!(reloaded.lock_status().locked);
+        assert!(!reloaded.lock_status().unlocked);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_unlock_allows_mutations_until_relocked() {
+        let (mut manager, dir) = temp_manager();
+        manager.lock(Some("secret")).unwrap();
+
+        assert!(matches!(manager.unlock("wrong"), Err(ConfigError::IncorrectPassphrase)));
+        assert!(manager.save_binding(test_binding("1234:5678")).is_err());
+
+        manager.unlock("secret").unwrap();
+        assert!(manager.save_binding(test_binding("1234:5678")).is_ok());
+        assert!(manager.save_settings(AppSettings::default()).is_ok());
+
+        manager.lock(Some("secret")).unwrap();
+        assert!(matches!(manager.save_settings(AppSettings::default()), Err(ConfigError::Locked)));
+
+        // Removing the lock requires unlocking first
+        assert!(matches!(manager.remove_lock(), Err(ConfigError::Locked)));
+        manager.unlock("secret").unwrap();
+        manager.remove_lock().unwrap();
+        assert!(!manager.lock_status().locked);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_move_to_copies_and_repoints() {
+        let (mut manager, dir) = temp_manager();
+        manager.save_binding(test_binding("1234:5678")).unwrap();
+        manager.add_log(LogEntryLevel::Info, "before move".to_string(), None);
+
+        let new_dir = dir.join("moved");
+        manager.move_to(new_dir.clone(), true).unwrap();
+
+        assert_eq!(manager.config_dir(), new_dir.as_path());
+        assert!(new_dir.join("config.json").exists());
+        assert!(new_dir.join("logs.json").exists());
+        assert!(!dir.join("config.json").exists());
+        assert!(!dir.join("logs.json").exists());
+
+        // Later saves go to the new location
+        manager.save_binding(test_binding("AAAA:BBBB")).unwrap();
+        let reloaded = ConfigManager::with_dir(new_dir).unwrap();
+        assert_eq!(reloaded.get_all_bindings().len(), 2);
+        assert_eq!(reloaded.get_logs(None, LogOrder::NewestFirst)[0].message, "before move");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_config_dir_override_precedence() {
+        let arg = PathBuf::from(r"D:\Portable\config");
+        let env = Some(OsString::from("/srv/buttonremap"));
+
+        assert_eq!(
+            choose_config_dir_override(Some(arg.clone()), env.clone()),
+            Some((arg, ConfigDirSource::Argument))
+        );
+        assert_eq!(
+            choose_config_dir_override(None, env),
+            Some((PathBuf::from("/srv/buttonremap"), ConfigDirSource::Environment))
+        );
+        assert_eq!(choose_config_dir_override(None, Some(OsString::new())), None);
+        assert_eq!(choose_config_dir_override(None, None), None);
+    }
+
+    #[test]
+    fn test_with_dir_creates_missing_override_dir() {
+        let dir = std::env::temp_dir()
+            .join(format!("usb-configurator-test-{}", uuid::Uuid::new_v4()))
+            .join("nested");
+        let mut manager = ConfigManager::with_dir(dir.clone()).unwrap();
+        manager.save_binding(test_binding("1234:5678")).unwrap();
+        manager.add_log(LogEntryLevel::Info, "hello".to_string(), None);
+        manager.flush_logs().unwrap();
+
+        assert!(dir.join("config.json").exists());
+        assert!(dir.join("logs.json").exists());
+        let _ = std::fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn test_reload_if_changed_skips_own_writes() {
+        let (mut manager, dir) = temp_manager();
+        manager.save_binding(test_binding("1234:5678")).unwrap();
+        assert!(!manager.reload_if_changed().unwrap());
+
+        // Another instance adds a binding
+        let mut other = ConfigManager::with_dir(dir.clone()).unwrap();
+        other.save_binding(test_binding("AAAA:BBBB")).unwrap();
+        assert!(manager.reload_if_changed().unwrap());
+        assert_eq!(manager.get_all_bindings().len(), 2);
+        assert!(!manager.reload_if_changed().unwrap());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_reload_keeps_config_when_file_is_invalid() {
+        let (mut manager, dir) = temp_manager();
+        manager.save_binding(test_binding("1234:5678")).unwrap();
+        let config_path = dir.join("config.json");
+        let saved = fs::read_to_string(&config_path).unwrap();
+
+        // A hand edit saved halfway
+        fs::write(&config_path, &saved[..saved.len() / 2]).unwrap();
+        assert!(manager.reload_if_changed().is_err());
+        assert_eq!(manager.get_all_bindings().len(), 1);
+        // Left alone: not renamed, and not retried until it changes again
+        assert!(config_path.exists());
+        assert!(!manager.reload_if_changed().unwrap());
+
+        // Fixing the edit reloads it
+        fs::write(&config_path, saved.replace("\"enabled\": true", "\"enabled\": false")).unwrap();
+        assert!(manager.reload_if_changed().unwrap());
+        assert!(!manager.get_all_bindings()[0].enabled);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_paths_follow_config_dir() {
+        let (mut manager, dir) = temp_manager();
+        let paths = manager.paths();
+        assert_eq!(paths.config_dir, dir.to_string_lossy());
+        assert_eq!(paths.config_file, dir.join("config.json").to_string_lossy());
+        assert_eq!(paths.logs_file, dir.join("logs.json").to_string_lossy());
+        assert_eq!(paths.activity_log, dir.join(ACTIVITY_LOG_FILE).to_string_lossy());
+
+        let new_dir = dir.join("moved");
+        manager.move_to(new_dir.clone(), false).unwrap();
+        assert_eq!(manager.paths().logs_file, new_dir.join("logs.json").to_string_lossy());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_move_to_unwritable_dir_keeps_old_location() {
+        let (mut manager, dir) = temp_manager();
+        manager.save_binding(test_binding("1234:5678")).unwrap();
+
+        // A file where the directory should go can't be created as a directory
+        let blocked = dir.join("blocked");
+        std::fs::write(&blocked, b"not a dir").unwrap();
+        assert!(manager.move_to(blocked, true).is_err());
+
+        assert_eq!(manager.config_dir(), dir.as_path());
+        assert!(dir.join("config.json").exists());
+        manager.save_binding(test_binding("AAAA:BBBB")).unwrap();
+        assert_eq!(ConfigManager::with_dir(dir.clone()).unwrap().get_all_bindings().len(), 2);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_dir_pointer_written_and_cleared() {
+        let (_, dir) = temp_manager();
+        let default_dir = dir.join("default");
+        let moved = dir.join("moved");
+
+        ConfigManager::write_dir_pointer(&default_dir, &moved).unwrap();
+        let pointer = std::fs::read_to_string(default_dir.join(CONFIG_DIR_POINTER)).unwrap();
+        assert_eq!(PathBuf::from(pointer), moved);
+
+        ConfigManager::write_dir_pointer(&default_dir, &default_dir).unwrap();
+        assert!(!default_dir.join(CONFIG_DIR_POINTER).exists());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_get_logs_ordering_with_limit() {
+        let (mut manager, dir) = temp_manager();
+        for i in 0..5 {
+            manager.add_log(LogEntryLevel::Info, format!("entry {}", i), None);
+        }
+
+        let messages = |logs: Vec<LogEntry>| logs.into_iter().map(|l| l.message).collect::<Vec<_>>();
+
+        assert_eq!(
+            messages(manager.get_logs(None, LogOrder::NewestFirst)),
+            vec!["entry 4", "entry 3", "entry 2", "entry 1", "entry 0"]
+        );
+        assert_eq!(
+            messages(manager.get_logs(None, LogOrder::OldestFirst)),
+            vec!["entry 0", "entry 1", "entry 2", "entry 3", "entry 4"]
+        );
+
+        // Limit picks the same entries either way; order only changes presentation
+        assert_eq!(
+            messages(manager.get_logs(Some(2), LogOrder::NewestFirst)),
+            vec!["entry 4", "entry 3"]
+        );
+        assert_eq!(
+            messages(manager.get_logs(Some(2), LogOrder::OldestFirst)),
+            vec!["entry 3", "entry 4"]
+        );
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_activity_log_lines_and_rotation() {
+        let (mut manager, dir) = temp_manager();
+        manager.add_log(LogEntryLevel::Warn, "two\nlines".to_string(), Some("AF88:6688".to_string()));
+        manager.add_log(LogEntryLevel::Info, "no source".to_string(), None);
+
+        let content = fs::read_to_string(dir.join(ACTIVITY_LOG_FILE)).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with(" WARN [AF88:6688] two lines"));
+        assert!(lines[1].ends_with(" INFO [-] no source"));
+
+        // Past the size limit the file moves to .1 and starts over
+        manager
+            .save_settings(AppSettings {
+                max_log_file_bytes: 10,
+                ..AppSettings::default()
+            })
+            .unwrap();
+        manager.add_log(LogEntryLevel::Info, "fresh".to_string(), None);
+        assert_eq!(fs::read_to_string(dir.join("activity.log.1")).unwrap(), content);
+        let fresh = fs::read_to_string(dir.join(ACTIVITY_LOG_FILE)).unwrap();
+        assert_eq!(fresh.lines().count(), 1);
+        assert!(fresh.ends_with(" INFO [-] fresh\n"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_get_logs_limit_independent_of_max_entries() {
+        let (mut manager, dir) = temp_manager();
+        for i in 0..5 {
+            manager.add_log(LogEntryLevel::Info, format!("entry {}", i), None);
+        }
+
+        // Lowering the cap doesn't hide entries that are already stored
+        manager
+            .save_settings(AppSettings {
+                max_log_entries: 2,
+                ..AppSettings::default()
+            })
+            .unwrap();
+
+        assert_eq!(manager.get_logs(None, LogOrder::NewestFirst).len(), 5);
+        let newest = manager.get_logs(Some(3), LogOrder::NewestFirst);
+        assert_eq!(newest.len(), 3);
+        assert_eq!(newest[0].message, "entry 4");
+        assert_eq!(manager.get_logs(Some(50), LogOrder::NewestFirst).len(), 5);
+
+        // The cap still applies when the next entry is added
+        manager.add_log(LogEntryLevel::Info, "entry 5".to_string(), None);
+        assert_eq!(manager.get_logs(Some(50), LogOrder::NewestFirst).len(), 2);
+
+        let _ = fs::remove_dir_all(dir);
     }
 }
@@ -0,0 +1,206 @@
+// ============================================
+// Config Hot Reload
+// Polls config.json and reloads it into the shared ConfigManager when it
+// changes on disk, e.g. after a hand edit or an import by another instance,
+// so the listener doesn't keep running on stale bindings
+// ============================================
+
+use crate::config::ConfigManager;
+use crate::types::LogEntryLevel;
+use crate::AppState;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How often config.json's size and modification time are checked
+const CONFIG_POLL_MS: u64 = 1000;
+
+/// How long config.json must stay unchanged before it's read, so an editor
+/// saving in several steps triggers one reload
+const CONFIG_RELOAD_DEBOUNCE_MS: u64 = 500;
+
+/// What's checked to notice a change without reading the file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+/// `None` while the file is missing
+pub fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        len: metadata.len(),
+        modified: metadata.modified().ok(),
+    })
+}
+
+/// Waits out bursts of changes: reports a change once the stamp has held
+/// still for the debounce period
+pub struct ChangeDebouncer {
+    debounce: Duration,
+    last: Option<FileStamp>,
+    changed_at: Option<Instant>, // Set while a change hasn't been reported
+}
+
+impl ChangeDebouncer {
+    pub fn new(debounce: Duration, initial: Option<FileStamp>) -> Self {
+        Self {
+            debounce,
+            last: initial,
+            changed_at: None,
+        }
+    }
+
+    /// Feed the current stamp. Returns true when a change has settled and
+    /// the file should be read.
+    pub fn update(&mut self, stamp: Option<FileStamp>, now: Instant) -> bool {
+        if stamp != self.last {
+            self.last = stamp;
+            self.changed_at = Some(now);
+            return false;
+        }
+        match self.changed_at {
+            Some(changed_at) if now.duration_since(changed_at) >= self.debounce => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Watch config.json on a background thread until shutdown
+pub fn start_config_watcher(config_manager: Arc<Mutex<ConfigManager>>, app: AppHandle) {
+    thread::spawn(move || {
+        let debounce = Duration::from_millis(CONFIG_RELOAD_DEBOUNCE_MS);
+        let mut watched: Option<PathBuf> = None;
+        let mut debouncer = ChangeDebouncer::new(debounce, None);
+
+        while !crate::shutdown::is_shutting_down() {
+            thread::sleep(Duration::from_millis(CONFIG_POLL_MS));
+
+            let Some(path) = config_manager.lock().ok().map(|c| c.config_path().to_path_buf()) else {
+                continue;
+            };
+            let stamp = file_stamp(&path);
+
+            // move_config_dir points us at another file; start over there
+            if watched.as_ref() != Some(&path) {
+                watched = Some(path);
+                debouncer = ChangeDebouncer::new(debounce, stamp);
+                continue;
+            }
+
+            if debouncer.update(stamp, Instant::now()) {
+                reload(&config_manager, &app);
+            }
+        }
+    });
+}
+
+fn reload(config_manager: &Mutex<ConfigManager>, app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let settings = {
+        let Ok(mut config) = config_manager.lock() else {
+            return;
+        };
+        // Our own saves change the file too; those aren't reloaded
+        match config.reload_if_changed() {
+            Ok(false) => return,
+            Ok(true) => config.add_log(
+                LogEntryLevel::Info,
+                "config.json changed on disk and was reloaded".to_string(),
+                Some("Config".to_string()),
+            ),
+            Err(e) => {
+                config.add_log(
+                    LogEntryLevel::Warn,
+                    format!("config.json changed on disk but couldn't be loaded; keeping the current config: {}", e),
+                    Some("Config".to_string()),
+                );
+                return;
+            }
+        }
+        config.get_settings()
+    };
+
+    // Not while holding the config lock: refresh_devices takes hid, then config
+    if let Ok(mut hid) = state.hid_manager.lock() {
+        hid.set_use_serial_in_device_id(settings.use_serial_in_device_id);
+    }
+
+    // Device list status comes from HidManager's configured set
+    if let Err(e) = crate::commands::resync_configured_devices(&state) {
+        log::warn!("Failed to resync configured devices after config reload: {}", e);
+    }
+
+    if let Err(e) = crate::http_api::sync_http_api(app, &settings) {
+        if let Ok(mut config) = config_manager.lock() {
+            config.add_log(LogEntryLevel::Warn, e, Some("System".to_string()));
+        }
+    }
+
+    if let Err(e) = app.emit("config-reloaded", ()) {
+        log::error!("Failed to emit config-reloaded event: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEBOUNCE: Duration = Duration::from_millis(CONFIG_RELOAD_DEBOUNCE_MS);
+
+    fn stamp(len: u64) -> Option<FileStamp> {
+        Some(FileStamp {
+            len,
+            modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(len)),
+        })
+    }
+
+    #[test]
+    fn test_change_reported_once_after_debounce() {
+        let start = Instant::now();
+        let mut debouncer = ChangeDebouncer::new(DEBOUNCE, stamp(10));
+
+        assert!(!debouncer.update(stamp(10), start));
+        assert!(!debouncer.update(stamp(20), start));
+        assert!(!debouncer.update(stamp(20), start + DEBOUNCE / 2));
+        assert!(debouncer.update(stamp(20), start + DEBOUNCE));
+        assert!(!debouncer.update(stamp(20), start + DEBOUNCE * 2));
+    }
+
+    #[test]
+    fn test_burst_of_changes_restarts_debounce() {
+        let start = Instant::now();
+        let step = DEBOUNCE / 4;
+        let mut debouncer = ChangeDebouncer::new(DEBOUNCE, stamp(0));
+
+        for i in 1..=8 {
+            assert!(!debouncer.update(stamp(i as u64), start + step * i));
+        }
+        assert!(!debouncer.update(stamp(8), start + step * 9));
+        assert!(debouncer.update(stamp(8), start + step * 8 + DEBOUNCE));
+    }
+
+    #[test]
+    fn test_deleted_file_counts_as_change() {
+        let start = Instant::now();
+        let mut debouncer = ChangeDebouncer::new(DEBOUNCE, stamp(10));
+        assert!(!debouncer.update(None, start));
+        assert!(debouncer.update(None, start + DEBOUNCE));
+    }
+
+    #[test]
+    fn test_file_stamp() {
+        let path = std::env::temp_dir().join(format!("usb-configurator-stamp-{}.json", uuid::Uuid::new_v4()));
+        assert_eq!(file_stamp(&path), None);
+        fs::write(&path, "{}").unwrap();
+        assert_eq!(file_stamp(&path).unwrap().len, 2);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,87 @@
+// ============================================
+// Action Confirmation
+// Asks for a yes/no before running a binding with confirm_dialog set.
+// No answer within the timeout counts as "no".
+// ============================================
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+/// How long the listener waits for an answer before cancelling
+pub const CONFIRM_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfirmOutcome {
+    Confirmed,
+    Cancelled,
+    TimedOut,
+}
+
+impl ConfirmOutcome {
+    /// Map the dialog answer (or lack of one) to an outcome.
+    /// A dialog that went away without answering is a cancel.
+    pub fn from_answer(answer: Result<bool, RecvTimeoutError>) -> Self {
+        match answer {
+            Ok(true) => ConfirmOutcome::Confirmed,
+            Ok(false) | Err(RecvTimeoutError::Disconnected) => ConfirmOutcome::Cancelled,
+            Err(RecvTimeoutError::Timeout) => ConfirmOutcome::TimedOut,
+        }
+    }
+
+    pub fn should_execute(self) -> bool {
+        self == ConfirmOutcome::Confirmed
+    }
+}
+
+/// Show a native yes/no dialog and block until it's answered or `timeout`
+/// elapses. The dialog can't be closed programmatically, so on timeout it
+/// stays up but a late "Yes" is ignored.
+pub fn confirm_action(app: &AppHandle, action_desc: &str, timeout: Duration) -> ConfirmOutcome {
+    let (tx, rx) = mpsc::channel();
+
+    app.dialog()
+        .message(format!("Run {}?", action_desc))
+        .title("Confirm action")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::YesNo)
+        .show(move |yes| {
+            let _ = tx.send(yes);
+        });
+
+    ConfirmOutcome::from_answer(rx.recv_timeout(timeout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_outcomes() {
+        assert_eq!(ConfirmOutcome::from_answer(Ok(true)), ConfirmOutcome::Confirmed);
+        assert_eq!(ConfirmOutcome::from_answer(Ok(false)), ConfirmOutcome::Cancelled);
+        assert_eq!(
+            ConfirmOutcome::from_answer(Err(RecvTimeoutError::Timeout)),
+            ConfirmOutcome::TimedOut
+        );
+        assert_eq!(
+            ConfirmOutcome::from_answer(Err(RecvTimeoutError::Disconnected)),
+            ConfirmOutcome::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_only_confirmed_executes() {
+        assert!(ConfirmOutcome::Confirmed.should_execute());
+        assert!(!ConfirmOutcome::Cancelled.should_execute());
+        assert!(!ConfirmOutcome::TimedOut.should_execute());
+    }
+
+    #[test]
+    fn test_timeout_without_answer() {
+        let (_tx, rx) = mpsc::channel::<bool>();
+        let outcome = ConfirmOutcome::from_answer(rx.recv_timeout(Duration::from_millis(10)));
+        assert_eq!(outcome, ConfirmOutcome::TimedOut);
+    }
+}
@@ -0,0 +1,93 @@
+// ============================================
+// Consumer Control
+// Media keys and remote controls report on the HID consumer page (0x0C)
+// rather than as keyboard input. This maps those reports to usage IDs and
+// stable identifiers bindings can match on.
+// ============================================
+
+/// Well-known consumer-page usages and the identifiers bindings use for them
+const CONSUMER_USAGES: &[(u16, &str)] = &[
+    (0x0030, "power"),
+    (0x0032, "sleep"),
+    (0x00B0, "play"),
+    (0x00B1, "pause"),
+    (0x00B3, "fast-forward"),
+    (0x00B4, "rewind"),
+    (0x00B5, "next-track"),
+    (0x00B6, "prev-track"),
+    (0x00B7, "stop"),
+    (0x00CD, "play-pause"),
+    (0x00E2, "mute"),
+    (0x00E9, "volume-up"),
+    (0x00EA, "volume-down"),
+    (0x0183, "media-player"),
+    (0x018A, "mail"),
+    (0x0192, "calculator"),
+    (0x0194, "file-explorer"),
+    (0x0221, "browser-search"),
+    (0x0223, "browser-home"),
+    (0x0224, "browser-back"),
+    (0x0225, "browser-forward"),
+    (0x0227, "browser-refresh"),
+    (0x022A, "browser-favorites"),
+];
+
+/// Identifier for a consumer usage: a known name, or "usage-XXXX" (hex)
+pub fn consumer_usage_id(usage: u16) -> String {
+    CONSUMER_USAGES
+        .iter()
+        .find(|(code, _)| *code == usage)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("usage-{:04X}", usage))
+}
+
+/// Extract the pressed usages from one consumer-control input report.
+///
+/// Consumer reports are almost always an array of 16-bit little-endian usage
+/// IDs, optionally preceded by a one-byte report ID. With no descriptor to
+/// go on, an odd length is taken to mean a report ID is present. An all-zero
+/// report is a release and yields nothing.
+pub fn parse_consumer_report(report: &[u8]) -> Vec<u16> {
+    let payload = if report.len() % 2 == 1 { &report[1..] } else { report };
+
+    payload
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .filter(|usage| *usage != 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_report_with_report_id() {
+        // Report ID 2, Play/Pause
+        assert_eq!(parse_consumer_report(&[0x02, 0xCD, 0x00]), vec![0x00CD]);
+        // Report ID 3, Browser Back (two-byte usage)
+        assert_eq!(parse_consumer_report(&[0x03, 0x24, 0x02]), vec![0x0224]);
+    }
+
+    #[test]
+    fn test_parse_report_without_report_id() {
+        assert_eq!(parse_consumer_report(&[0xE9, 0x00]), vec![0x00E9]);
+        // Two keys held at once
+        assert_eq!(parse_consumer_report(&[0xB5, 0x00, 0xE2, 0x00]), vec![0x00B5, 0x00E2]);
+    }
+
+    #[test]
+    fn test_release_report_is_empty() {
+        assert!(parse_consumer_report(&[0x02, 0x00, 0x00]).is_empty());
+        assert!(parse_consumer_report(&[0x00, 0x00]).is_empty());
+        assert!(parse_consumer_report(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_consumer_usage_ids() {
+        assert_eq!(consumer_usage_id(0x00CD), "play-pause");
+        assert_eq!(consumer_usage_id(0x00B5), "next-track");
+        assert_eq!(consumer_usage_id(0x00E9), "volume-up");
+        assert_eq!(consumer_usage_id(0x0ABC), "usage-0ABC");
+    }
+}
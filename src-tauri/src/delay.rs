@@ -0,0 +1,59 @@
+// ============================================
+// Delay Actions
+// A `Delay` step pauses a sequence, e.g. to let a launched app open before
+// a hotkey is sent. `executable_path` holds the duration in milliseconds.
+// ============================================
+
+use std::time::Duration;
+
+/// Longest a single Delay action waits; longer values are capped
+pub const MAX_DELAY_MS: u64 = 60_000;
+
+/// Parse the duration stored in a Delay action ("500" or "500ms")
+pub fn parse_delay_ms(value: &str) -> Result<u64, String> {
+    let trimmed = value.trim().trim_end_matches("ms").trim();
+    trimmed
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid delay: {} (expected milliseconds)", value))
+}
+
+/// Cap a delay at `MAX_DELAY_MS`, also returning whether it was capped
+pub fn cap_delay_ms(ms: u64) -> (u64, bool) {
+    (ms.min(MAX_DELAY_MS), ms > MAX_DELAY_MS)
+}
+
+/// Parse, cap and wait out a Delay action on the current thread. Returns the
+/// milliseconds actually waited and whether the requested value was capped.
+pub fn run_delay(value: &str) -> Result<(u64, bool), String> {
+    let (ms, capped) = cap_delay_ms(parse_delay_ms(value)?);
+    std::thread::sleep(Duration::from_millis(ms));
+    Ok((ms, capped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_parse_delay_ms() {
+        assert_eq!(parse_delay_ms("500"), Ok(500));
+        assert_eq!(parse_delay_ms(" 250ms "), Ok(250));
+        assert!(parse_delay_ms("-5").is_err());
+        assert!(parse_delay_ms("soon").is_err());
+    }
+
+    #[test]
+    fn test_cap_delay_ms() {
+        assert_eq!(cap_delay_ms(1000), (1000, false));
+        assert_eq!(cap_delay_ms(MAX_DELAY_MS), (MAX_DELAY_MS, false));
+        assert_eq!(cap_delay_ms(10 * 60 * 1000), (MAX_DELAY_MS, true));
+    }
+
+    #[test]
+    fn test_run_delay_waits() {
+        let start = Instant::now();
+        assert_eq!(run_delay("20"), Ok((20, false)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,690 @@
+// ============================================
+// Action Execution
+// The one place actions run. The background listener, `test_action` /
+// `test_binding`, the CLI and the HTTP API all go through `ActionExecutor`,
+// so a binding behaves the same however it's triggered. The config lock is
+// only taken to log, never while an action runs.
+// ============================================
+
+use crate::args::parse_arguments;
+use crate::config::ConfigManager;
+use crate::launch;
+use crate::plugins::{self, PluginPayload};
+use crate::power::{self, PowerBranch};
+use crate::script::{self, ScriptShell};
+use crate::types::{
+    ActionConfig, ActionSequence, ActionType, BindingTestResult, BindingTestStep, DeviceBinding, LogEntryLevel,
+    TriggerType,
+};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Log-friendly "Type: target" description of an action
+pub fn describe_action(action: &ActionConfig) -> String {
+    let label = match action.r#type {
+        ActionType::LaunchApp => "Launch App",
+        ActionType::RunScript => "Run Script",
+        ActionType::SystemCommand => "System Command",
+        ActionType::Hotkey => "Hotkey",
+        ActionType::SetVolume => "Set Volume",
+        ActionType::Plugin => "Plugin",
+        ActionType::ClipboardTemplate => "Clipboard Template",
+        ActionType::TextMacro => "Text Macro",
+        ActionType::ScriptBlock => "Script Block",
+        ActionType::MouseMove => "Mouse Move",
+        ActionType::MouseAction => "Mouse Action",
+        ActionType::SetLayer => "Set Layer",
+        ActionType::ClearLayer => "Clear Layer",
+        ActionType::ToggleLayer => "Toggle Layer",
+        ActionType::Sequence => "Sequence",
+        ActionType::Delay => "Delay",
+        ActionType::ShellCapture => "Shell Capture",
+    };
+    format!("{}: {}", label, action.executable_path)
+}
+
+fn is_layer_action(action: &ActionConfig) -> bool {
+    matches!(
+        action.r#type,
+        ActionType::SetLayer | ActionType::ClearLayer | ActionType::ToggleLayer
+    )
+}
+
+/// Whether the caller waits for actions that take a while
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Completion {
+    /// Plugins, script blocks, shell captures and sequences run on their own
+    /// thread and count as succeeded once started. For the listener, which
+    /// mustn't stall on one press.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Detach,
+    /// Everything runs to the end and reports how it went
+    Wait,
+}
+
+/// What an action runs for
+#[derive(Debug, Clone)]
+pub struct ExecutionContext {
+    pub device_id: String,
+    pub trigger: TriggerType,
+    pub binding_id: Option<String>,
+    /// Pressed button, passed on to plugins
+    pub button: Option<String>,
+    /// Log source: the device ID for presses, otherwise "Test", "CLI" or "HTTP"
+    pub source: String,
+    pub completion: Completion,
+}
+
+impl ExecutionContext {
+    /// Context for running a saved binding outside the listener
+    pub fn for_binding(binding: &DeviceBinding, source: &str) -> Self {
+        Self {
+            device_id: binding.device_id.clone(),
+            trigger: binding.trigger_type.clone(),
+            binding_id: Some(binding.id.clone()),
+            button: plugins::button_id(binding.button_code, binding.consumer_usage.as_deref()),
+            source: source.to_string(),
+            completion: Completion::Wait,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ActionExecutor {
+    config_manager: Arc<Mutex<ConfigManager>>,
+}
+
+impl ActionExecutor {
+    pub fn new(config_manager: Arc<Mutex<ConfigManager>>) -> Self {
+        Self { config_manager }
+    }
+
+    fn log(&self, level: LogEntryLevel, message: String, source: &str) {
+        if let Ok(mut config) = self.config_manager.lock() {
+            config.add_log(level, message, Some(source.to_string()));
+        }
+    }
+
+    /// Log `result` as success or error and pass it on
+    fn log_result<T>(
+        &self,
+        result: Result<T, String>,
+        source: &str,
+        success: impl FnOnce(&T) -> String,
+        failure: impl FnOnce(&str) -> String,
+    ) -> Result<(), String> {
+        match result {
+            Ok(value) => {
+                self.log(LogEntryLevel::Success, success(&value), source);
+                Ok(())
+            }
+            Err(e) => {
+                self.log(LogEntryLevel::Error, failure(&e), source);
+                Err(e)
+            }
+        }
+    }
+
+    /// Run an action and log the outcome. `Ok` means it succeeded or, for
+    /// work detached to a background thread, was started. Layer actions are
+    /// listener state and are applied by the caller; here they do nothing.
+    pub fn execute_action(&self, action: &ActionConfig, context: &ExecutionContext) -> Result<(), String> {
+        log::info!("Executing: {} {}", action.executable_path, action.arguments);
+        let source = context.source.as_str();
+
+        if !cfg!(target_os = "windows") && launch::wants_elevation(action) {
+            self.log(
+                LogEntryLevel::Warn,
+                format!("Run as administrator is only supported on Windows; launching {} normally", action.executable_path),
+                source,
+            );
+        }
+
+        let spawned = match action.r#type {
+            ActionType::LaunchApp | ActionType::RunScript | ActionType::SystemCommand
+                if cfg!(target_os = "windows") && launch::wants_elevation(action) =>
+            {
+                self.log(
+                    LogEntryLevel::Info,
+                    format!("Elevation requested for {}", action.executable_path),
+                    source,
+                );
+                return self.log_result(
+                    launch::launch_elevated(action),
+                    source,
+                    |_| format!("Elevated launch accepted by the shell: {}", action.executable_path),
+                    |e| format!("Elevated launch failed: {}", e),
+                );
+            }
+            ActionType::LaunchApp if launch::needs_window_state(action.window_state) => {
+                let result = launch::working_directory(action)
+                    .map_err(|e| e.to_string())
+                    .and_then(|directory| {
+                        launch::launch_with_window_state(
+                            &action.executable_path,
+                            &action.arguments,
+                            directory,
+                            action.window_state.unwrap_or_default(),
+                        )
+                    });
+                return self.log_result(
+                    result,
+                    source,
+                    |_| format!("Action executed: {}", action.executable_path),
+                    |e| format!("Action failed: {}", e),
+                );
+            }
+            ActionType::LaunchApp => {
+                // Launch executable directly (supports paths with spaces)
+                let mut cmd = Command::new(&action.executable_path);
+                if !action.arguments.is_empty() {
+                    cmd.args(parse_arguments(&action.arguments));
+                }
+                launch::spawn_with_options(cmd, action)
+            }
+            ActionType::RunScript => {
+                let cmd = if cfg!(target_os = "windows") {
+                    // Run script through cmd with proper quoting
+                    let quoted_path = format!("\"{}\"", action.executable_path);
+                    let mut cmd = Command::new("cmd");
+                    cmd.args(["/C", &quoted_path]).args(parse_arguments(&action.arguments));
+                    cmd
+                } else {
+                    let mut cmd = Command::new(&action.executable_path);
+                    cmd.args(parse_arguments(&action.arguments));
+                    cmd
+                };
+                launch::spawn_with_options(cmd, action)
+            }
+            ActionType::SystemCommand => launch::spawn_with_options(crate::shell::shell_command(action), action),
+            ActionType::Hotkey => {
+                let result = crate::hotkey::execute_hotkey_to_window(
+                    &action.executable_path,
+                    action.target_window.as_deref(),
+                    action.hold_ms,
+                );
+                return self.log_result(
+                    result,
+                    source,
+                    |_| format!("Hotkey executed: {}", action.executable_path),
+                    |e| format!("Hotkey failed: {}", e),
+                );
+            }
+            ActionType::SetVolume => {
+                let result = crate::volume::parse_volume_percent(&action.executable_path)
+                    .and_then(crate::volume::set_volume_percent);
+                return self.log_result(
+                    result,
+                    source,
+                    |(before, after)| format!("Volume set: {}% -> {}%", before, after),
+                    |e| format!("Volume change failed: {}", e),
+                );
+            }
+            ActionType::Delay => {
+                // Sleeps on the calling thread without holding the config lock
+                let result = crate::delay::run_delay(&action.executable_path);
+                if let Ok((_, true)) = result {
+                    self.log(
+                        LogEntryLevel::Warn,
+                        format!(
+                            "Delay of {}ms capped at {}ms",
+                            action.executable_path.trim(),
+                            crate::delay::MAX_DELAY_MS
+                        ),
+                        source,
+                    );
+                }
+                return match result {
+                    Ok((ms, _)) => {
+                        self.log(LogEntryLevel::Success, format!("Waited {}ms", ms), source);
+                        Ok(())
+                    }
+                    Err(e) => {
+                        self.log(LogEntryLevel::Error, e.clone(), source);
+                        Err(e)
+                    }
+                };
+            }
+            ActionType::MouseMove => {
+                let result = crate::mouse::parse_mouse_target(&action.executable_path, &action.arguments)
+                    .and_then(crate::mouse::move_mouse);
+                return self.log_result(
+                    result,
+                    source,
+                    |_| format!("Mouse moved to {}", action.executable_path),
+                    |e| format!("Mouse move failed: {}", e),
+                );
+            }
+            ActionType::MouseAction => {
+                let result = crate::mouse::parse_mouse_action(&action.executable_path)
+                    .and_then(crate::mouse::perform_mouse_action);
+                return self.log_result(
+                    result,
+                    source,
+                    |_| format!("Mouse action: {}", action.executable_path),
+                    |e| format!("Mouse action failed: {}", e),
+                );
+            }
+            ActionType::TextMacro => {
+                let result = crate::hotkey::type_text(&action.executable_path);
+                return self.log_result(
+                    result,
+                    source,
+                    |_| format!("Text macro typed ({} characters)", action.executable_path.chars().count()),
+                    |e| format!("Text macro failed: {}", e),
+                );
+            }
+            ActionType::ClipboardTemplate => {
+                let text = match crate::clipboard::read_clipboard_text() {
+                    Ok(Some(text)) => text,
+                    Ok(None) => {
+                        self.log(
+                            LogEntryLevel::Warn,
+                            "Clipboard is empty or not text - nothing typed".to_string(),
+                            source,
+                        );
+                        return Err("Clipboard is empty or not text".to_string());
+                    }
+                    Err(e) => {
+                        self.log(LogEntryLevel::Error, format!("Clipboard template failed: {}", e), source);
+                        return Err(e);
+                    }
+                };
+                let output = crate::clipboard::apply_template(&action.executable_path, &text);
+                let count = output.chars().count();
+                return self.log_result(
+                    crate::hotkey::type_text(&output),
+                    source,
+                    |_| format!("Clipboard template typed ({} characters)", count),
+                    |e| format!("Clipboard template failed: {}", e),
+                );
+            }
+            ActionType::Plugin => {
+                let plugin = self.config_manager.lock().ok().and_then(|config| {
+                    plugins::find_plugin(&config.get_plugins(), &action.executable_path).cloned()
+                });
+                let Some(plugin) = plugin else {
+                    let error = format!("Plugin not registered: {}", action.executable_path);
+                    self.log(LogEntryLevel::Error, error.clone(), source);
+                    return Err(error);
+                };
+                let payload = PluginPayload::new(
+                    &context.device_id,
+                    context.trigger.clone(),
+                    context.binding_id.clone(),
+                    &action.arguments,
+                )
+                .with_button(context.button.clone());
+                let timeout = plugins::plugin_timeout(action);
+
+                let executor = self.clone();
+                let source = source.to_string();
+                return self.finish(context.completion, move || {
+                    executor.log_result(
+                        plugins::run_plugin(&plugin, &payload, timeout),
+                        &source,
+                        |output| format!("Plugin {} succeeded: {}", plugin.name, output),
+                        str::to_string,
+                    )
+                });
+            }
+            ActionType::ScriptBlock => {
+                let action = action.clone();
+                let shell = ScriptShell::from_arguments(&action.arguments);
+                let executor = self.clone();
+                let source = source.to_string();
+                return self.finish(context.completion, move || {
+                    let result = match script::run_script_block(&action) {
+                        Ok(0) => Ok(()),
+                        Ok(code) => Err(format!("Script block exited with code {}", code)),
+                        Err(e) => Err(format!("Script block failed: {}", e)),
+                    };
+                    executor.log_result(result, &source, |_| format!("Script block finished ({:?})", shell), str::to_string)
+                });
+            }
+            ActionType::ShellCapture => {
+                let action = action.clone();
+                let executor = self.clone();
+                let source = source.to_string();
+                return self.finish(context.completion, move || {
+                    // A non-zero exit fails, with the command's output as the error
+                    let result = crate::shell::run_shell_capture(&action);
+                    let succeeded = result.as_ref().is_ok_and(|output| output.succeeded());
+                    let (level, message) = crate::shell::describe_result(&result);
+                    executor.log(level, message.clone(), &source);
+                    if succeeded {
+                        Ok(())
+                    } else {
+                        Err(message)
+                    }
+                });
+            }
+            ActionType::SetLayer | ActionType::ClearLayer | ActionType::ToggleLayer => {
+                // Applied to LayerState by the listener before we get here
+                return Ok(());
+            }
+            ActionType::Sequence => {
+                let Some(sequence) = action.sequence.clone().filter(|s| !s.steps.is_empty()) else {
+                    self.log(LogEntryLevel::Error, "Sequence has no steps".to_string(), source);
+                    return Err("Sequence has no steps".to_string());
+                };
+                let executor = self.clone();
+                let context = context.clone();
+                return self.finish(context.completion, move || {
+                    sequence_result(&sequence, &executor.run_sequence(&sequence, &context))
+                });
+            }
+        };
+
+        let result = match spawned {
+            Ok(child) => {
+                self.log(
+                    LogEntryLevel::Success,
+                    format!("Action executed: {}", action.executable_path),
+                    source,
+                );
+                child
+            }
+            Err(e) => {
+                self.log(LogEntryLevel::Error, format!("Action failed: {}", e), source);
+                return Err(e.to_string());
+            }
+        };
+
+        // Without a timeout the process is left to run on its own
+        if let Some(timeout) = launch::action_timeout(action) {
+            let executor = self.clone();
+            let path = action.executable_path.clone();
+            let source = source.to_string();
+            launch::watch_with_timeout(result, timeout, move |outcome| {
+                let (level, message) = launch::describe_wait_outcome(&path, timeout, &outcome);
+                executor.log(level, message, &source);
+            });
+        }
+        Ok(())
+    }
+
+    /// Run `work` now, or hand it to a background thread when detached
+    fn finish(
+        &self,
+        completion: Completion,
+        work: impl FnOnce() -> Result<(), String> + Send + 'static,
+    ) -> Result<(), String> {
+        match completion {
+            Completion::Wait => work(),
+            Completion::Detach => {
+                thread::spawn(move || {
+                    let _ = work(); // Already logged
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// Run a sequence's steps in order, pausing `delay_ms` between them. A
+    /// failed step skips the rest unless `continue_on_error` is set. Returns
+    /// the outcome of each step that ran.
+    pub fn run_sequence(&self, sequence: &ActionSequence, context: &ExecutionContext) -> Vec<Result<(), String>> {
+        let total = sequence.steps.len();
+        let mut outcomes = Vec::new();
+        for (index, step) in sequence.steps.iter().enumerate() {
+            if index > 0 && sequence.delay_ms > 0 {
+                thread::sleep(Duration::from_millis(sequence.delay_ms));
+            }
+
+            let outcome = if step.r#type == ActionType::Sequence {
+                let error = format!("Sequence step {} is itself a sequence; nested sequences aren't supported", index + 1);
+                self.log(LogEntryLevel::Error, error.clone(), &context.source);
+                Err(error)
+            } else {
+                self.execute_action(step, context)
+            };
+            let failed = outcome.is_err();
+            outcomes.push(outcome);
+
+            if failed && !sequence.continue_on_error {
+                self.log(
+                    LogEntryLevel::Warn,
+                    format!("Sequence stopped after step {} of {} failed", index + 1, total),
+                    &context.source,
+                );
+                break;
+            }
+        }
+        outcomes
+    }
+
+    /// The action a binding runs right now given its power condition, or
+    /// `None` if it's skipped. Logs the branch taken when there's a condition.
+    pub fn resolve_action(config: &mut ConfigManager, binding: &DeviceBinding, source: &str) -> Option<ActionConfig> {
+        let power_source = power::current_power_source();
+        let (action, message) = match power::resolve_power_branch(binding.power_condition.as_ref(), power_source.clone()) {
+            PowerBranch::Primary if binding.power_condition.is_none() => return Some(binding.action.clone()),
+            PowerBranch::Primary => (Some(binding.action.clone()), "running primary action"),
+            PowerBranch::Alternate(alternate) => (Some(alternate.clone()), "running alternate action"),
+            PowerBranch::Skip => (None, "action skipped"),
+        };
+        config.add_log(
+            LogEntryLevel::Info,
+            format!("Power {:?}: {}", power_source, message),
+            Some(source.to_string()),
+        );
+        action
+    }
+
+    /// Run a saved binding's action once the way a matching press would,
+    /// minus the press-dependent gates (trigger, cooldown, arming,
+    /// confirmation). Power conditions are resolved as usual and everything,
+    /// sequences included, runs to the end before this returns. Failure
+    /// details are in the log under `source`.
+    pub fn run_binding(&self, binding: &DeviceBinding, source: &str) -> BindingTestResult {
+        let context = ExecutionContext::for_binding(binding, source);
+        let step = |step: usize, action: &ActionConfig, outcome: Result<(), String>| BindingTestStep {
+            step,
+            description: describe_action(action),
+            succeeded: outcome.is_ok(),
+            message: outcome.err(),
+        };
+
+        let action = match self.config_manager.lock() {
+            Ok(mut config) => Self::resolve_action(&mut config, binding, source),
+            Err(e) => {
+                return BindingTestResult {
+                    binding_id: binding.id.clone(),
+                    succeeded: false,
+                    steps: vec![step(1, &binding.action, Err(e.to_string()))],
+                }
+            }
+        };
+        let Some(action) = action else {
+            return BindingTestResult {
+                binding_id: binding.id.clone(),
+                succeeded: true,
+                steps: vec![BindingTestStep {
+                    message: Some("Skipped: power condition not met".to_string()),
+                    ..step(1, &binding.action, Ok(()))
+                }],
+            };
+        };
+
+        let steps = match (&action.r#type, &action.sequence) {
+            (ActionType::Sequence, Some(sequence)) if !sequence.steps.is_empty() => self
+                .run_sequence(sequence, &context)
+                .into_iter()
+                .zip(&sequence.steps)
+                .enumerate()
+                .map(|(index, (outcome, action))| step(index + 1, action, outcome))
+                .collect(),
+            _ if is_layer_action(&action) => vec![BindingTestStep {
+                message: Some("Layer changes only apply inside the background listener".to_string()),
+                ..step(1, &action, Ok(()))
+            }],
+            _ => vec![step(1, &action, self.execute_action(&action, &context))],
+        };
+
+        BindingTestResult {
+            binding_id: binding.id.clone(),
+            succeeded: steps.iter().all(|s| s.succeeded),
+            steps,
+        }
+    }
+
+    /// Run one action for `test_action` and wait for it. Layer actions are
+    /// refused since there are no layers outside the listener.
+    pub fn test_action(&self, action: &ActionConfig) -> Result<(), String> {
+        self.log(
+            LogEntryLevel::Info,
+            format!("Testing action: {} {}", action.executable_path, action.arguments),
+            "Test",
+        );
+        if is_layer_action(action) {
+            return Err("Layer actions only take effect in the background listener".to_string());
+        }
+        let context = ExecutionContext {
+            device_id: "0000:0000".to_string(),
+            trigger: TriggerType::SinglePress,
+            binding_id: None,
+            button: None,
+            source: "Test".to_string(),
+            completion: Completion::Wait,
+        };
+        self.execute_action(action, &context)
+    }
+}
+
+/// A waited-for sequence's overall outcome, naming the steps that failed
+fn sequence_result(sequence: &ActionSequence, outcomes: &[Result<(), String>]) -> Result<(), String> {
+    let failures: Vec<String> = outcomes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, outcome)| outcome.as_ref().err().map(|e| format!("step {}: {}", index + 1, e)))
+        .collect();
+    if failures.is_empty() {
+        Ok(())
+    } else if sequence.continue_on_error {
+        Err(format!("Sequence finished with errors: {}", failures.join("; ")))
+    } else {
+        Err(format!(
+            "Sequence stopped after step {} of {} failed: {}",
+            outcomes.len(),
+            sequence.steps.len(),
+            failures.join("; ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LogOrder;
+
+    fn delay(ms: &str) -> ActionConfig {
+        ActionConfig {
+            r#type: ActionType::Delay,
+            executable_path: ms.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn sequence_action(steps: Vec<ActionConfig>, continue_on_error: bool) -> (ActionConfig, ActionSequence) {
+        let sequence = ActionSequence {
+            steps,
+            delay_ms: 0,
+            continue_on_error,
+        };
+        let mut action = delay("");
+        action.r#type = ActionType::Sequence;
+        action.sequence = Some(sequence.clone());
+        (action, sequence)
+    }
+
+    fn executor() -> (ActionExecutor, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("usb-configurator-test-{}", uuid::Uuid::new_v4()));
+        let config = ConfigManager::with_dir(dir.clone()).unwrap();
+        (ActionExecutor::new(Arc::new(Mutex::new(config))), dir)
+    }
+
+    #[test]
+    fn test_sequence_with_delay_runs_in_order() {
+        let (executor, dir) = executor();
+        let binding = DeviceBinding::new(
+            "1234:5678".to_string(),
+            "1234".to_string(),
+            "5678".to_string(),
+            TriggerType::SinglePress,
+            sequence_action(vec![delay("40"), delay("10")], false).0,
+        );
+
+        let start = std::time::Instant::now();
+        let result = executor.run_binding(&binding, "Test");
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        assert!(result.succeeded);
+        let descriptions: Vec<_> = result.steps.iter().map(|s| s.description.as_str()).collect();
+        assert_eq!(descriptions, vec!["Delay: 40", "Delay: 10"]);
+
+        let waits: Vec<_> = executor
+            .config_manager
+            .lock()
+            .unwrap()
+            .get_logs(None, LogOrder::OldestFirst)
+            .into_iter()
+            .map(|entry| entry.message)
+            .filter(|message| message.starts_with("Waited"))
+            .collect();
+        assert_eq!(waits, vec!["Waited 40ms", "Waited 10ms"]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_failed_step_stops_sequence_unless_told_to_continue() {
+        let (executor, dir) = executor();
+        let context = ExecutionContext {
+            device_id: "1234:5678".to_string(),
+            trigger: TriggerType::SinglePress,
+            binding_id: None,
+            button: None,
+            source: "Test".to_string(),
+            completion: Completion::Wait,
+        };
+        let steps = vec![delay("not a number"), delay("0")];
+
+        let (stopping, _) = sequence_action(steps.clone(), false);
+        let error = executor.execute_action(&stopping, &context).unwrap_err();
+        assert!(error.starts_with("Sequence stopped after step 1 of 2 failed"), "{}", error);
+
+        let (continuing, sequence) = sequence_action(steps, true);
+        let outcomes = executor.run_sequence(&sequence, &context);
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes[1].is_ok());
+        let error = executor.execute_action(&continuing, &context).unwrap_err();
+        assert!(error.starts_with("Sequence finished with errors: step 1:"), "{}", error);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_layer_action_only_applies_in_listener() {
+        let (executor, dir) = executor();
+        let mut action = delay("");
+        action.r#type = ActionType::SetLayer;
+        action.executable_path = "Fn".to_string();
+        assert!(executor.test_action(&action).is_err());
+
+        let binding = DeviceBinding::new(
+            "1234:5678".to_string(),
+            "1234".to_string(),
+            "5678".to_string(),
+            TriggerType::SinglePress,
+            action,
+        );
+        let result = executor.run_binding(&binding, "CLI");
+        assert!(result.succeeded);
+        assert!(result.steps[0].message.as_deref().unwrap().contains("background listener"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
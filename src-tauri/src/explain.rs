@@ -0,0 +1,206 @@
+// ============================================
+// Binding Explanations
+// Answers "why didn't my binding fire?" by resolving each of a device's
+// bindings against the current state, in the order the listener tries them
+// ============================================
+
+use crate::config::{constraint_matches, evaluation_order};
+use crate::layers::required_layer;
+use crate::power::{resolve_power_branch, PowerBranch};
+use crate::types::{
+    BindingExplanation, ConditionCheck, ConditionState, DeviceBinding, DeviceExplanation, PowerSource, TriggerType,
+};
+
+/// Current state the conditions are resolved against
+#[derive(Debug, Clone)]
+pub struct ExplainContext {
+    pub manufacturer: Option<String>,
+    pub active_layers: Vec<String>,
+    pub power_source: PowerSource,
+}
+
+pub fn explain_device(bindings: &[DeviceBinding], device_id: &str, context: &ExplainContext) -> DeviceExplanation {
+    DeviceExplanation {
+        device_id: device_id.to_string(),
+        manufacturer: context.manufacturer.clone(),
+        bindings: evaluation_order(bindings, device_id)
+            .into_iter()
+            .map(|binding| explain_binding(binding, context))
+            .collect(),
+    }
+}
+
+fn explain_binding(binding: &DeviceBinding, context: &ExplainContext) -> BindingExplanation {
+    let mut conditions = vec![check(
+        "enabled",
+        binding.enabled,
+        if binding.enabled { "binding is enabled" } else { "binding is disabled" }.to_string(),
+    )];
+
+    if let Some(manufacturer) = &binding.manufacturer {
+        conditions.push(check(
+            "manufacturer",
+            constraint_matches(Some(manufacturer), context.manufacturer.as_deref()),
+            format!(
+                "requires \"{}\", device reports {}",
+                manufacturer,
+                context.manufacturer.as_deref().map(|m| format!("\"{}\"", m)).unwrap_or_else(|| "none".to_string())
+            ),
+        ));
+    }
+
+    if let Some(usage) = &binding.consumer_usage {
+        conditions.push(depends_on_input("consumer-usage", format!("only the {} key", usage)));
+    }
+
+    if let Some(code) = binding.button_code {
+        conditions.push(depends_on_input("button", format!("only the key with code 0x{:02X}", code)));
+    }
+
+    conditions.push(match binding.trigger_type {
+        TriggerType::SinglePress => depends_on_input("trigger", "fires on a single press".to_string()),
+        TriggerType::DoublePress => depends_on_input("trigger", "fires on a double press".to_string()),
+        TriggerType::TriplePress => depends_on_input("trigger", "fires on a triple press".to_string()),
+        TriggerType::LongPress => check("trigger", false, "long press is not supported by the listener yet".to_string()),
+    });
+
+    if let Some(layer) = required_layer(binding) {
+        let active = context.active_layers.iter().any(|l| l == layer);
+        conditions.push(check(
+            "layer",
+            active,
+            format!("requires layer \"{}\" ({})", layer, if active { "active" } else { "inactive" }),
+        ));
+    }
+
+    if binding.power_condition.is_some() {
+        let source = context.power_source.clone();
+        conditions.push(match resolve_power_branch(binding.power_condition.as_ref(), source.clone()) {
+            PowerBranch::Primary => check("power", true, format!("on {:?} power: primary action runs", source)),
+            PowerBranch::Alternate(_) => check("power", true, format!("on {:?} power: alternate action runs", source)),
+            PowerBranch::Skip => check("power", false, format!("on {:?} power: action is skipped", source)),
+        });
+    }
+
+    if let Some(arm) = &binding.arm_to_fire {
+        conditions.push(depends_on_input(
+            "arm-to-fire",
+            format!("needs a second press within {}s", arm.window_secs),
+        ));
+    }
+
+    if binding.confirm_dialog {
+        conditions.push(depends_on_input("confirm", "asks for confirmation first".to_string()));
+    }
+
+    BindingExplanation {
+        binding_id: binding.id.clone(),
+        can_fire: conditions.iter().all(|c| c.state != ConditionState::Fail),
+        conditions,
+    }
+}
+
+fn check(condition: &str, passed: bool, detail: String) -> ConditionCheck {
+    ConditionCheck {
+        condition: condition.to_string(),
+        state: if passed { ConditionState::Pass } else { ConditionState::Fail },
+        detail,
+    }
+}
+
+fn depends_on_input(condition: &str, detail: String) -> ConditionCheck {
+    ConditionCheck {
+        condition: condition.to_string(),
+        state: ConditionState::DependsOnInput,
+        detail,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ActionConfig, ActionType, PowerCondition};
+
+    fn binding(device_id: &str) -> DeviceBinding {
+        DeviceBinding::new(
+            device_id.to_string(),
+            "AF88".to_string(),
+            "6688".to_string(),
+            TriggerType::SinglePress,
+            ActionConfig {
+                r#type: ActionType::LaunchApp,
+                executable_path: "notepad.exe".to_string(),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn states(explanation: &BindingExplanation) -> Vec<(&str, ConditionState)> {
+        explanation
+            .conditions
+            .iter()
+            .map(|c| (c.condition.as_str(), c.state))
+            .collect()
+    }
+
+    #[test]
+    fn test_explain_device_in_evaluation_order() {
+        let mut generic = binding("AF88:6688");
+        generic.required_layer = Some("fn".to_string());
+        let mut acme = binding("AF88:6688");
+        acme.manufacturer = Some("Acme".to_string());
+        acme.trigger_type = TriggerType::DoublePress;
+        let mut on_ac = binding("AF88:6688");
+        on_ac.enabled = false;
+        on_ac.power_condition = Some(PowerCondition {
+            run_on: PowerSource::Ac,
+            otherwise: None,
+        });
+        let other_device = binding("1234:5678");
+        let bindings = vec![generic.clone(), acme.clone(), on_ac.clone(), other_device];
+
+        let context = ExplainContext {
+            manufacturer: Some("Globex".to_string()),
+            active_layers: vec!["fn".to_string()],
+            power_source: PowerSource::Battery,
+        };
+        let explanation = explain_device(&bindings, "AF88:6688", &context);
+
+        // The manufacturer-constrained binding is tried first, then saved order
+        let order: Vec<_> = explanation.bindings.iter().map(|b| b.binding_id.clone()).collect();
+        assert_eq!(order, vec![acme.id, generic.id, on_ac.id]);
+
+        let [acme, generic, on_ac] = &explanation.bindings[..] else {
+            panic!("expected three explanations");
+        };
+        assert_eq!(
+            states(acme),
+            vec![
+                ("enabled", ConditionState::Pass),
+                ("manufacturer", ConditionState::Fail),
+                ("trigger", ConditionState::DependsOnInput),
+            ]
+        );
+        assert!(!acme.can_fire);
+
+        assert_eq!(
+            states(generic),
+            vec![
+                ("enabled", ConditionState::Pass),
+                ("trigger", ConditionState::DependsOnInput),
+                ("layer", ConditionState::Pass),
+            ]
+        );
+        assert!(generic.can_fire);
+
+        assert_eq!(
+            states(on_ac),
+            vec![
+                ("enabled", ConditionState::Fail),
+                ("trigger", ConditionState::DependsOnInput),
+                ("power", ConditionState::Fail),
+            ]
+        );
+        assert!(!on_ac.can_fire);
+    }
+}
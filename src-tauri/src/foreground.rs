@@ -0,0 +1,224 @@
+// ============================================
+// Foreground-App Profile Switching (Windows)
+// Polls the foreground window's process and activates the profile of the
+// first matching `ProfileRule`
+// ============================================
+
+use crate::config::{ConfigManager, ProfileRule};
+use crate::types::LogEntryLevel;
+use crate::AppState;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+/// How often the foreground window is checked
+const FOREGROUND_POLL_MS: u64 = 250;
+
+/// How long the foreground app must stay put before its profile is applied,
+/// so alt-tabbing through windows doesn't switch on every step
+const PROFILE_SWITCH_DEBOUNCE_MS: u64 = 750;
+
+/// Executable name of the foreground window's process, if it can be read
+/// (elevated processes can't be opened from a normal one)
+pub fn foreground_process_name() -> Option<String> {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() {
+            return None;
+        }
+        crate::hotkey::window_process_name(hwnd)
+    }
+}
+
+/// First rule for `process_name`. Names compare case-insensitively and a
+/// rule may leave off ".exe".
+pub fn matching_rule<'a>(rules: &'a [ProfileRule], process_name: &str) -> Option<&'a ProfileRule> {
+    let process = executable_stem(process_name);
+    rules.iter().find(|r| executable_stem(&r.process_name) == process)
+}
+
+fn executable_stem(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    match name.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => name,
+    }
+}
+
+/// Decides when the foreground app should change the active profile.
+///
+/// A rule's profile is applied once the foreground app has stayed the same
+/// for the debounce period. When no rule matches any more, the profile that
+/// was active before the first rule applied comes back. A profile picked by
+/// hand is left alone until the foreground app changes again.
+pub struct ProfileSwitcher {
+    debounce: Duration,
+    wanted: Option<String>, // Rule profile for the current foreground app
+    since: Instant,
+    settled: bool, // `wanted` has already been acted on
+    base: Option<String>, // Profile to return to when no rule matches
+}
+
+impl ProfileSwitcher {
+    pub fn new(debounce: Duration, now: Instant) -> Self {
+        Self {
+            debounce,
+            wanted: None,
+            since: now,
+            settled: true,
+            base: None,
+        }
+    }
+
+    /// Feed the rule profile for the current foreground app (`None` when no
+    /// rule matches). Returns the profile to activate, if any.
+    pub fn update(&mut self, wanted: Option<&str>, active: &str, now: Instant) -> Option<String> {
+        if wanted != self.wanted.as_deref() {
+            self.wanted = wanted.map(str::to_string);
+            self.since = now;
+            self.settled = false;
+            return None;
+        }
+        if self.settled || now.duration_since(self.since) < self.debounce {
+            return None;
+        }
+        self.settled = true;
+
+        let target = match &self.wanted {
+            Some(profile_id) => {
+                self.base.get_or_insert_with(|| active.to_string());
+                profile_id.clone()
+            }
+            None => self.base.take()?,
+        };
+        (target != active).then_some(target)
+    }
+}
+
+/// Poll the foreground app on a background thread until shutdown
+pub fn start_profile_watcher(config_manager: Arc<Mutex<ConfigManager>>, app: AppHandle) {
+    thread::spawn(move || {
+        let debounce = Duration::from_millis(PROFILE_SWITCH_DEBOUNCE_MS);
+        let mut switcher = ProfileSwitcher::new(debounce, Instant::now());
+
+        while !crate::shutdown::is_shutting_down() {
+            thread::sleep(Duration::from_millis(FOREGROUND_POLL_MS));
+
+            let process = foreground_process_name();
+            let switch = {
+                let Ok(config) = config_manager.lock() else {
+                    continue;
+                };
+                if config.profile_rules().is_empty() {
+                    continue;
+                }
+                let wanted = process
+                    .as_deref()
+                    .and_then(|p| matching_rule(config.profile_rules(), p))
+                    .map(|r| r.profile_id.as_str());
+                switcher.update(wanted, config.active_profile_id(), Instant::now())
+            };
+
+            if let Some(profile_id) = switch {
+                switch_to(&config_manager, &app, &profile_id, process.as_deref());
+            }
+        }
+    });
+}
+
+fn switch_to(config_manager: &Mutex<ConfigManager>, app: &AppHandle, profile_id: &str, process: Option<&str>) {
+    {
+        let Ok(mut config) = config_manager.lock() else {
+            return;
+        };
+        if let Err(e) = config.activate_profile(profile_id) {
+            log::warn!("Automatic switch to profile {} failed: {}", profile_id, e);
+            return;
+        }
+        config.add_log(
+            LogEntryLevel::Info,
+            format!(
+                "Switched to profile {} (foreground app: {})",
+                profile_id,
+                process.unwrap_or("unknown")
+            ),
+            Some("Config".to_string()),
+        );
+    }
+
+    // Device list status comes from HidManager's configured set
+    if let Err(e) = crate::commands::resync_configured_devices(&app.state::<AppState>()) {
+        log::warn!("Failed to resync configured devices after profile switch: {}", e);
+    }
+
+    if let Err(e) = app.emit("profile-switched", serde_json::json!({
+        "profileId": profile_id,
+        "processName": process,
+    })) {
+        log::error!("Failed to emit profile-switched event: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEBOUNCE: Duration = Duration::from_millis(PROFILE_SWITCH_DEBOUNCE_MS);
+
+    fn rule(process_name: &str, profile_id: &str) -> ProfileRule {
+        ProfileRule {
+            process_name: process_name.to_string(),
+            profile_id: profile_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matching_rule() {
+        let rules = vec![rule("Photoshop.exe", "photo"), rule("code", "editor"), rule("photoshop", "other")];
+        assert_eq!(matching_rule(&rules, "photoshop.EXE").unwrap().profile_id, "photo");
+        assert_eq!(matching_rule(&rules, "Code.exe").unwrap().profile_id, "editor");
+        assert!(matching_rule(&rules, "notepad.exe").is_none());
+        assert!(matching_rule(&rules, "codex.exe").is_none());
+    }
+
+    #[test]
+    fn test_switch_waits_for_debounce_and_returns_to_base() {
+        let start = Instant::now();
+        let mut switcher = ProfileSwitcher::new(DEBOUNCE, start);
+
+        assert_eq!(switcher.update(Some("photo"), "default", start), None);
+        assert_eq!(switcher.update(Some("photo"), "default", start + DEBOUNCE / 2), None);
+        assert_eq!(switcher.update(Some("photo"), "default", start + DEBOUNCE), Some("photo".to_string()));
+        // Applied once, not on every poll
+        assert_eq!(switcher.update(Some("photo"), "photo", start + DEBOUNCE * 2), None);
+
+        let left = start + DEBOUNCE * 3;
+        assert_eq!(switcher.update(None, "photo", left), None);
+        assert_eq!(switcher.update(None, "photo", left + DEBOUNCE), Some("default".to_string()));
+    }
+
+    #[test]
+    fn test_rapid_alt_tab_does_not_switch() {
+        let start = Instant::now();
+        let mut switcher = ProfileSwitcher::new(DEBOUNCE, start);
+        let step = Duration::from_millis(100);
+
+        for i in 0..10 {
+            let wanted = if i % 2 == 0 { Some("photo") } else { None };
+            assert_eq!(switcher.update(wanted, "default", start + step * i), None);
+        }
+    }
+
+    #[test]
+    fn test_manual_switch_kept_until_app_changes() {
+        let start = Instant::now();
+        let mut switcher = ProfileSwitcher::new(DEBOUNCE, start);
+        switcher.update(Some("photo"), "default", start);
+        assert!(switcher.update(Some("photo"), "default", start + DEBOUNCE).is_some());
+
+        // The user picks another profile while the app stays in front
+        assert_eq!(switcher.update(Some("photo"), "games", start + DEBOUNCE * 4), None);
+    }
+}
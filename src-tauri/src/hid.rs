@@ -1,12 +1,16 @@
+use crate::config::{binding_applies, build_device_id, endpoint_device_id};
 use crate::input_monitor::InputMonitor;
-use crate::types::{DeviceStatus, HidDevice, MonitoringState};
-use hidapi::{HidApi, HidDevice as RawHidDevice};
-use std::collections::HashSet;
+use crate::types::{
+    CapturedReport, CodedError, DeviceStatus, ErrorCode, HidDevice, HidInterfaceInfo, HidReportKind, InterfaceActivity,
+    MonitoringState,
+};
+use hidapi::{DeviceInfo, HidApi, HidDevice as RawHidDevice};
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -19,13 +23,44 @@ pub enum HidError {
     OpenError(String),
     #[error("Read error: {0}")]
     ReadError(String),
+    #[error("Write error: {0}")]
+    WriteError(String),
+    #[error("Permission denied: {0}")]
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    PermissionDenied(String),
+}
+
+impl CodedError for HidError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            HidError::InitError(_) => ErrorCode::HidInitFailed,
+            HidError::DeviceNotFound(_) => ErrorCode::DeviceNotFound,
+            HidError::OpenError(_) => ErrorCode::DeviceOpenFailed,
+            HidError::ReadError(_) => ErrorCode::DeviceReadFailed,
+            HidError::WriteError(_) => ErrorCode::DeviceWriteFailed,
+            HidError::PermissionDenied(_) => ErrorCode::PermissionDenied,
+        }
+    }
+}
+
+/// Last device found by "Find by Press", shared with the detection threads
+pub type DetectedDeviceSlot = Arc<Mutex<Option<HidDevice>>>;
+
+/// Store a detection so `get_monitoring_state` can report it to clients
+/// that missed the `monitoring-detected` event
+pub fn record_detected_device(slot: &DetectedDeviceSlot, device: &HidDevice) {
+    if let Ok(mut detected) = slot.lock() {
+        *detected = Some(device.clone());
+    }
 }
 
 /// Result of device refresh, containing both current and disconnected devices
 #[derive(Debug, Clone)]
 pub struct DeviceRefreshResult {
     pub devices: Vec<HidDevice>,
+    pub connected_ids: Vec<String>, // Devices that weren't there last scan (includes reconnections)
     pub disconnected_ids: Vec<String>,
+    pub reconnected_ids: Vec<String>, // Devices that disconnected earlier and are back
 }
 
 pub struct HidManager {
@@ -33,6 +68,10 @@ pub struct HidManager {
     monitoring_active: Arc<AtomicBool>,
     configured_devices: Vec<String>, // Device IDs that have bindings
     previous_devices: HashSet<String>, // Track previously seen device IDs for disconnection detection
+    disconnected_devices: HashSet<String>, // Devices seen disconnecting, for reconnection detection
+    cached_devices: Option<Vec<HidDevice>>, // Last enumerated device list (see list_devices_cached)
+    use_serial_in_device_id: bool, // Build VID:PID:SERIAL IDs (AppSettings::use_serial_in_device_id)
+    detected_device: DetectedDeviceSlot, // Result of the current/last monitoring scan
 }
 
 impl HidManager {
@@ -44,6 +83,10 @@ impl HidManager {
             monitoring_active: Arc::new(AtomicBool::new(false)),
             configured_devices: Vec::new(),
             previous_devices: HashSet::new(),
+            disconnected_devices: HashSet::new(),
+            cached_devices: None,
+            use_serial_in_device_id: false,
+            detected_device: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -51,112 +94,132 @@ impl HidManager {
         // Refresh device list
         self.api.refresh_devices().map_err(|e| HidError::InitError(e.to_string()))?;
 
+        let (devices, current_device_ids) = self.enumerate_devices();
+
+        // Update previous devices for next comparison
+        self.previous_devices = current_device_ids;
+        self.cached_devices = Some(devices.clone());
+
+        Ok(devices)
+    }
+
+    /// Build one `HidDevice` per bindable endpoint (each interface of a
+    /// composite device) from the current `device_list()`, plus the set of
+    /// IDs seen. Call after `refresh_devices`.
+    fn enumerate_devices(&self) -> (Vec<HidDevice>, HashSet<String>) {
+        let interface_counts = interface_counts(&self.api, self.use_serial_in_device_id);
         let mut devices = Vec::new();
         let mut current_device_ids = HashSet::new();
 
         for device_info in self.api.device_list() {
-            let vendor_id = format!("{:04X}", device_info.vendor_id());
-            let product_id = format!("{:04X}", device_info.product_id());
-            let device_id = format!("{}:{}", vendor_id, product_id);
+            let owner_id = owner_device_id(device_info, self.use_serial_in_device_id);
+            let total_interfaces = interface_counts.get(&owner_id).copied().unwrap_or(1);
+            let interface_number = device_info.interface_number() as u8;
+            let device_id = endpoint_device_id(owner_id, interface_number, total_interfaces);
 
             // Track current device IDs
             current_device_ids.insert(device_id.clone());
 
+            // Avoid duplicates (one interface can have several collections)
+            if devices.iter().any(|d: &HidDevice| d.id == device_id) {
+                continue;
+            }
+
             // Determine status based on whether we have a binding
-            let status = if self.configured_devices.contains(&device_id) {
+            let status = if self.is_configured(&device_id) {
                 DeviceStatus::Configured
             } else {
                 DeviceStatus::Connected
             };
 
-            let device = HidDevice {
-                id: device_id,
+            devices.push(HidDevice {
+                total_interfaces,
                 name: device_info
                     .product_string()
                     .unwrap_or("Unknown Device")
                     .to_string(),
-                vendor_id,
-                product_id,
-                interface_number: device_info.interface_number() as u8,
-                total_interfaces: 1, // HidAPI doesn't directly expose this
+                vendor_id: format!("{:04X}", device_info.vendor_id()),
+                product_id: format!("{:04X}", device_info.product_id()),
+                id: device_id,
+                interface_number,
                 status,
                 manufacturer: device_info.manufacturer_string().map(|s| s.to_string()),
                 serial_number: device_info.serial_number().map(|s| s.to_string()),
-            };
-
-            // Avoid duplicates (same VID:PID)
-            if !devices.iter().any(|d: &HidDevice| d.id == device.id) {
-                devices.push(device);
-            }
+                consumer_usage: None,
+                raw_report: Vec::new(),
+                is_repeat: false,
+                button_code: None,
+            });
         }
 
-        // Update previous devices for next comparison
-        self.previous_devices = current_device_ids;
+        (devices, current_device_ids)
+    }
 
-        Ok(devices)
+    /// Return the last enumerated device list without rescanning USB.
+    ///
+    /// `HidApi::refresh_devices` is slow enough to cause UI stutter when polled,
+    /// so this returns whatever the previous `list_devices`/`refresh_devices` call
+    /// saw. The tradeoff is staleness: devices plugged in or removed since then
+    /// won't show up until an explicit refresh. Only the first call (before any
+    /// enumeration has happened) triggers a scan.
+    pub fn list_devices_cached(&mut self) -> Result<Vec<HidDevice>, HidError> {
+        let Some(cached) = &self.cached_devices else {
+            return self.list_devices();
+        };
+
+        // Status can change without a rescan (bindings saved/deleted), so re-derive it
+        Ok(cached
+            .iter()
+            .cloned()
+            .map(|mut device| {
+                device.status = if self.is_configured(&device.id) {
+                    DeviceStatus::Configured
+                } else {
+                    DeviceStatus::Connected
+                };
+                device
+            })
+            .collect())
     }
 
-    /// Refresh devices and detect disconnections
+    /// Refresh devices and detect connections and disconnections
     pub fn refresh_devices_with_disconnections(&mut self) -> Result<DeviceRefreshResult, HidError> {
         // Refresh device list
         self.api.refresh_devices().map_err(|e| HidError::InitError(e.to_string()))?;
 
-        let mut devices = Vec::new();
-        let mut current_device_ids = HashSet::new();
-
-        for device_info in self.api.device_list() {
-            let vendor_id = format!("{:04X}", device_info.vendor_id());
-            let product_id = format!("{:04X}", device_info.product_id());
-            let device_id = format!("{}:{}", vendor_id, product_id);
-
-            // Track current device IDs
-            current_device_ids.insert(device_id.clone());
+        let first_scan = self.cached_devices.is_none();
+        let (devices, current_device_ids) = self.enumerate_devices();
 
-            // Determine status based on whether we have a binding
-            let status = if self.configured_devices.contains(&device_id) {
-                DeviceStatus::Configured
-            } else {
-                DeviceStatus::Connected
-            };
-
-            let device = HidDevice {
-                id: device_id,
-                name: device_info
-                    .product_string()
-                    .unwrap_or("Unknown Device")
-                    .to_string(),
-                vendor_id,
-                product_id,
-                interface_number: device_info.interface_number() as u8,
-                total_interfaces: 1,
-                status,
-                manufacturer: device_info.manufacturer_string().map(|s| s.to_string()),
-                serial_number: device_info.serial_number().map(|s| s.to_string()),
-            };
-
-            // Avoid duplicates (same VID:PID)
-            if !devices.iter().any(|d: &HidDevice| d.id == device.id) {
-                devices.push(device);
-            }
+        let (mut connected_ids, disconnected_ids, reconnected_ids) = track_connection_changes(
+            &self.previous_devices,
+            &current_device_ids,
+            &mut self.disconnected_devices,
+        );
+        // Everything is "new" to the first scan; that's not a plug-in
+        if first_scan {
+            connected_ids.clear();
         }
 
-        // Find disconnected devices (were in previous but not in current)
-        let disconnected_ids: Vec<String> = self.previous_devices
-            .difference(&current_device_ids)
-            .cloned()
-            .collect();
-
+        for id in &connected_ids {
+            log::info!("Device connected: {}", id);
+        }
         // Log disconnections
         for id in &disconnected_ids {
             log::info!("Device disconnected: {}", id);
         }
+        for id in &reconnected_ids {
+            log::info!("Device reconnected: {}", id);
+        }
 
         // Update previous devices for next comparison
         self.previous_devices = current_device_ids;
+        self.cached_devices = Some(devices.clone());
 
         Ok(DeviceRefreshResult {
             devices,
+            connected_ids,
             disconnected_ids,
+            reconnected_ids,
         })
     }
 
@@ -164,45 +227,176 @@ impl HidManager {
         self.list_devices()
     }
 
-    pub fn get_device_info(&self, device_id: &str) -> Result<HidDevice, HidError> {
-        let parts: Vec<&str> = device_id.split(':').collect();
-        if parts.len() != 2 {
+    /// Enumerate every interface hidapi reports, without the VID:PID dedup
+    /// that `list_devices` applies. Intended for "my device isn't detected"
+    /// support reports.
+    pub fn export_device_list(&mut self) -> Result<Vec<HidInterfaceInfo>, HidError> {
+        self.api.refresh_devices().map_err(|e| HidError::InitError(e.to_string()))?;
+
+        let mut interfaces: Vec<HidInterfaceInfo> = self
+            .api
+            .device_list()
+            .map(interface_info_from)
+            .collect();
+        sort_interfaces(&mut interfaces);
+
+        Ok(interfaces)
+    }
+
+    /// Listen on every interface of a VID:PID for `window` and report which
+    /// ones produced input reports. Composite devices often expose several
+    /// interfaces and only one carries the button presses.
+    pub fn detect_active_interface(
+        &mut self,
+        device_id: &str,
+        window: Duration,
+    ) -> Result<Vec<InterfaceActivity>, HidError> {
+        let (vid, pid) = parse_vid_pid(device_id)?;
+        self.api.refresh_devices().map_err(|e| HidError::InitError(e.to_string()))?;
+
+        let mut handles = Vec::new();
+        let mut results = Vec::new();
+        for device_info in self.api.device_list() {
+            if device_info.vendor_id() != vid || device_info.product_id() != pid {
+                continue;
+            }
+            let info = interface_info_from(device_info);
+            match device_info.open_device(&self.api) {
+                Ok(device) => {
+                    // Read all interfaces concurrently so they share one window
+                    handles.push((info, thread::spawn(move || count_reports(&device, window))));
+                }
+                Err(e) => results.push((info, Err(e.to_string()))),
+            }
+        }
+
+        if handles.is_empty() && results.is_empty() {
+            return Err(HidError::DeviceNotFound(device_id.to_string()));
+        }
+
+        for (info, handle) in handles {
+            let result = handle
+                .join()
+                .unwrap_or_else(|_| Err("Read thread panicked".to_string()));
+            results.push((info, result));
+        }
+
+        let activity = aggregate_interface_activity(results);
+        log::info!(
+            "Interface activity for {}: {:?}",
+            device_id,
+            activity.iter().map(|a| (a.interface_number, a.report_count)).collect::<Vec<_>>()
+        );
+        Ok(activity)
+    }
+
+    /// Read every input report from all interfaces of a VID:PID for `window`
+    /// and return them in arrival order, for devices whose presses Raw Input
+    /// doesn't recognize. Fails only if no interface could be read.
+    pub fn capture_reports(&mut self, device_id: &str, window: Duration) -> Result<Vec<CapturedReport>, HidError> {
+        let (vid, pid) = parse_vid_pid(device_id)?;
+        self.api.refresh_devices().map_err(|e| HidError::InitError(e.to_string()))?;
+
+        let started = Instant::now();
+        let mut handles = Vec::new();
+        let mut results = Vec::new();
+        for device_info in self.api.device_list() {
+            if device_info.vendor_id() != vid || device_info.product_id() != pid {
+                continue;
+            }
+            let info = interface_info_from(device_info);
+            match device_info.open_device(&self.api) {
+                Ok(device) => {
+                    handles.push((info, thread::spawn(move || read_reports(&device, window, started))));
+                }
+                Err(e) => results.push((info, Err(e.to_string()))),
+            }
+        }
+
+        if handles.is_empty() && results.is_empty() {
             return Err(HidError::DeviceNotFound(device_id.to_string()));
         }
-        
-        let vid = u16::from_str_radix(parts[0], 16)
-            .map_err(|_| HidError::DeviceNotFound(device_id.to_string()))?;
-        let pid = u16::from_str_radix(parts[1], 16)
-            .map_err(|_| HidError::DeviceNotFound(device_id.to_string()))?;
-        
+
+        for (info, handle) in handles {
+            let result = handle
+                .join()
+                .unwrap_or_else(|_| Err("Read thread panicked".to_string()));
+            results.push((info, result));
+        }
+
+        let reports = merge_captured_reports(results).map_err(HidError::ReadError)?;
+        log::info!("Captured {} report(s) from {} in {}ms", reports.len(), device_id, window.as_millis());
+        Ok(reports)
+    }
+
+    /// Write an output or feature report to a device, e.g. to set LEDs or
+    /// send an init command. `report` starts with the report ID byte (0x00
+    /// for devices without numbered reports). The interface is resolved like
+    /// `get_device_info`; each of its collections is tried in turn, since on
+    /// Windows only the one declaring the report accepts it. Returns the
+    /// number of bytes written.
+    pub fn send_report(&mut self, device_id: &str, report: &[u8], kind: HidReportKind) -> Result<usize, HidError> {
+        self.api.refresh_devices().map_err(|e| HidError::InitError(e.to_string()))?;
+        let target = self.get_device_info(device_id)?;
+
+        let interface_counts = interface_counts(&self.api, self.use_serial_in_device_id);
+        let mut last_error = None;
         for device_info in self.api.device_list() {
-            if device_info.vendor_id() == vid && device_info.product_id() == pid {
-                let vendor_id = format!("{:04X}", device_info.vendor_id());
-                let product_id = format!("{:04X}", device_info.product_id());
-                let id = format!("{}:{}", vendor_id, product_id);
-                
-                return Ok(HidDevice {
-                    id: id.clone(),
-                    name: device_info
-                        .product_string()
-                        .unwrap_or("Unknown Device")
-                        .to_string(),
-                    vendor_id,
-                    product_id,
-                    interface_number: device_info.interface_number() as u8,
-                    total_interfaces: 1,
-                    status: if self.configured_devices.contains(&id) {
-                        DeviceStatus::Configured
-                    } else {
-                        DeviceStatus::Connected
-                    },
-                    manufacturer: device_info.manufacturer_string().map(|s| s.to_string()),
-                    serial_number: device_info.serial_number().map(|s| s.to_string()),
-                });
+            let owner_id = owner_device_id(device_info, self.use_serial_in_device_id);
+            let total_interfaces = interface_counts.get(&owner_id).copied().unwrap_or(1);
+            let endpoint_id = endpoint_device_id(owner_id, device_info.interface_number() as u8, total_interfaces);
+            if endpoint_id != target.id {
+                continue;
+            }
+
+            let device = match device_info.open_device(&self.api) {
+                Ok(device) => device,
+                Err(e) => {
+                    last_error = Some(HidError::OpenError(e.to_string()));
+                    continue;
+                }
+            };
+            let written = match kind {
+                HidReportKind::Output => device.write(report),
+                HidReportKind::Feature => device.send_feature_report(report).map(|_| report.len()),
+            };
+            match written {
+                Ok(written) => {
+                    log::info!(
+                        "Sent {:?} report to {} (usage page {:04X}): {}",
+                        kind,
+                        target.id,
+                        device_info.usage_page(),
+                        format_report_hex(report, report.len())
+                    );
+                    return Ok(written);
+                }
+                Err(e) => last_error = Some(HidError::WriteError(e.to_string())),
             }
         }
-        
-        Err(HidError::DeviceNotFound(device_id.to_string()))
+
+        Err(last_error.unwrap_or_else(|| HidError::DeviceNotFound(device_id.to_string())))
+    }
+
+    /// Look up a connected device by its ID. A device ID without an
+    /// interface (`VID:PID`, `VID:PID:SERIAL`) resolves to the device's
+    /// primary (lowest-numbered) interface.
+    pub fn get_device_info(&self, device_id: &str) -> Result<HidDevice, HidError> {
+        let (devices, _) = self.enumerate_devices();
+        if let Some(device) = devices.iter().find(|d| d.id == device_id) {
+            return Ok(device.clone());
+        }
+
+        // Stable sort keeps enumeration order among equal interface numbers
+        let mut matches: Vec<HidDevice> = devices
+            .into_iter()
+            .filter(|d| binding_applies(device_id, &d.id))
+            .collect();
+        matches.sort_by_key(|d| d.interface_number);
+        matches
+            .into_iter()
+            .next()
+            .ok_or_else(|| HidError::DeviceNotFound(device_id.to_string()))
     }
 
     pub fn set_device_configured(&mut self, device_id: &str) {
@@ -211,11 +405,34 @@ impl HidManager {
         }
     }
 
-    pub fn set_device_unconfigured(&mut self, device_id: &str) {
-        self.configured_devices.retain(|id| id != device_id);
+    /// Whether a device has bindings, either under its own ID or under the
+    /// device or shared `VID:PID` it belongs to
+    pub(crate) fn is_configured(&self, device_id: &str) -> bool {
+        self.configured_devices.iter().any(|id| binding_applies(id, device_id))
+    }
+
+    /// Switch between `VID:PID` and `VID:PID:SERIAL` device IDs. The cached
+    /// list is dropped since every ID in it may have changed.
+    pub fn set_use_serial_in_device_id(&mut self, enabled: bool) {
+        if self.use_serial_in_device_id != enabled {
+            self.use_serial_in_device_id = enabled;
+            self.cached_devices = None;
+            self.previous_devices.clear();
+            self.disconnected_devices.clear();
+        }
+    }
+
+    /// Replace the configured set wholesale, e.g. after bindings were reloaded
+    /// or imported behind our back
+    pub fn resync_configured_devices(&mut self, device_ids: Vec<String>) {
+        self.configured_devices.clear();
+        for device_id in device_ids {
+            self.set_device_configured(&device_id);
+        }
     }
 
     pub fn start_monitoring(&self) -> Result<(), HidError> {
+        self.clear_detected_device();
         self.monitoring_active.store(true, Ordering::SeqCst);
         log::info!("Started HID monitoring mode");
         Ok(())
@@ -226,6 +443,25 @@ impl HidManager {
         log::info!("Stopped HID monitoring mode");
     }
 
+    /// The flag `start_monitoring`/`stop_monitoring` toggle, for monitors
+    /// that should stop together with this one
+    #[cfg_attr(not(any(target_os = "macos", target_os = "linux")), allow(dead_code))]
+    pub fn monitoring_flag(&self) -> Arc<AtomicBool> {
+        self.monitoring_active.clone()
+    }
+
+    /// Where detection threads record what they found
+    pub fn detected_device_slot(&self) -> DetectedDeviceSlot {
+        self.detected_device.clone()
+    }
+
+    /// Forget the previous scan's result
+    pub fn clear_detected_device(&self) {
+        if let Ok(mut detected) = self.detected_device.lock() {
+            *detected = None;
+        }
+    }
+
     pub fn is_monitoring(&self) -> bool {
         self.monitoring_active.load(Ordering::SeqCst)
     }
@@ -233,166 +469,438 @@ impl HidManager {
     pub fn get_monitoring_state(&self) -> MonitoringState {
         MonitoringState {
             is_active: self.is_monitoring(),
-            detected_device: None, // Populated during actual monitoring
+            detected_device: self.detected_device.lock().ok().and_then(|d| d.clone()),
         }
     }
 
-    // This is called from a separate monitoring thread
-    pub fn monitor_for_input<F>(&self, mut callback: F) -> Result<(), HidError>
+    /// Poll devices on a background thread until one sends a report, then
+    /// stop monitoring and pass it to `callback`. `allowed_device_ids`
+    /// (`VID:PID`) limits the scan; `None` polls every openable device.
+    pub fn monitor_for_input<F>(&self, allowed_device_ids: Option<Vec<String>>, mut callback: F) -> Result<(), HidError>
     where
         F: FnMut(HidDevice) + Send + 'static,
     {
         let monitoring = self.monitoring_active.clone();
+        let use_serial = self.use_serial_in_device_id;
 
         thread::spawn(move || {
-            println!("🚀 [RUST-THREAD] ====== THREAD SPAWNED ======");
-            println!("🔵 [RUST-THREAD] HID monitoring thread started");
             log::info!("HID monitoring thread started");
 
-            // List ALL devices to verify XFKEY is visible
-            println!("🔍 [RUST-THREAD] Enumerating all HID devices to find AF88:6688...");
-            match HidApi::new() {
-                Ok(temp_api) => {
-                    let mut found_xfkey = false;
-                    let mut xfkey_count = 0;
-                    for device_info in temp_api.device_list() {
-                        let vid = device_info.vendor_id();
-                        let pid = device_info.product_id();
-                        let name = device_info.product_string().unwrap_or("Unknown");
-                        let interface = device_info.interface_number();
-
-                        if vid == 0xAF88 && pid == 0x6688 {
-                            xfkey_count += 1;
-                            found_xfkey = true;
-                            println!("  ✅ XFKEY #{}: Interface {}", xfkey_count, interface);
-                        } else {
-                            println!("  📋 Device: {:04X}:{:04X} - {} (Interface {})", vid, pid, name, interface);
-                        }
+            let Some(mut api) = open_polling_api(&monitoring) else {
+                return;
+            };
+
+            while monitoring.load(Ordering::SeqCst) {
+                if let Err(e) = api.refresh_devices() {
+                    log::warn!("Failed to refresh HID devices: {}", e);
+                    thread::sleep(Duration::from_millis(50));
+                    continue;
+                }
+
+                let interface_counts = interface_counts(&api, use_serial);
+                for device_info in api.device_list() {
+                    if !device_allowed(allowed_device_ids.as_deref(), device_info.vendor_id(), device_info.product_id()) {
+                        continue;
                     }
-                    if !found_xfkey {
-                        println!("  ❌ XFKEY (AF88:6688) NOT FOUND in device list!");
+
+                    // Skip if monitoring stopped
+                    if !monitoring.load(Ordering::SeqCst) {
+                        log::info!("Monitoring stopped during device iteration");
+                        return;
+                    }
+
+                    let device = match device_info.open_device(&api) {
+                        Ok(device) => device,
+                        Err(e) => {
+                            log::trace!(
+                                "Cannot open {:04X}:{:04X}: {}",
+                                device_info.vendor_id(),
+                                device_info.product_id(),
+                                e
+                            );
+                            continue;
+                        }
+                    };
+
+                    let mut buf = [0u8; 256];
+                    match device.read_timeout(&mut buf, POLL_READ_TIMEOUT_MS) {
+                        Ok(size) if size > 0 => {
+                            let owner_id = owner_device_id(device_info, use_serial);
+                            let total_interfaces = interface_counts.get(&owner_id).copied().unwrap_or(1);
+                            let interface_number = device_info.interface_number() as u8;
+
+                            let detected_device = HidDevice {
+                                id: endpoint_device_id(owner_id, interface_number, total_interfaces),
+                                name: device_info.product_string().unwrap_or("Unknown Device").to_string(),
+                                vendor_id: format!("{:04X}", device_info.vendor_id()),
+                                product_id: format!("{:04X}", device_info.product_id()),
+                                interface_number,
+                                total_interfaces,
+                                status: DeviceStatus::Connected,
+                                manufacturer: device_info.manufacturer_string().map(|s| s.to_string()),
+                                serial_number: device_info.serial_number().map(|s| s.to_string()),
+                                consumer_usage: None,
+                                raw_report: buf[..size].to_vec(),
+                                is_repeat: false,
+                                button_code: None,
+                            };
+
+                            log::info!(
+                                "Input detected from: {} ({}:{}, Interface {})",
+                                detected_device.name,
+                                detected_device.vendor_id,
+                                detected_device.product_id,
+                                detected_device.interface_number
+                            );
+
+                            // Stop monitoring and call callback
+                            monitoring.store(false, Ordering::SeqCst);
+                            callback(detected_device);
+                            return;
+                        }
+                        Ok(_) => {} // No input within the timeout
+                        Err(e) => {
+                            log::trace!(
+                                "Read error on {:04X}:{:04X}: {}",
+                                device_info.vendor_id(),
+                                device_info.product_id(),
+                                e
+                            );
+                        }
                     }
                 }
-                Err(e) => {
-                    println!("  ❌ Failed to enumerate devices: {}", e);
-                }
+
+                thread::sleep(Duration::from_millis(50));
             }
 
-            println!("🔵 [RUST-THREAD] Checking monitoring flag: {}", monitoring.load(Ordering::SeqCst));
+            log::info!("HID monitoring thread stopped normally");
+        });
 
-            while monitoring.load(Ordering::SeqCst) {
-                println!("🔵 [RUST-THREAD] Inside while loop - iteration start");
-                // Create fresh HID API instance for this iteration
-                match HidApi::new() {
-                    Ok(api) => {
-                        let device_count = api.device_list().count();
-                        println!("🔍 [RUST-THREAD] Polling {} HID devices", device_count);
-                        log::debug!("Polling {} HID devices for input", device_count);
+        Ok(())
+    }
+}
 
-                        let mut devices_opened = 0;
-                        let mut devices_read = 0;
+/// Per-device read timeout while polling for input. Every device is read in
+/// turn, so this bounds how long one pass over all devices takes.
+const POLL_READ_TIMEOUT_MS: i32 = 20;
+
+/// ID of the device owning `device_info`, without any interface suffix
+fn owner_device_id(device_info: &DeviceInfo, use_serial: bool) -> String {
+    build_device_id(
+        &format!("{:04X}", device_info.vendor_id()),
+        &format!("{:04X}", device_info.product_id()),
+        device_info.serial_number(),
+        use_serial,
+    )
+}
 
-                        for device_info in api.device_list() {
-                            // FILTER: Only monitor the XFKEY device for testing
-                            if device_info.vendor_id() != 0xAF88 || device_info.product_id() != 0x6688 {
-                                continue;
-                            }
+/// Number of distinct interfaces per device ID in `api.device_list()`
+fn interface_counts(api: &HidApi, use_serial: bool) -> HashMap<String, u8> {
+    count_interfaces(
+        api.device_list()
+            .map(|info| (owner_device_id(info, use_serial), info.interface_number())),
+    )
+}
 
-                            println!("🎯 [RUST-THREAD] Found XFKEY device! Attempting to read...");
+/// Count distinct interface numbers per device ID. hidapi lists one entry
+/// per top-level collection, so an interface can appear more than once.
+fn count_interfaces(entries: impl IntoIterator<Item = (String, i32)>) -> HashMap<String, u8> {
+    let mut interfaces: HashMap<String, HashSet<i32>> = HashMap::new();
+    for (device_id, interface_number) in entries {
+        interfaces.entry(device_id).or_default().insert(interface_number);
+    }
+    interfaces
+        .into_iter()
+        .map(|(id, numbers)| (id, numbers.len().min(u8::MAX as usize) as u8))
+        .collect()
+}
 
-                            // Skip if monitoring stopped
-                            if !monitoring.load(Ordering::SeqCst) {
-                                log::info!("Monitoring stopped during device iteration");
-                                return;
-                            }
+/// The one `HidApi` a polling thread reuses for its whole run, refreshing its
+/// device list each pass. Creating a new `HidApi` per pass re-initialised the
+/// backend on every pass as well as re-enumerating; refreshing skips the
+/// re-initialisation. How much CPU that saves hasn't been measured. On
+/// failure, monitoring is stopped.
+fn open_polling_api(monitoring: &AtomicBool) -> Option<HidApi> {
+    match HidApi::new() {
+        Ok(api) => Some(api),
+        Err(e) => {
+            log::error!("Failed to create HID API: {}", e);
+            monitoring.store(false, Ordering::SeqCst);
+            None
+        }
+    }
+}
 
-                            // Try to open device
-                            match device_info.open_device(&api) {
-                                Ok(device) => {
-                                    devices_opened += 1;
-                                    println!("🎯 [RUST-THREAD] XFKEY device opened successfully!");
-                                    let mut buf = [0u8; 256];  // Larger buffer for XFKEY
+/// Whether `monitor_for_input` should poll a device (`None` allows all)
+fn device_allowed(allowed_device_ids: Option<&[String]>, vendor_id: u16, product_id: u16) -> bool {
+    let Some(allowed) = allowed_device_ids else {
+        return true;
+    };
+    let id = format!("{:04X}:{:04X}", vendor_id, product_id);
+    allowed.iter().any(|a| {
+        a.split(':').take(2).collect::<Vec<_>>().join(":").eq_ignore_ascii_case(&id)
+    })
+}
 
-                                    // Non-blocking read with timeout (500ms for XFKEY)
-                                    println!("🎯 [RUST-THREAD] Waiting for input (500ms timeout)...");
-                                    match device.read_timeout(&mut buf, 500) {
-                                        Ok(size) if size > 0 => {
-                                            devices_read += 1;
-                                            println!("🔥 [RUST-THREAD] ✅ INPUT DETECTED! Read {} bytes from XFKEY!", size);
-                                            // Input detected!
-                                            let vendor_id = format!("{:04X}", device_info.vendor_id());
-                                            let product_id = format!("{:04X}", device_info.product_id());
+/// Compare two enumerations and return (connected, disconnected,
+/// reconnected) device IDs, each sorted. `disconnected` remembers devices
+/// that went away so their return can be told apart from a device that was
+/// simply never seen before.
+fn track_connection_changes(
+    previous: &HashSet<String>,
+    current: &HashSet<String>,
+    disconnected: &mut HashSet<String>,
+) -> (Vec<String>, Vec<String>, Vec<String>) {
+    // In current but not in previous, and the reverse
+    let mut connected_ids: Vec<String> = current.difference(previous).cloned().collect();
+    let mut disconnected_ids: Vec<String> = previous.difference(current).cloned().collect();
+    connected_ids.sort();
+    disconnected_ids.sort();
+
+    let reconnected_ids: Vec<String> = connected_ids
+        .iter()
+        .filter(|id| disconnected.contains(*id))
+        .cloned()
+        .collect();
+
+    for id in &disconnected_ids {
+        disconnected.insert(id.clone());
+    }
+    for id in &reconnected_ids {
+        disconnected.remove(id);
+    }
 
-                                            let detected_device = HidDevice {
-                                                id: format!("{}:{}", vendor_id, product_id),
-                                                name: device_info.product_string().unwrap_or("Unknown Device").to_string(),
-                                                vendor_id,
-                                                product_id,
-                                                interface_number: device_info.interface_number() as u8,
-                                                total_interfaces: 1,
-                                                status: DeviceStatus::Connected,
-                                                manufacturer: device_info.manufacturer_string().map(|s| s.to_string()),
-                                                serial_number: device_info.serial_number().map(|s| s.to_string()),
-                                            };
+    (connected_ids, disconnected_ids, reconnected_ids)
+}
 
-                                            log::info!(
-                                                "Input detected from: {} ({}:{}, Interface {})",
-                                                detected_device.name,
-                                                detected_device.vendor_id,
-                                                detected_device.product_id,
-                                                detected_device.interface_number
-                                            );
+/// Manufacturer string for a VID:PID device ID, read through a fresh hidapi
+/// context. Raw input only reports VID/PID, so the listener uses this to
+/// honour manufacturer-constrained bindings.
+pub fn lookup_manufacturer(device_id: &str) -> Option<String> {
+    let mut parts = device_id.split(':');
+    let vid = u16::from_str_radix(parts.next()?, 16).ok()?;
+    let pid = u16::from_str_radix(parts.next()?, 16).ok()?;
+
+    let api = match HidApi::new() {
+        Ok(api) => api,
+        Err(e) => {
+            log::warn!("Manufacturer lookup for {} failed: {}", device_id, e);
+            return None;
+        }
+    };
 
-                                            // Stop monitoring and call callback
-                                            monitoring.store(false, Ordering::SeqCst);
-                                            callback(detected_device);
-                                            return;
-                                        }
-                                        Ok(_) => {
-                                            println!("⚪ [RUST-THREAD] No input detected (timeout reached)");
-                                            // No input, continue
-                                        }
-                                        Err(e) => {
-                                            println!("❌ [RUST-THREAD] Read error on XFKEY: {}", e);
-                                            log::trace!("Read error on {}:{}: {}",
-                                                device_info.vendor_id(),
-                                                device_info.product_id(),
-                                                e
-                                            );
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    println!("❌ [RUST-THREAD] Cannot open XFKEY device: {}", e);
-                                    log::trace!(
-                                        "Cannot open {}:{}: {}",
-                                        device_info.vendor_id(),
-                                        device_info.product_id(),
-                                        e
-                                    );
-                                }
-                            }
-                        }
+    api.device_list()
+        .filter(|d| d.vendor_id() == vid && d.product_id() == pid)
+        .find_map(|d| d.manufacturer_string().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string))
+}
 
-                        println!("📊 [RUST-THREAD] Devices opened: {}/{}, Devices with input: {}", devices_opened, device_count, devices_read);
-                    }
-                    Err(e) => {
-                        log::error!("Failed to create HID API: {}", e);
-                        monitoring.store(false, Ordering::SeqCst);
-                        return;
-                    }
-                }
+/// VID and PID from the start of a device ID
+fn parse_vid_pid(device_id: &str) -> Result<(u16, u16), HidError> {
+    let parts: Vec<&str> = device_id.split(':').collect();
+    if parts.len() < 2 {
+        return Err(HidError::DeviceNotFound(device_id.to_string()));
+    }
+    let vid = u16::from_str_radix(parts[0], 16)
+        .map_err(|_| HidError::DeviceNotFound(device_id.to_string()))?;
+    let pid = u16::from_str_radix(parts[1], 16)
+        .map_err(|_| HidError::DeviceNotFound(device_id.to_string()))?;
+    Ok((vid, pid))
+}
 
-                thread::sleep(Duration::from_millis(50));
+/// Space-separated uppercase hex, e.g. `01 CD 00`, truncated after `max` bytes
+pub fn format_report_hex(report: &[u8], max: usize) -> String {
+    let hex = report
+        .iter()
+        .take(max)
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if report.len() > max {
+        format!("{} ... ({} bytes total)", hex, report.len())
+    } else {
+        hex
+    }
+}
+
+/// Largest report `parse_report_hex` accepts, report ID byte excluded
+/// (full-speed USB caps a HID report at 1024 bytes)
+pub const MAX_REPORT_BYTES: usize = 1024;
+
+/// Parse user-entered hex such as `01 FF 00`, `0x01,0xff` or `01ff00` into
+/// a report, prefixed with `report_id` (0x00 when the device doesn't number
+/// its reports, as hidapi expects)
+pub fn parse_report_hex(hex: &str, report_id: Option<u8>) -> Result<Vec<u8>, String> {
+    let mut report = vec![report_id.unwrap_or(0)];
+    for token in hex.split(|c: char| c.is_whitespace() || matches!(c, ',' | ':' | '-')) {
+        if token.is_empty() {
+            continue;
+        }
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        if digits.is_empty() || digits.len() % 2 != 0 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid hex byte(s): {}", token));
+        }
+        for i in (0..digits.len()).step_by(2) {
+            // Checked above, so this can't fail
+            report.push(u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or_default());
+        }
+    }
+
+    match report.len() - 1 {
+        0 => Err("Report is empty".to_string()),
+        n if n > MAX_REPORT_BYTES => Err(format!("Report is {} bytes; the maximum is {}", n, MAX_REPORT_BYTES)),
+        _ => Ok(report),
+    }
+}
+
+/// Stop keeping reports from one interface past this many, so a device that
+/// streams constantly can't grow a capture without bound
+const MAX_CAPTURED_REPORTS: usize = 1000;
+
+/// Reports read from an open interface until `window` after `started`, each
+/// with its milliseconds since `started`
+fn read_reports(device: &RawHidDevice, window: Duration, started: Instant) -> Result<Vec<(u64, Vec<u8>)>, String> {
+    let deadline = started + window;
+    let mut buf = [0u8; 256];
+    let mut reports = Vec::new();
+
+    while reports.len() < MAX_CAPTURED_REPORTS {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        match device.read_timeout(&mut buf, remaining.as_millis().max(1) as i32) {
+            Ok(size) if size > 0 => reports.push((started.elapsed().as_millis() as u64, buf[..size].to_vec())),
+            Ok(_) => {}
+            Err(e) if !reports.is_empty() => {
+                log::debug!("Read error after {} report(s): {}", reports.len(), e);
+                break;
             }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(reports)
+}
 
-            log::info!("HID monitoring thread stopped normally");
-        });
+/// Combine per-interface captures into one list in arrival order. An
+/// interface that failed is skipped unless they all did.
+fn merge_captured_reports(
+    results: Vec<(HidInterfaceInfo, Result<Vec<(u64, Vec<u8>)>, String>)>,
+) -> Result<Vec<CapturedReport>, String> {
+    let mut reports = Vec::new();
+    let mut errors = Vec::new();
+    let interfaces = results.len();
+
+    for (info, result) in results {
+        match result {
+            Ok(captured) => reports.extend(captured.into_iter().map(|(elapsed_ms, bytes)| CapturedReport {
+                interface_number: info.interface_number,
+                usage_page: info.usage_page,
+                elapsed_ms,
+                length: bytes.len(),
+                hex: format_report_hex(&bytes, bytes.len()),
+            })),
+            Err(e) => {
+                log::warn!("Couldn't read interface {} ({}): {}", info.interface_number, info.path, e);
+                errors.push(format!("interface {}: {}", info.interface_number, e));
+            }
+        }
+    }
 
-        Ok(())
+    if errors.len() == interfaces {
+        return Err(errors.join("; "));
+    }
+    // Stable sort keeps each interface's own order for equal timestamps
+    reports.sort_by_key(|r| r.elapsed_ms);
+    Ok(reports)
+}
+
+/// Count input reports read from an open interface until `window` elapses
+fn count_reports(device: &RawHidDevice, window: Duration) -> Result<u32, String> {
+    let deadline = Instant::now() + window;
+    let mut buf = [0u8; 256];
+    let mut count = 0;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(count);
+        }
+        match device.read_timeout(&mut buf, remaining.as_millis().max(1) as i32) {
+            Ok(size) if size > 0 => count += 1,
+            Ok(_) => {}
+            Err(e) if count > 0 => {
+                log::debug!("Read error after {} report(s): {}", count, e);
+                return Ok(count);
+            }
+            Err(e) => return Err(e.to_string()),
+        }
     }
 }
 
+/// Turn per-interface read results into activity entries, busiest first.
+/// Every interface tied for the highest non-zero count is recommended.
+fn aggregate_interface_activity(results: Vec<(HidInterfaceInfo, Result<u32, String>)>) -> Vec<InterfaceActivity> {
+    let max_reports = results
+        .iter()
+        .filter_map(|(_, r)| r.as_ref().ok().copied())
+        .max()
+        .unwrap_or(0);
+
+    let mut activity: Vec<InterfaceActivity> = results
+        .into_iter()
+        .map(|(info, result)| {
+            let (report_count, error) = match result {
+                Ok(count) => (count, None),
+                Err(e) => (0, Some(e)),
+            };
+            InterfaceActivity {
+                interface_number: info.interface_number,
+                usage_page: info.usage_page,
+                usage: info.usage,
+                path: info.path,
+                report_count,
+                error,
+                recommended: max_reports > 0 && report_count == max_reports,
+            }
+        })
+        .collect();
+
+    activity.sort_by(|a, b| {
+        b.report_count
+            .cmp(&a.report_count)
+            .then(a.interface_number.cmp(&b.interface_number))
+    });
+    activity
+}
+
+fn interface_info_from(device_info: &DeviceInfo) -> HidInterfaceInfo {
+    HidInterfaceInfo {
+        path: device_info.path().to_string_lossy().into_owned(),
+        vendor_id: format!("{:04X}", device_info.vendor_id()),
+        product_id: format!("{:04X}", device_info.product_id()),
+        serial_number: device_info.serial_number().map(|s| s.to_string()),
+        release_number: format!("{:04X}", device_info.release_number()),
+        manufacturer: device_info.manufacturer_string().map(|s| s.to_string()),
+        product: device_info.product_string().map(|s| s.to_string()),
+        usage_page: device_info.usage_page(),
+        usage: device_info.usage(),
+        interface_number: device_info.interface_number(),
+        bus_type: format!("{:?}", device_info.bus_type()),
+    }
+}
+
+/// Order by VID:PID, then interface, so interfaces of one device sit together
+fn sort_interfaces(interfaces: &mut [HidInterfaceInfo]) {
+    interfaces.sort_by(|a, b| {
+        (&a.vendor_id, &a.product_id, a.interface_number, a.usage_page, a.usage)
+            .cmp(&(&b.vendor_id, &b.product_id, b.interface_number, b.usage_page, b.usage))
+    });
+}
+
 impl InputMonitor for HidManager {
     fn start_monitoring(&mut self) -> Receiver<HidDevice> {
         let (tx, rx) = channel();
@@ -404,9 +912,14 @@ impl InputMonitor for HidManager {
         thread::spawn(move || {
             println!("🔵 [HidMonitor] HID monitoring thread started");
 
+            let Some(mut api) = open_polling_api(&monitoring) else {
+                println!("❌ [HidMonitor] Failed to create HID API");
+                return;
+            };
+
             while monitoring.load(Ordering::SeqCst) {
-                match HidApi::new() {
-                    Ok(api) => {
+                match api.refresh_devices() {
+                    Ok(()) => {
                         for device_info in api.device_list() {
                             if !monitoring.load(Ordering::SeqCst) {
                                 return;
@@ -432,6 +945,10 @@ impl InputMonitor for HidManager {
                                                 status: DeviceStatus::Connected,
                                                 manufacturer: device_info.manufacturer_string().map(|s| s.to_string()),
                                                 serial_number: device_info.serial_number().map(|s| s.to_string()),
+                                                consumer_usage: None,
+                                                raw_report: buf[..size].to_vec(),
+                                                is_repeat: false,
+                                                button_code: None,
                                             };
 
                                             monitoring.store(false, Ordering::SeqCst);
@@ -450,9 +967,7 @@ impl InputMonitor for HidManager {
                         }
                     }
                     Err(e) => {
-                        println!("❌ [HidMonitor] Failed to create HID API: {}", e);
-                        monitoring.store(false, Ordering::SeqCst);
-                        return;
+                        println!("❌ [HidMonitor] Failed to refresh HID devices: {}", e);
                     }
                 }
 
@@ -483,13 +998,40 @@ mod tests {
     fn test_device_refresh_result_creation() {
         let result = DeviceRefreshResult {
             devices: vec![],
+            connected_ids: vec![],
             disconnected_ids: vec!["1234:5678".to_string()],
+            reconnected_ids: vec![],
         };
         assert!(result.devices.is_empty());
         assert_eq!(result.disconnected_ids.len(), 1);
         assert_eq!(result.disconnected_ids[0], "1234:5678");
     }
 
+    #[test]
+    fn test_device_allowed() {
+        assert!(device_allowed(None, 0x1234, 0x5678));
+
+        let allowed = vec!["af88:6688".to_string(), "1234:5678:1".to_string()];
+        assert!(device_allowed(Some(&allowed), 0xAF88, 0x6688));
+        assert!(device_allowed(Some(&allowed), 0x1234, 0x5678));
+        assert!(!device_allowed(Some(&allowed), 0xAF88, 0x6689));
+        assert!(!device_allowed(Some(&[]), 0xAF88, 0x6688));
+    }
+
+    #[test]
+    fn test_count_interfaces() {
+        let counts = count_interfaces(vec![
+            ("AF88:0002".to_string(), 0),
+            ("AF88:0002".to_string(), 1),
+            ("AF88:0002".to_string(), 1), // Second collection on interface 1
+            ("AF88:0002".to_string(), 2),
+            ("046D:C52B".to_string(), -1), // Bluetooth/non-USB reports -1
+        ]);
+        assert_eq!(counts.get("AF88:0002"), Some(&3));
+        assert_eq!(counts.get("046D:C52B"), Some(&1));
+        assert_eq!(counts.get("FFFF:FFFF"), None);
+    }
+
     #[test]
     fn test_hid_error_display() {
         let err = HidError::InitError("test error".to_string());
@@ -503,6 +1045,24 @@ mod tests {
 
         let err = HidError::ReadError("timeout".to_string());
         assert!(err.to_string().contains("timeout"));
+
+        let err = HidError::WriteError("pipe stalled".to_string());
+        assert!(err.to_string().contains("pipe stalled"));
+    }
+
+    #[test]
+    fn test_hid_error_codes() {
+        assert_eq!(HidError::DeviceNotFound("x".to_string()).code(), ErrorCode::DeviceNotFound);
+        assert_eq!(HidError::PermissionDenied("x".to_string()).code(), ErrorCode::PermissionDenied);
+
+        let result = crate::types::IpcResult::<()>::from_error(&HidError::PermissionDenied("/dev/input".to_string()));
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["success"], false);
+        assert_eq!(json["code"], "permission-denied");
+        assert_eq!(json["error"], "Permission denied: /dev/input");
+
+        let json = serde_json::to_value(crate::types::IpcResult::ok(1)).unwrap();
+        assert!(json["code"].is_null());
     }
 
     #[test]
@@ -530,11 +1090,34 @@ mod tests {
             1
         );
 
-        // Set device as unconfigured
-        manager.set_device_unconfigured("1234:5678");
+        // Its last binding is gone
+        manager.resync_configured_devices(Vec::new());
         assert!(!manager.configured_devices.contains(&"1234:5678".to_string()));
     }
 
+    #[test]
+    fn test_resync_configured_devices_after_drift() {
+        let mut manager = match HidManager::new() {
+            Ok(m) => m,
+            Err(_) => return,
+        };
+
+        // Simulate drift: a stale device plus a missing one
+        manager.set_device_configured("DEAD:BEEF");
+        manager.set_device_configured("1234:5678");
+
+        let bindings = vec![
+            "1234:5678".to_string(),
+            "AAAA:BBBB".to_string(),
+            "1234:5678".to_string(), // duplicates collapse
+        ];
+        manager.resync_configured_devices(bindings);
+
+        let mut configured = manager.configured_devices.clone();
+        configured.sort();
+        assert_eq!(configured, vec!["1234:5678".to_string(), "AAAA:BBBB".to_string()]);
+    }
+
     #[test]
     fn test_monitoring_state() {
         // This test requires HID API to be available
@@ -554,9 +1137,34 @@ mod tests {
         let _ = manager.start_monitoring();
         assert!(manager.is_monitoring());
 
+        // A detection stays readable after monitoring stops...
+        let found = HidDevice {
+            id: "AF88:0002".to_string(),
+            name: "Detected".to_string(),
+            vendor_id: "AF88".to_string(),
+            product_id: "0002".to_string(),
+            interface_number: 0,
+            total_interfaces: 1,
+            status: DeviceStatus::Connected,
+            manufacturer: None,
+            serial_number: None,
+            consumer_usage: None,
+            raw_report: Vec::new(),
+            is_repeat: false,
+            button_code: None,
+        };
+        record_detected_device(&manager.detected_device_slot(), &found);
+
         // Stop monitoring
         manager.stop_monitoring();
         assert!(!manager.is_monitoring());
+        let detected = manager.get_monitoring_state().detected_device;
+        assert_eq!(detected.map(|d| d.id), Some("AF88:0002".to_string()));
+
+        // ...and is cleared when the next scan starts
+        let _ = manager.start_monitoring();
+        assert!(manager.get_monitoring_state().detected_device.is_none());
+        manager.stop_monitoring();
     }
 
     #[test]
@@ -569,4 +1177,205 @@ mod tests {
         // Initially empty
         assert!(manager.previous_devices.is_empty());
     }
+
+    #[test]
+    fn test_track_connection_changes_disconnect_then_reconnect() {
+        let set = |ids: &[&str]| ids.iter().map(|s| s.to_string()).collect::<HashSet<String>>();
+        let mut disconnected = HashSet::new();
+
+        // Unplug 1234:5678
+        let (new, gone, back) = track_connection_changes(
+            &set(&["1234:5678", "AAAA:BBBB"]),
+            &set(&["AAAA:BBBB"]),
+            &mut disconnected,
+        );
+        assert!(new.is_empty());
+        assert_eq!(gone, vec!["1234:5678".to_string()]);
+        assert!(back.is_empty());
+
+        // Plug it back in, plus a brand-new device
+        let (new, gone, back) = track_connection_changes(
+            &set(&["AAAA:BBBB"]),
+            &set(&["1234:5678", "AAAA:BBBB", "CCCC:DDDD"]),
+            &mut disconnected,
+        );
+        assert_eq!(new, vec!["1234:5678".to_string(), "CCCC:DDDD".to_string()]);
+        assert!(gone.is_empty());
+        assert_eq!(back, vec!["1234:5678".to_string()]);
+        assert!(disconnected.is_empty());
+    }
+
+    fn interface(vid: &str, pid: &str, interface_number: i32, usage_page: u16) -> HidInterfaceInfo {
+        HidInterfaceInfo {
+            path: format!("\\\\?\\HID#VID_{}&PID_{}&MI_{:02}", vid, pid, interface_number),
+            vendor_id: vid.to_string(),
+            product_id: pid.to_string(),
+            serial_number: None,
+            release_number: "0100".to_string(),
+            manufacturer: None,
+            product: Some("Test Device".to_string()),
+            usage_page,
+            usage: 0x06,
+            interface_number,
+            bus_type: "Usb".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sort_interfaces_keeps_all_interfaces_of_same_device() {
+        let mut interfaces = vec![
+            interface("AF88", "6688", 1, 0x0C),
+            interface("1234", "5678", 0, 0x01),
+            interface("AF88", "6688", 0, 0x01),
+        ];
+        sort_interfaces(&mut interfaces);
+
+        let same_device: Vec<&HidInterfaceInfo> = interfaces
+            .iter()
+            .filter(|i| i.vendor_id == "AF88" && i.product_id == "6688")
+            .collect();
+        assert_eq!(same_device.len(), 2);
+        assert_eq!(same_device[0].interface_number, 0);
+        assert_eq!(same_device[1].interface_number, 1);
+
+        let json = serde_json::to_value(&interfaces).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert_eq!(json[1]["usagePage"], 0x01);
+    }
+
+    #[test]
+    fn test_export_device_list_not_deduplicated() {
+        let mut manager = match HidManager::new() {
+            Ok(m) => m,
+            Err(_) => return,
+        };
+
+        let exported = manager.export_device_list().unwrap();
+        let listed = manager.list_devices().unwrap();
+        assert!(exported.len() >= listed.len());
+    }
+
+    #[test]
+    fn test_list_devices_cached_does_not_refresh() {
+        let mut manager = match HidManager::new() {
+            Ok(m) => m,
+            Err(_) => return,
+        };
+
+        // Seed the cache with a device that a real scan would never return
+        let fake = HidDevice {
+            id: "FFFF:FFFE".to_string(),
+            name: "Cached Only".to_string(),
+            vendor_id: "FFFF".to_string(),
+            product_id: "FFFE".to_string(),
+            interface_number: 0,
+            total_interfaces: 1,
+            status: DeviceStatus::Connected,
+            manufacturer: None,
+            serial_number: None,
+            consumer_usage: None,
+            raw_report: Vec::new(),
+            is_repeat: false,
+            button_code: None,
+        };
+        manager.cached_devices = Some(vec![fake]);
+        manager.set_device_configured("FFFF:FFFE");
+
+        let devices = manager.list_devices_cached().unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].id, "FFFF:FFFE");
+        assert_eq!(devices[0].status, DeviceStatus::Configured);
+        // previous_devices is only touched by a real enumeration
+        assert!(manager.previous_devices.is_empty());
+    }
+
+    #[test]
+    fn test_aggregate_interface_activity() {
+        let results = vec![
+            (interface("AF88", "6688", 0, 0x01), Ok(0)),
+            (interface("AF88", "6688", 1, 0x0C), Ok(4)),
+            (interface("AF88", "6688", 2, 0xFF00), Err("Access denied".to_string())),
+            (interface("AF88", "6688", 3, 0x01), Ok(4)),
+        ];
+
+        let activity = aggregate_interface_activity(results);
+        let order: Vec<i32> = activity.iter().map(|a| a.interface_number).collect();
+        assert_eq!(order, vec![1, 3, 0, 2]);
+
+        assert!(activity[0].recommended && activity[1].recommended);
+        assert!(!activity[2].recommended);
+        assert_eq!(activity[3].report_count, 0);
+        assert_eq!(activity[3].error.as_deref(), Some("Access denied"));
+        assert!(!activity[3].recommended);
+    }
+
+    #[test]
+    fn test_aggregate_interface_activity_nothing_pressed() {
+        let results = vec![
+            (interface("AF88", "6688", 0, 0x01), Ok(0)),
+            (interface("AF88", "6688", 1, 0x0C), Ok(0)),
+        ];
+
+        let activity = aggregate_interface_activity(results);
+        assert!(activity.iter().all(|a| !a.recommended));
+    }
+
+    #[test]
+    fn test_format_report_hex() {
+        assert_eq!(format_report_hex(&[0x01, 0xCD, 0x00], 64), "01 CD 00");
+        assert_eq!(format_report_hex(&[], 64), "");
+        assert_eq!(format_report_hex(&[0xAB; 6], 4), "AB AB AB AB ... (6 bytes total)");
+        assert_eq!(format_report_hex(&[0x0F; 4], 4), "0F 0F 0F 0F");
+    }
+
+    #[test]
+    fn test_parse_report_hex() {
+        assert_eq!(parse_report_hex("01 ff 00", None), Ok(vec![0x00, 0x01, 0xFF, 0x00]));
+        assert_eq!(parse_report_hex("0x01,0xFF", Some(0x05)), Ok(vec![0x05, 0x01, 0xFF]));
+        assert_eq!(parse_report_hex(" 01ff00 ", Some(0x02)), Ok(vec![0x02, 0x01, 0xFF, 0x00]));
+        assert_eq!(parse_report_hex("AA:bb-CC", None), Ok(vec![0x00, 0xAA, 0xBB, 0xCC]));
+    }
+
+    #[test]
+    fn test_parse_report_hex_rejects_bad_input() {
+        assert!(parse_report_hex("", None).is_err());
+        assert!(parse_report_hex(" , ", Some(1)).is_err());
+        assert!(parse_report_hex("0x", None).is_err());
+        assert!(parse_report_hex("abc", None).is_err());
+        assert!(parse_report_hex("zz", None).is_err());
+        assert!(parse_report_hex(&"00".repeat(MAX_REPORT_BYTES), None).is_ok());
+        assert!(parse_report_hex(&"00".repeat(MAX_REPORT_BYTES + 1), None).is_err());
+    }
+
+    #[test]
+    fn test_merge_captured_reports_in_arrival_order() {
+        let results = vec![
+            (interface("AF88", "6688", 0, 0x01), Ok(vec![(120, vec![0x00, 0x00, 0x68])])),
+            (interface("AF88", "6688", 1, 0x0C), Ok(vec![(40, vec![0x02, 0xCD]), (300, vec![0x02, 0x00])])),
+            (interface("AF88", "6688", 2, 0xFF00), Err("Access denied".to_string())),
+        ];
+
+        let reports = merge_captured_reports(results).unwrap();
+        let summary: Vec<(i32, u64, &str)> = reports
+            .iter()
+            .map(|r| (r.interface_number, r.elapsed_ms, r.hex.as_str()))
+            .collect();
+        assert_eq!(summary, vec![(1, 40, "02 CD"), (0, 120, "00 00 68"), (1, 300, "02 00")]);
+        assert_eq!(reports[0].usage_page, 0x0C);
+        assert_eq!(reports[1].length, 3);
+    }
+
+    #[test]
+    fn test_merge_captured_reports_fails_when_nothing_readable() {
+        let results = vec![
+            (interface("AF88", "6688", 0, 0x01), Err("Access denied".to_string())),
+            (interface("AF88", "6688", 1, 0x0C), Err("Access denied".to_string())),
+        ];
+        let err = merge_captured_reports(results).unwrap_err();
+        assert!(err.contains("interface 0") && err.contains("interface 1"));
+
+        // Readable but quiet is not an error
+        let results = vec![(interface("AF88", "6688", 0, 0x01), Ok(Vec::new()))];
+        assert!(merge_captured_reports(results).unwrap().is_empty());
+    }
 }
@@ -1,56 +1,315 @@
 // ============================================
 // Hotkey Simulation Module
 // Uses Windows SendInput API to simulate keyboard input
+// Parsing is platform-independent so hotkeys can be previewed anywhere
 // ============================================
 
+use crate::types::{ActionConfig, ActionType, DeviceBinding, HotkeyConflict, HotkeyPreview, ResolvedKey};
 use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
 
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::{CloseHandle, BOOL, HWND, LPARAM, WPARAM};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, VIRTUAL_KEY,
-    VK_CONTROL, VK_MENU, VK_SHIFT, VK_LWIN,
+    MapVirtualKeyW, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS,
+    KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, MAPVK_VK_TO_VSC, VIRTUAL_KEY,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumWindows, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible, PostMessageW,
+    WM_APPCOMMAND, WM_KEYDOWN, WM_KEYUP, WM_SYSKEYDOWN, WM_SYSKEYUP,
 };
 
-/// Parse hotkey string like "Ctrl+Shift+V" and simulate key press
+// Virtual-key codes for modifiers, defined here rather than taken from the
+// windows crate so the parser builds on every platform
+const VK_SHIFT: u16 = 0x10;
+const VK_CONTROL: u16 = 0x11;
+const VK_MENU: u16 = 0x12;
+const VK_LCONTROL: u16 = 0xA2;
+const VK_RCONTROL: u16 = 0xA3;
+const VK_LMENU: u16 = 0xA4;
+const VK_RMENU: u16 = 0xA5;
+const VK_LWIN: u16 = 0x5B;
+const VK_RETURN: u16 = 0x0D;
+
+/// Set on a key code (above the 0-255 VK range) for keys that share a VK with
+/// another key and differ only by the extended-key flag, e.g. numpad Enter
+const EXTENDED_FLAG: u16 = 0x100;
+
+/// The VK code Windows sees for a parsed key code
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn base_vk(key: u16) -> u16 {
+    key & 0xFF
+}
+
+/// Keys that must be sent with the extended-key flag to be told apart from
+/// their twins: right Ctrl/Alt, the Windows keys, the navigation block (as
+/// opposed to the numpad's), numpad / and Enter, Num Lock, Print Screen and
+/// media keys
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn is_extended_key(key: u16) -> bool {
+    key & EXTENDED_FLAG != 0
+        || matches!(key, 0xA3 | 0xA5 | 0x5B | 0x5C | 0x21..=0x28 | 0x2C | 0x2D | 0x2E | 0x6F | 0x90)
+        || media_app_command(key).is_some()
+}
+
+/// Media keys and the APPCOMMAND_* each one maps to.
+///
+/// These take a separate path from ordinary keys. Through SendInput they go
+/// out as extended keys with a real scan code, which the shell turns into
+/// app commands for whichever player owns the media session. When posted to
+/// a target window they're sent as WM_APPCOMMAND instead, since players like
+/// Spotify ignore posted WM_KEYDOWN for them. Every other key, including
+/// modifiers combined with a media key, uses the normal keyboard path.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const MEDIA_APP_COMMANDS: &[(u16, u16)] = &[
+    (0xAD, 8),  // VOLUMEMUTE -> APPCOMMAND_VOLUME_MUTE
+    (0xAE, 9),  // VOLUMEDOWN -> APPCOMMAND_VOLUME_DOWN
+    (0xAF, 10), // VOLUMEUP   -> APPCOMMAND_VOLUME_UP
+    (0xB0, 11), // NEXTTRACK  -> APPCOMMAND_MEDIA_NEXTTRACK
+    (0xB1, 12), // PREVTRACK  -> APPCOMMAND_MEDIA_PREVIOUSTRACK
+    (0xB2, 13), // STOP       -> APPCOMMAND_MEDIA_STOP
+    (0xB3, 14), // PLAYPAUSE  -> APPCOMMAND_MEDIA_PLAY_PAUSE
+];
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn media_app_command(vk: u16) -> Option<u16> {
+    MEDIA_APP_COMMANDS
+        .iter()
+        .find(|&&(key, _)| key == vk)
+        .map(|&(_, command)| command)
+}
+
+/// App commands for a combination made only of media keys, or `None` if any
+/// key needs the normal keyboard path
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn media_commands(keys: &[u16]) -> Option<Vec<u16>> {
+    if keys.is_empty() {
+        return None;
+    }
+    keys.iter().map(|&vk| media_app_command(vk)).collect()
+}
+
+/// lParam for WM_APPCOMMAND: the command in the high word, sent as if from
+/// the keyboard (FAPPCOMMAND_KEY, all other bits zero)
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn app_command_lparam(command: u16) -> isize {
+    (command as isize) << 16
+}
+
+/// Longest a hotkey may be held down, so a typo can't pin keys for minutes
+pub const MAX_HOLD_MS: u64 = 5000;
+
+/// How long to hold keys for an action's `hold_ms`. `None` or 0 means an
+/// instant tap.
+pub fn hold_duration(hold_ms: Option<u64>) -> Option<Duration> {
+    hold_ms
+        .filter(|&ms| ms > 0)
+        .map(|ms| Duration::from_millis(ms.min(MAX_HOLD_MS)))
+}
+
+/// Parse hotkey string like "Ctrl+Shift+V" and simulate key press, holding
+/// the keys for `hold` before releasing them
 /// Returns Ok(()) on success, Err with description on failure
-pub fn execute_hotkey(hotkey_str: &str) -> Result<(), String> {
+pub fn execute_hotkey(hotkey_str: &str, hold: Option<Duration>) -> Result<(), String> {
     log::info!("Executing hotkey: {}", hotkey_str);
 
     #[cfg(target_os = "windows")]
     {
         let keys = parse_hotkey(hotkey_str)?;
-        send_keys(&keys)?;
+        send_keys(&keys, hold)?;
         Ok(())
     }
 
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = hold;
         Err("Hotkey simulation is only supported on Windows".to_string())
     }
 }
 
+/// Send a hotkey to a specific window instead of the focused one.
+///
+/// `target` is matched case-insensitively against each visible top-level
+/// window's title and owning process name (e.g. "notepad.exe"). Keys are
+/// delivered as posted key messages so focus isn't stolen.
+/// Some apps (games, anything reading Raw Input or checking modifier state via
+/// `GetKeyState`) ignore posted messages. If no window matches, falls back to
+/// `SendInput` on the focused window. `hold_ms` is the action's hold time
+/// (see `hold_duration`).
+pub fn execute_hotkey_to_window(hotkey_str: &str, target: Option<&str>, hold_ms: Option<u64>) -> Result<(), String> {
+    let hold = hold_duration(hold_ms);
+    let target = match target.map(str::trim) {
+        Some(t) if !t.is_empty() => t,
+        _ => return execute_hotkey(hotkey_str, hold),
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        let keys = parse_hotkey(hotkey_str)?;
+        match find_target_window(target) {
+            Some(hwnd) => {
+                log::info!("Posting hotkey {} to window matching '{}'", hotkey_str, target);
+                post_keys(hwnd, &keys, hold)
+            }
+            None => {
+                log::warn!("No window matches '{}', falling back to SendInput", target);
+                send_keys(&keys, hold)
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (target, hold);
+        Err("Hotkey simulation is only supported on Windows".to_string())
+    }
+}
+
+/// Parse a hotkey without sending it, returning the resolved keys and any
+/// warnings worth showing before the user saves the binding
+pub fn preview_hotkey(hotkey_str: &str) -> Result<HotkeyPreview, String> {
+    let parts = parse_hotkey_parts(hotkey_str)?;
+    let mut warnings = Vec::new();
+
+    let is_modifier = |vk: u16| matches!(vk, VK_SHIFT | VK_CONTROL | VK_MENU | VK_LWIN);
+    let non_modifiers = parts.iter().filter(|(_, vk)| !is_modifier(*vk)).count();
+    if non_modifiers == 0 {
+        warnings.push("Hotkey only contains modifier keys".to_string());
+    } else if non_modifiers > 1 {
+        warnings.push("Multiple non-modifier keys will be pressed simultaneously".to_string());
+    }
+
+    let mut seen = Vec::new();
+    for (name, vk) in &parts {
+        if seen.contains(vk) {
+            warnings.push(format!("Key {} appears more than once", name));
+        }
+        seen.push(*vk);
+    }
+
+    Ok(HotkeyPreview {
+        keys: parts
+            .into_iter()
+            .map(|(name, vk_code)| ResolvedKey { name, vk_code })
+            .collect(),
+        warnings,
+    })
+}
+
+/// Shortcuts Windows or the shell handle before any application sees them.
+/// Sending one from a binding usually does something other than expected.
+const RESERVED_HOTKEYS: &[(&str, &str)] = &[
+    ("Win", "opens the Start menu"),
+    ("Win+L", "locks the workstation"),
+    ("Win+D", "shows the desktop"),
+    ("Win+E", "opens File Explorer"),
+    ("Win+R", "opens the Run dialog"),
+    ("Win+I", "opens Settings"),
+    ("Win+M", "minimizes all windows"),
+    ("Win+X", "opens the Quick Link menu"),
+    ("Win+Tab", "opens Task View"),
+    ("Win+Shift+S", "starts a screen snip"),
+    ("Win+PrintScreen", "saves a screenshot"),
+    ("Ctrl+Escape", "opens the Start menu"),
+    ("Ctrl+Alt+Delete", "opens the security screen and can't be sent by apps"),
+    ("Ctrl+Shift+Escape", "opens Task Manager"),
+    ("Alt+Tab", "switches windows"),
+    ("Alt+F4", "closes the active window"),
+];
+
+/// If a hotkey matches a reserved system shortcut (in any key order),
+/// describe the conflict
+pub fn reserved_hotkey_conflict(hotkey_str: &str) -> Option<String> {
+    let keys = key_set(hotkey_str)?;
+    RESERVED_HOTKEYS
+        .iter()
+        .find(|(reserved, _)| key_set(reserved).as_ref() == Some(&keys))
+        .map(|(reserved, effect)| format!("{} is a system shortcut that {}", reserved, effect))
+}
+
+/// Flag hotkey actions (including power alternates and sequence steps) that
+/// hit reserved shortcuts
+pub fn find_hotkey_conflicts(bindings: &[DeviceBinding]) -> Vec<HotkeyConflict> {
+    let mut conflicts = Vec::new();
+    for binding in bindings {
+        let alternate = binding.power_condition.as_ref().and_then(|c| c.otherwise.as_ref());
+        let actions = std::iter::once(&binding.action).chain(alternate).flat_map(ActionConfig::and_steps);
+        for action in actions {
+            if action.r#type != ActionType::Hotkey {
+                continue;
+            }
+            if let Some(warning) = reserved_hotkey_conflict(&action.executable_path) {
+                conflicts.push(HotkeyConflict {
+                    binding_id: binding.id.clone(),
+                    device_id: binding.device_id.clone(),
+                    hotkey: action.executable_path.clone(),
+                    warning,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+/// Sorted, deduplicated key codes so "Shift+Win+S" equals "Win+Shift+S"
+fn key_set(hotkey_str: &str) -> Option<Vec<u16>> {
+    let mut keys = parse_hotkey(hotkey_str).ok()?;
+    keys.sort_unstable();
+    keys.dedup();
+    Some(keys)
+}
+
 /// Parse hotkey string into virtual key codes
-/// Supports: Ctrl, Alt, Shift, Win + any letter/number/F-key
-#[cfg(target_os = "windows")]
-fn parse_hotkey(hotkey_str: &str) -> Result<Vec<u16>, String> {
+/// Supports: Ctrl, Alt, Shift, Win + any letter/number/F-key/punctuation key
+pub fn parse_hotkey(hotkey_str: &str) -> Result<Vec<u16>, String> {
+    Ok(parse_hotkey_parts(hotkey_str)?
+        .into_iter()
+        .map(|(_, vk)| vk)
+        .collect())
+}
+
+/// Split a hotkey string on `+`. A `+` where a key name should start is the
+/// plus key itself, so "Ctrl++" is Ctrl and +, and "+" alone is just +.
+fn split_hotkey(hotkey_str: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (i, c) in hotkey_str.char_indices() {
+        if c == '+' && !hotkey_str[start..i].trim().is_empty() {
+            parts.push(hotkey_str[start..i].trim());
+            start = i + 1;
+        }
+    }
+    parts.push(hotkey_str[start..].trim());
+    parts
+}
+
+/// Parse hotkey string into (normalized key name, virtual key code) pairs
+fn parse_hotkey_parts(hotkey_str: &str) -> Result<Vec<(String, u16)>, String> {
     let mut keys = Vec::new();
-    let parts: Vec<&str> = hotkey_str.split('+').map(|s| s.trim()).collect();
 
-    if parts.is_empty() {
+    if hotkey_str.trim().is_empty() {
         return Err("Empty hotkey string".to_string());
     }
+    let parts = split_hotkey(hotkey_str);
 
-    let key_map = build_key_map();
+    let key_map = key_map();
 
     for part in parts {
         let upper = part.to_uppercase();
         if let Some(&vk) = key_map.get(upper.as_str()) {
-            keys.push(vk);
+            keys.push((upper, vk));
         } else if upper.len() == 1 {
             // Single character - use ASCII value for A-Z and 0-9
             let c = upper.chars().next().unwrap();
             if c.is_ascii_alphanumeric() {
-                keys.push(c as u16);
+                keys.push((upper, c as u16));
             } else {
                 return Err(format!("Unsupported key: {}", part));
             }
@@ -62,29 +321,75 @@ fn parse_hotkey(hotkey_str: &str) -> Result<Vec<u16>, String> {
     Ok(keys)
 }
 
+/// Key name to virtual key code table, built once on first use
+fn key_map() -> &'static HashMap<&'static str, u16> {
+    static KEY_MAP: OnceLock<HashMap<&'static str, u16>> = OnceLock::new();
+    KEY_MAP.get_or_init(build_key_map)
+}
+
 /// Build mapping of key names to virtual key codes
-#[cfg(target_os = "windows")]
 fn build_key_map() -> HashMap<&'static str, u16> {
     let mut map = HashMap::new();
 
     // Modifiers
-    map.insert("CTRL", VK_CONTROL.0);
-    map.insert("CONTROL", VK_CONTROL.0);
-    map.insert("ALT", VK_MENU.0);
-    map.insert("SHIFT", VK_SHIFT.0);
-    map.insert("WIN", VK_LWIN.0);
-    map.insert("WINDOWS", VK_LWIN.0);
-    map.insert("META", VK_LWIN.0);
+    map.insert("CTRL", VK_CONTROL);
+    map.insert("CONTROL", VK_CONTROL);
+    map.insert("ALT", VK_MENU);
+    map.insert("SHIFT", VK_SHIFT);
+    map.insert("WIN", VK_LWIN);
+    map.insert("WINDOWS", VK_LWIN);
+    map.insert("META", VK_LWIN);
+
+    // Left/right-specific modifiers
+    map.insert("LCTRL", 0xA2);
+    map.insert("LCONTROL", 0xA2);
+    map.insert("RCTRL", 0xA3);
+    map.insert("RCONTROL", 0xA3);
+    map.insert("LALT", 0xA4);
+    map.insert("RALT", 0xA5);
+    map.insert("LSHIFT", 0xA0);
+    map.insert("RSHIFT", 0xA1);
+    map.insert("LWIN", VK_LWIN);
+    map.insert("RWIN", 0x5C);
+
+    // Numpad
+    const NUMPAD_DIGITS: [(&str, &str); 10] = [
+        ("NUM0", "NUMPAD0"),
+        ("NUM1", "NUMPAD1"),
+        ("NUM2", "NUMPAD2"),
+        ("NUM3", "NUMPAD3"),
+        ("NUM4", "NUMPAD4"),
+        ("NUM5", "NUMPAD5"),
+        ("NUM6", "NUMPAD6"),
+        ("NUM7", "NUMPAD7"),
+        ("NUM8", "NUMPAD8"),
+        ("NUM9", "NUMPAD9"),
+    ];
+    for (i, (short, long)) in NUMPAD_DIGITS.into_iter().enumerate() {
+        map.insert(short, 0x60 + i as u16); // VK_NUMPAD0 = 0x60
+        map.insert(long, 0x60 + i as u16);
+    }
+    map.insert("NUMMULTIPLY", 0x6A);
+    map.insert("NUMPLUS", 0x6B);
+    map.insert("NUMADD", 0x6B);
+    map.insert("NUMMINUS", 0x6D);
+    map.insert("NUMSUBTRACT", 0x6D);
+    map.insert("NUMDECIMAL", 0x6E);
+    map.insert("NUMDIVIDE", 0x6F);
+    map.insert("NUMENTER", VK_RETURN | EXTENDED_FLAG);
 
     // Function keys
-    for i in 1..=24 {
-        let key = format!("F{}", i);
-        map.insert(Box::leak(key.into_boxed_str()), 0x70 + (i - 1) as u16); // VK_F1 = 0x70
+    const FUNCTION_KEYS: [&str; 24] = [
+        "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12", "F13", "F14", "F15", "F16",
+        "F17", "F18", "F19", "F20", "F21", "F22", "F23", "F24",
+    ];
+    for (i, key) in FUNCTION_KEYS.into_iter().enumerate() {
+        map.insert(key, 0x70 + i as u16); // VK_F1 = 0x70
     }
 
     // Special keys
-    map.insert("ENTER", 0x0D);
-    map.insert("RETURN", 0x0D);
+    map.insert("ENTER", VK_RETURN);
+    map.insert("RETURN", VK_RETURN);
     map.insert("TAB", 0x09);
     map.insert("ESCAPE", 0x1B);
     map.insert("ESC", 0x1B);
@@ -109,6 +414,23 @@ fn build_key_map() -> HashMap<&'static str, u16> {
     map.insert("NUMLOCK", 0x90);
     map.insert("SCROLLLOCK", 0x91);
 
+    // Punctuation (US layout positions; the OEM codes name physical keys,
+    // so "+" is the =/+ key)
+    map.insert("+", 0xBB); // VK_OEM_PLUS
+    map.insert("=", 0xBB);
+    map.insert("PLUS", 0xBB);
+    map.insert("-", 0xBD); // VK_OEM_MINUS
+    map.insert("MINUS", 0xBD);
+    map.insert(",", 0xBC); // VK_OEM_COMMA
+    map.insert(".", 0xBE); // VK_OEM_PERIOD
+    map.insert(";", 0xBA); // VK_OEM_1
+    map.insert("/", 0xBF); // VK_OEM_2
+    map.insert("`", 0xC0); // VK_OEM_3
+    map.insert("[", 0xDB); // VK_OEM_4
+    map.insert("\\", 0xDC); // VK_OEM_5
+    map.insert("]", 0xDD); // VK_OEM_6
+    map.insert("'", 0xDE); // VK_OEM_7
+
     // Media keys
     map.insert("VOLUMEUP", 0xAF);
     map.insert("VOLUMEDOWN", 0xAE);
@@ -122,28 +444,48 @@ fn build_key_map() -> HashMap<&'static str, u16> {
     map
 }
 
+/// Key transitions for a combination as `(vk, key_up)`: every key pressed
+/// in order, then released in reverse order
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn key_transitions(keys: &[u16]) -> Vec<(u16, bool)> {
+    keys.iter()
+        .map(|&vk| (vk, false))
+        .chain(keys.iter().rev().map(|&vk| (vk, true)))
+        .collect()
+}
+
 /// Send key combination using SendInput
-/// First presses all keys down, then releases them in reverse order
+/// First presses all keys down, then releases them in reverse order. With
+/// `hold`, the releases go out as a second batch after sleeping that long.
 #[cfg(target_os = "windows")]
-fn send_keys(keys: &[u16]) -> Result<(), String> {
+fn send_keys(keys: &[u16], hold: Option<Duration>) -> Result<(), String> {
     if keys.is_empty() {
         return Err("No keys to send".to_string());
     }
 
-    let mut inputs: Vec<INPUT> = Vec::new();
+    let inputs: Vec<INPUT> = key_transitions(keys)
+        .into_iter()
+        .map(|(vk, key_up)| create_key_input(vk, key_up))
+        .collect();
 
-    // Press all keys down
-    for &vk in keys {
-        inputs.push(create_key_input(vk, false));
+    match hold {
+        None => send_inputs(&inputs)?,
+        Some(hold) => {
+            let (downs, ups) = inputs.split_at(keys.len());
+            send_inputs(downs)?;
+            std::thread::sleep(hold);
+            send_inputs(ups)?;
+        }
     }
 
-    // Release all keys in reverse order
-    for &vk in keys.iter().rev() {
-        inputs.push(create_key_input(vk, true));
-    }
+    log::info!("Hotkey executed successfully: {} keys", keys.len());
+    Ok(())
+}
 
+#[cfg(target_os = "windows")]
+fn send_inputs(inputs: &[INPUT]) -> Result<(), String> {
     unsafe {
-        let sent = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+        let sent = SendInput(inputs, std::mem::size_of::<INPUT>() as i32);
         if sent != inputs.len() as u32 {
             return Err(format!(
                 "SendInput failed: sent {} of {} inputs",
@@ -152,21 +494,292 @@ fn send_keys(keys: &[u16]) -> Result<(), String> {
             ));
         }
     }
+    Ok(())
+}
 
-    log::info!("Hotkey executed successfully: {} keys", keys.len());
+/// A window message to post for one key transition
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq)]
+struct PostedKeyMessage {
+    msg: u32,
+    vk: u16,
+    lparam: isize,
+}
+
+/// Build the key message sequence for a key combination, in the same
+/// press-all-then-release-in-reverse order that `send_keys` uses
+#[cfg(target_os = "windows")]
+fn build_key_messages(keys: &[u16], to_scan_code: impl Fn(u16) -> u32) -> Vec<PostedKeyMessage> {
+    let mut held: Vec<u16> = Vec::new();
+    key_transitions(keys)
+        .into_iter()
+        .map(|(key, key_up)| {
+            if !key_up {
+                held.push(key);
+            }
+            let system = is_system_key(&held);
+            if key_up {
+                held.retain(|&k| k != key);
+            }
+
+            let vk = base_vk(key);
+            let mut lparam = key_message_lparam(to_scan_code(vk), key_up);
+            if is_extended_key(key) {
+                lparam |= 1 << 24;
+            }
+            let msg = match (system, key_up) {
+                (true, false) => WM_SYSKEYDOWN,
+                (true, true) => WM_SYSKEYUP,
+                (false, false) => WM_KEYDOWN,
+                (false, true) => WM_KEYUP,
+            };
+            if system {
+                lparam |= 1 << 29; // Context code: Alt is down
+            }
+            PostedKeyMessage { msg, vk, lparam }
+        })
+        .collect()
+}
+
+/// Whether a key transition is sent as WM_SYSKEYDOWN/WM_SYSKEYUP, given the
+/// keys held down at that point. Windows does that while Alt is held without
+/// Ctrl, and windows only treat a key as Alt+key (Alt+F4, menu access keys)
+/// when it arrives that way. Ctrl+Alt (AltGr) combinations stay plain keys.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn is_system_key(held: &[u16]) -> bool {
+    let holding = |codes: &[u16]| held.iter().any(|&k| codes.contains(&base_vk(k)));
+    holding(&[VK_MENU, VK_LMENU, VK_RMENU]) && !holding(&[VK_CONTROL, VK_LCONTROL, VK_RCONTROL])
+}
+
+/// lParam layout for keyboard messages: repeat count (bits 0-15), scan code
+/// (bits 16-23), previous key state (bit 30) and transition state (bit 31)
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn key_message_lparam(scan_code: u32, key_up: bool) -> isize {
+    let mut lparam: u32 = 1 | ((scan_code & 0xFF) << 16);
+    if key_up {
+        lparam |= (1 << 30) | (1 << 31);
+    }
+    lparam as i32 as isize
+}
+
+/// Case-insensitive substring match against a window title or process name
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn window_matches(target: &str, title: &str, process_name: &str) -> bool {
+    let target = target.to_lowercase();
+    title.to_lowercase().contains(&target) || process_name.to_lowercase().contains(&target)
+}
+
+/// Post a key combination to a window's message queue, pausing for `hold`
+/// between the key-downs and key-ups
+#[cfg(target_os = "windows")]
+fn post_keys(hwnd: HWND, keys: &[u16], hold: Option<Duration>) -> Result<(), String> {
+    if keys.is_empty() {
+        return Err("No keys to send".to_string());
+    }
+
+    // Media keys go to the window as app commands (see MEDIA_APP_COMMANDS)
+    if let Some(commands) = media_commands(keys) {
+        for command in commands {
+            unsafe {
+                PostMessageW(hwnd, WM_APPCOMMAND, WPARAM(hwnd.0 as usize), LPARAM(app_command_lparam(command)))
+                    .map_err(|e| format!("PostMessage failed: {}", e))?;
+            }
+        }
+        log::info!("Media command posted successfully: {} keys", keys.len());
+        return Ok(());
+    }
+
+    let messages = build_key_messages(keys, |vk| unsafe { MapVirtualKeyW(vk as u32, MAPVK_VK_TO_VSC) });
+
+    for (i, m) in messages.iter().enumerate() {
+        if i == keys.len() {
+            if let Some(hold) = hold {
+                std::thread::sleep(hold);
+            }
+        }
+        unsafe {
+            PostMessageW(hwnd, m.msg, WPARAM(m.vk as usize), LPARAM(m.lparam))
+                .map_err(|e| format!("PostMessage failed: {}", e))?;
+        }
+    }
+
+    log::info!("Hotkey posted successfully: {} keys", keys.len());
     Ok(())
 }
 
-/// Create INPUT structure for a key event
+/// Find the first visible top-level window whose title or process name matches
+#[cfg(target_os = "windows")]
+fn find_target_window(target: &str) -> Option<HWND> {
+    struct Search<'a> {
+        target: &'a str,
+        found: Option<HWND>,
+    }
+
+    unsafe extern "system" fn enum_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = &mut *(lparam.0 as *mut Search);
+
+        if !IsWindowVisible(hwnd).as_bool() {
+            return BOOL(1);
+        }
+
+        let mut title_buf = [0u16; 512];
+        let len = GetWindowTextW(hwnd, &mut title_buf);
+        let title = String::from_utf16_lossy(&title_buf[..len.max(0) as usize]);
+        let process_name = window_process_name(hwnd).unwrap_or_default();
+
+        if window_matches(search.target, &title, &process_name) {
+            search.found = Some(hwnd);
+            return BOOL(0); // Stop enumerating
+        }
+
+        BOOL(1)
+    }
+
+    let mut search = Search { target, found: None };
+    unsafe {
+        // EnumWindows reports an error when the callback stops early, so ignore it
+        let _ = EnumWindows(Some(enum_proc), LPARAM(&mut search as *mut Search as isize));
+    }
+    search.found
+}
+
+/// Executable file name (e.g. "notepad.exe") of the process owning a window
 #[cfg(target_os = "windows")]
-fn create_key_input(vk: u16, key_up: bool) -> INPUT {
+pub(crate) unsafe fn window_process_name(hwnd: HWND) -> Option<String> {
+    let mut pid: u32 = 0;
+    GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    if pid == 0 {
+        return None;
+    }
+
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+    let mut buf = [0u16; 260];
+    let mut len = buf.len() as u32;
+    let result = QueryFullProcessImageNameW(
+        process,
+        PROCESS_NAME_WIN32,
+        windows::core::PWSTR(buf.as_mut_ptr()),
+        &mut len,
+    );
+    let _ = CloseHandle(process);
+    result.ok()?;
+
+    let path = String::from_utf16_lossy(&buf[..len as usize]);
+    path.rsplit('\\').next().map(|s| s.to_string())
+}
+
+/// One key tapped (pressed and released) while typing text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Keystroke {
+    /// A virtual key, for characters apps expect as real keys
+    Key(u16),
+    /// A UTF-16 code unit sent with KEYEVENTF_UNICODE
+    Unicode(u16),
+}
+
+/// Keystrokes that type `text`. Newlines become Enter and tabs become Tab;
+/// characters outside the BMP are sent as their two surrogate code units.
+pub fn text_keystrokes(text: &str) -> Vec<Keystroke> {
+    let mut keystrokes = Vec::new();
+
+    for c in text.chars() {
+        match c {
+            '\r' => continue, // "\r\n" becomes a single Enter
+            '\n' => keystrokes.push(Keystroke::Key(0x0D)),
+            '\t' => keystrokes.push(Keystroke::Key(0x09)),
+            _ => {
+                let mut units = [0u16; 2];
+                keystrokes.extend(c.encode_utf16(&mut units).iter().map(|&u| Keystroke::Unicode(u)));
+            }
+        }
+    }
+
+    keystrokes
+}
+
+/// Type arbitrary text into the focused window as Unicode key events, so the
+/// result doesn't depend on the keyboard layout (see `text_keystrokes`)
+pub fn type_text(text: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut inputs: Vec<INPUT> = Vec::new();
+
+        for keystroke in text_keystrokes(text) {
+            match keystroke {
+                Keystroke::Key(vk) => {
+                    inputs.push(create_key_input(vk, false));
+                    inputs.push(create_key_input(vk, true));
+                }
+                Keystroke::Unicode(unit) => {
+                    inputs.push(create_unicode_input(unit, false));
+                    inputs.push(create_unicode_input(unit, true));
+                }
+            }
+        }
+
+        if inputs.is_empty() {
+            return Ok(());
+        }
+
+        unsafe {
+            let sent = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+            if sent != inputs.len() as u32 {
+                return Err(format!(
+                    "SendInput failed: sent {} of {} inputs",
+                    sent,
+                    inputs.len()
+                ));
+            }
+        }
+
+        log::info!("Typed {} characters", text.chars().count());
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = text;
+        Err("Text input is only supported on Windows".to_string())
+    }
+}
+
+/// Create INPUT structure for a single UTF-16 code unit
+#[cfg(target_os = "windows")]
+fn create_unicode_input(unit: u16, key_up: bool) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VIRTUAL_KEY(0),
+                wScan: unit,
+                dwFlags: if key_up { KEYEVENTF_UNICODE | KEYEVENTF_KEYUP } else { KEYEVENTF_UNICODE },
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+/// Create INPUT structure for a key event. Extended keys (see
+/// `is_extended_key`) are flagged and carry their scan code so they're
+/// delivered like a real keyboard's.
+#[cfg(target_os = "windows")]
+fn create_key_input(key: u16, key_up: bool) -> INPUT {
+    let vk = base_vk(key);
+    let mut flags = if key_up { KEYEVENTF_KEYUP } else { KEYBD_EVENT_FLAGS::default() };
+    let mut scan = 0;
+    if is_extended_key(key) {
+        flags |= KEYEVENTF_EXTENDEDKEY;
+        scan = unsafe { MapVirtualKeyW(vk as u32, MAPVK_VK_TO_VSC) } as u16;
+    }
+
     INPUT {
         r#type: INPUT_KEYBOARD,
         Anonymous: INPUT_0 {
             ki: KEYBDINPUT {
                 wVk: VIRTUAL_KEY(vk),
-                wScan: 0,
-                dwFlags: if key_up { KEYEVENTF_KEYUP } else { Default::default() },
+                wScan: scan,
+                dwFlags: flags,
                 time: 0,
                 dwExtraInfo: 0,
             },
@@ -179,23 +792,243 @@ mod tests {
     use super::*;
 
     #[test]
-    #[cfg(target_os = "windows")]
     fn test_parse_hotkey() {
         let keys = parse_hotkey("Ctrl+Shift+V").unwrap();
         assert_eq!(keys.len(), 3);
-        assert_eq!(keys[0], VK_CONTROL.0);
-        assert_eq!(keys[1], VK_SHIFT.0);
+        assert_eq!(keys[0], VK_CONTROL);
+        assert_eq!(keys[1], VK_SHIFT);
         assert_eq!(keys[2], 'V' as u16);
     }
 
     #[test]
-    #[cfg(target_os = "windows")]
+    fn test_text_keystrokes() {
+        assert_eq!(
+            text_keystrokes("a\tb\r\n"),
+            vec![
+                Keystroke::Unicode('a' as u16),
+                Keystroke::Key(0x09),
+                Keystroke::Unicode('b' as u16),
+                Keystroke::Key(0x0D),
+            ]
+        );
+        // Outside the BMP: one keystroke per surrogate
+        assert_eq!(
+            text_keystrokes("😀"),
+            vec![Keystroke::Unicode(0xD83D), Keystroke::Unicode(0xDE00)]
+        );
+    }
+
+    #[test]
+    fn test_parse_symbol_keys() {
+        assert_eq!(parse_hotkey("Ctrl++").unwrap(), vec![VK_CONTROL, 0xBB]);
+        assert_eq!(parse_hotkey("Ctrl+-").unwrap(), vec![VK_CONTROL, 0xBD]);
+        assert_eq!(parse_hotkey("Alt+/").unwrap(), vec![VK_MENU, 0xBF]);
+        assert_eq!(parse_hotkey("+").unwrap(), vec![0xBB]);
+        assert_eq!(parse_hotkey("Ctrl + Shift + +").unwrap(), vec![VK_CONTROL, VK_SHIFT, 0xBB]);
+        assert_eq!(parse_hotkey("Ctrl+\\").unwrap(), vec![VK_CONTROL, 0xDC]);
+
+        // A trailing separator is still an error, not the plus key
+        assert!(parse_hotkey("Ctrl+").is_err());
+        assert!(parse_hotkey("Ctrl+?").is_err());
+    }
+
+    #[test]
+    fn test_parse_sided_modifiers_and_numpad() {
+        assert_eq!(parse_hotkey("RAlt+F4").unwrap(), vec![0xA5, 0x73]);
+        assert_eq!(parse_hotkey("LCtrl+RShift+A").unwrap(), vec![0xA2, 0xA1, 'A' as u16]);
+        assert_eq!(parse_hotkey("Numpad5").unwrap(), vec![0x65]);
+        assert_eq!(parse_hotkey("num0+NumPlus").unwrap(), vec![0x60, 0x6B]);
+
+        // Numpad Enter is Enter plus the extended flag
+        let num_enter = parse_hotkey("NumEnter").unwrap()[0];
+        assert_eq!(base_vk(num_enter), 0x0D);
+        assert!(is_extended_key(num_enter));
+        assert!(!is_extended_key(parse_hotkey("Enter").unwrap()[0]));
+        assert!(is_extended_key(0xA5) && !is_extended_key(0xA4));
+    }
+
+    #[test]
+    fn test_key_map_built_once() {
+        // Repeated parses share one table instead of rebuilding (and
+        // re-allocating key names) on every press
+        let first = key_map() as *const _;
+        for _ in 0..1000 {
+            assert_eq!(parse_hotkey("F13").unwrap(), vec![0x7C]);
+        }
+        assert!(std::ptr::eq(first, key_map()));
+        assert_eq!(parse_hotkey("F1").unwrap(), vec![0x70]);
+        assert_eq!(parse_hotkey("F24").unwrap(), vec![0x87]);
+    }
+
+    #[test]
     fn test_parse_single_key() {
         let keys = parse_hotkey("F1").unwrap();
         assert_eq!(keys.len(), 1);
         assert_eq!(keys[0], 0x70); // VK_F1
     }
 
+    #[test]
+    fn test_preview_hotkey_resolves_names_and_codes() {
+        let preview = preview_hotkey("ctrl+alt+Delete").unwrap();
+        let resolved: Vec<(&str, u16)> = preview
+            .keys
+            .iter()
+            .map(|k| (k.name.as_str(), k.vk_code))
+            .collect();
+        assert_eq!(resolved, vec![("CTRL", VK_CONTROL), ("ALT", VK_MENU), ("DELETE", 0x2E)]);
+        assert!(preview.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_preview_hotkey_warnings() {
+        let preview = preview_hotkey("Ctrl+Shift").unwrap();
+        assert_eq!(preview.warnings, vec!["Hotkey only contains modifier keys".to_string()]);
+
+        let preview = preview_hotkey("Ctrl+A+B").unwrap();
+        assert_eq!(preview.warnings.len(), 1);
+
+        let preview = preview_hotkey("Ctrl+Control+C").unwrap();
+        assert!(preview.warnings.iter().any(|w| w.contains("more than once")));
+    }
+
+    #[test]
+    fn test_preview_hotkey_unknown_key() {
+        assert!(preview_hotkey("Ctrl+Banana").is_err());
+    }
+
+    #[test]
+    fn test_window_matches_title_or_process() {
+        assert!(window_matches("notepad", "Untitled - Notepad", "notepad.exe"));
+        assert!(window_matches("OBS64.EXE", "OBS 30.0 - Profile: Untitled", "obs64.exe"));
+        assert!(window_matches("profile", "OBS 30.0 - Profile: Untitled", "obs64.exe"));
+        assert!(!window_matches("spotify", "Untitled - Notepad", "notepad.exe"));
+    }
+
+    #[test]
+    fn test_key_message_lparam() {
+        // Key down: repeat count 1, scan code in bits 16-23
+        assert_eq!(key_message_lparam(0x1E, false), 0x001E_0001);
+        // Key up: also sets previous-state and transition bits
+        assert_eq!(key_message_lparam(0x1E, true) as u32, 0xC01E_0001);
+    }
+
+    #[test]
+    fn test_key_transitions_press_in_order_release_in_reverse() {
+        let keys = [VK_CONTROL, VK_SHIFT, 'V' as u16];
+        assert_eq!(
+            key_transitions(&keys),
+            vec![
+                (VK_CONTROL, false),
+                (VK_SHIFT, false),
+                ('V' as u16, false),
+                ('V' as u16, true),
+                (VK_SHIFT, true),
+                (VK_CONTROL, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_media_keys_route_as_app_commands() {
+        let keys = parse_hotkey("PlayPause").unwrap();
+        assert_eq!(media_commands(&keys), Some(vec![14]));
+        assert_eq!(app_command_lparam(14), 0x000E_0000);
+        assert_eq!(media_commands(&parse_hotkey("VolumeUp").unwrap()), Some(vec![10]));
+
+        // Anything else, even alongside a media key, uses the keyboard path
+        assert_eq!(media_commands(&parse_hotkey("Ctrl+NextTrack").unwrap()), None);
+        assert_eq!(media_commands(&parse_hotkey("Space").unwrap()), None);
+        assert_eq!(media_commands(&[]), None);
+    }
+
+    #[test]
+    fn test_hold_duration() {
+        assert_eq!(hold_duration(None), None);
+        assert_eq!(hold_duration(Some(0)), None);
+        assert_eq!(hold_duration(Some(250)), Some(Duration::from_millis(250)));
+        assert_eq!(hold_duration(Some(60_000)), Some(Duration::from_millis(MAX_HOLD_MS)));
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn test_build_key_messages_order() {
+        let keys = [VK_CONTROL, 'S' as u16];
+        let messages = build_key_messages(&keys, |_| 0x10);
+
+        assert_eq!(messages.len(), 4);
+        assert_eq!((messages[0].msg, messages[0].vk), (WM_KEYDOWN, VK_CONTROL));
+        assert_eq!((messages[1].msg, messages[1].vk), (WM_KEYDOWN, 'S' as u16));
+        assert_eq!((messages[2].msg, messages[2].vk), (WM_KEYUP, 'S' as u16));
+        assert_eq!((messages[3].msg, messages[3].vk), (WM_KEYUP, VK_CONTROL));
+        assert_eq!(messages[3].lparam, key_message_lparam(0x10, true));
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn test_build_key_messages_alt_combo_uses_syskey() {
+        let messages = build_key_messages(&parse_hotkey("Alt+F4").unwrap(), |_| 0x10);
+        let msgs: Vec<u32> = messages.iter().map(|m| m.msg).collect();
+        assert_eq!(msgs, vec![WM_SYSKEYDOWN, WM_SYSKEYDOWN, WM_SYSKEYUP, WM_SYSKEYUP]);
+        assert!(messages.iter().all(|m| m.lparam & (1 << 29) != 0));
+
+        let messages = build_key_messages(&parse_hotkey("Ctrl+Alt+Delete").unwrap(), |_| 0x10);
+        assert!(messages.iter().all(|m| m.msg == WM_KEYDOWN || m.msg == WM_KEYUP));
+    }
+
+    #[test]
+    fn test_is_system_key() {
+        assert!(is_system_key(&[VK_MENU]));
+        assert!(is_system_key(&[VK_LMENU, 0x73]));
+        assert!(!is_system_key(&[VK_CONTROL, VK_MENU, 0x2E]));
+        assert!(!is_system_key(&[VK_SHIFT, 'A' as u16]));
+        assert!(!is_system_key(&[]));
+    }
+
+    #[test]
+    fn test_reserved_hotkey_flagged_in_any_order() {
+        assert!(reserved_hotkey_conflict("Win+L").unwrap().contains("locks"));
+        assert!(reserved_hotkey_conflict("shift+win+s").is_some());
+        assert!(reserved_hotkey_conflict("Delete+Alt+Ctrl").is_some());
+        assert!(reserved_hotkey_conflict("Meta").is_some());
+    }
+
+    #[test]
+    fn test_unreserved_hotkey_not_flagged() {
+        assert!(reserved_hotkey_conflict("Ctrl+Shift+M").is_none());
+        assert!(reserved_hotkey_conflict("Win+Shift+L").is_none());
+        assert!(reserved_hotkey_conflict("L").is_none());
+        assert!(reserved_hotkey_conflict("NotAKey").is_none());
+    }
+
+    #[test]
+    fn test_find_hotkey_conflicts_only_checks_hotkey_actions() {
+        use crate::types::{ActionConfig, TriggerType};
+        let binding = |action_type: ActionType, path: &str| {
+            DeviceBinding::new(
+                "1234:5678".to_string(),
+                "1234".to_string(),
+                "5678".to_string(),
+                TriggerType::SinglePress,
+                ActionConfig {
+                    r#type: action_type,
+                    executable_path: path.to_string(),
+                    ..Default::default()
+                },
+            )
+        };
+        let locking = binding(ActionType::Hotkey, "Win+L");
+        let bindings = vec![
+            locking.clone(),
+            binding(ActionType::Hotkey, "Ctrl+C"),
+            binding(ActionType::LaunchApp, "Win+L"),
+        ];
+
+        let conflicts = find_hotkey_conflicts(&bindings);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].binding_id, locking.id);
+        assert_eq!(conflicts[0].hotkey, "Win+L");
+    }
+
     #[test]
     fn test_empty_hotkey() {
         let result = parse_hotkey("");
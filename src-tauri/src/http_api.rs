@@ -0,0 +1,422 @@
+// ============================================
+// HTTP Control API
+// Optional local server so home-automation tools, Stream Deck plugins and
+// scripts can trigger the same actions the physical buttons do. Bound to
+// 127.0.0.1 only and gated by `enable_http_api` plus a bearer token.
+//
+// Endpoints (every response body is an IpcResult as JSON):
+//   GET  /devices               connected HID devices
+//   GET  /bindings              saved bindings
+//   POST /trigger/{binding_id}  run a binding once (200 ok, 500 action failed)
+//
+// Every request needs `Authorization: Bearer <http_api_token>`; anything else
+// gets 401. Each connection is handled on its own thread, so a long-running
+// action doesn't hold up other requests; past `MAX_CONNECTIONS` at once, new
+// ones get 503.
+// ============================================
+
+use crate::executor::ActionExecutor;
+use crate::types::{AppSettings, IpcResult, LogEntryLevel};
+use crate::AppState;
+use serde::Serialize;
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Request heads larger than this are rejected (bodies are never read)
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// A client that connects but never finishes its request is dropped after this
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connections handled at once; more are turned away with 503
+const MAX_CONNECTIONS: usize = 8;
+
+/// Counts connections being handled, up to a limit
+#[derive(Clone)]
+pub struct ConnectionSlots {
+    in_use: Arc<AtomicUsize>,
+    max: usize,
+}
+
+/// A taken slot, given back on drop
+pub struct ConnectionSlot {
+    in_use: Arc<AtomicUsize>,
+}
+
+impl ConnectionSlots {
+    pub fn new(max: usize) -> Self {
+        Self {
+            in_use: Arc::new(AtomicUsize::new(0)),
+            max,
+        }
+    }
+
+    /// `None` while all slots are taken
+    pub fn try_acquire(&self) -> Option<ConnectionSlot> {
+        self.in_use
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < self.max).then_some(n + 1))
+            .ok()?;
+        Some(ConnectionSlot {
+            in_use: self.in_use.clone(),
+        })
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.in_use.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The parts of an HTTP request the API looks at
+#[derive(Debug, PartialEq)]
+pub struct HttpRequest {
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    /// Token from an `Authorization: Bearer` header
+    pub token: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Route {
+    Devices,
+    Bindings,
+    Trigger(String),
+    NotFound,
+    MethodNotAllowed,
+}
+
+/// Parse a request line and headers (everything before the blank line).
+/// Returns `None` for anything that isn't an HTTP/1.x request.
+pub fn parse_request(head: &str) -> Option<HttpRequest> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    if !request_line.next()?.starts_with("HTTP/1.") {
+        return None;
+    }
+
+    let path = target.split('?').next().unwrap_or_default().to_string();
+    let token = lines.take_while(|line| !line.is_empty()).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if !name.trim().eq_ignore_ascii_case("authorization") {
+            return None;
+        }
+        let (scheme, token) = value.trim().split_once(' ')?;
+        scheme.eq_ignore_ascii_case("bearer").then(|| token.trim().to_string())
+    });
+
+    Some(HttpRequest { method, path, token })
+}
+
+/// Map a method and path to an endpoint. Binding IDs are UUIDs, so path
+/// segments are used as-is without percent-decoding.
+pub fn route(method: &str, path: &str) -> Route {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let (allowed, route) = match segments.as_slice() {
+        ["devices"] => ("GET", Route::Devices),
+        ["bindings"] => ("GET", Route::Bindings),
+        ["trigger", id] if !id.is_empty() => ("POST", Route::Trigger(id.to_string())),
+        _ => return Route::NotFound,
+    };
+    if method == allowed {
+        route
+    } else {
+        Route::MethodNotAllowed
+    }
+}
+
+/// Compare tokens without bailing out at the first differing byte. An empty
+/// expected token never matches.
+pub fn token_matches(provided: Option<&str>, expected: &str) -> bool {
+    let Some(provided) = provided else {
+        return false;
+    };
+    if expected.is_empty() || provided.len() != expected.len() {
+        return false;
+    }
+    provided.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// A running server; `stop` shuts it down
+pub struct HttpApiServer {
+    port: u16,
+    token: String,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HttpApiServer {
+    pub fn start(app: AppHandle, port: u16, token: String) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let stop = stop.clone();
+            let token = token.clone();
+            std::thread::spawn(move || {
+                let slots = ConnectionSlots::new(MAX_CONNECTIONS);
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            log::warn!("HTTP API accept failed: {}", e);
+                            continue;
+                        }
+                    };
+                    let Some(slot) = slots.try_acquire() else {
+                        if let Err(e) = write_response(stream, error_response(503, "Too many requests in progress")) {
+                            log::warn!("HTTP API request failed: {}", e);
+                        }
+                        continue;
+                    };
+
+                    // Triggered actions may run for a while; keep accepting meanwhile
+                    let app = app.clone();
+                    let token = token.clone();
+                    std::thread::spawn(move || {
+                        let _slot = slot;
+                        if let Err(e) = handle_connection(&app, stream, &token) {
+                            log::warn!("HTTP API request failed: {}", e);
+                        }
+                    });
+                }
+            })
+        };
+
+        Ok(Self {
+            port,
+            token,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Whether this server already runs with the given port and token
+    pub fn matches(&self, port: u16, token: &str) -> bool {
+        self.port == port && self.token == token
+    }
+
+    /// Stop accepting. Requests already in progress finish on their own threads.
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the blocking accept so the thread sees the flag
+        let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, self.port));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Start, restart or stop the server to match `settings`. Only the primary
+/// instance serves the API.
+pub fn sync_http_api(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let is_primary = state.instance_lock.lock().map_err(|e| e.to_string())?.is_primary();
+    let wanted = (settings.enable_http_api && is_primary)
+        .then_some((settings.http_api_port, settings.http_api_token.as_str()));
+
+    let mut server = state.http_api.lock().map_err(|e| e.to_string())?;
+    if let (Some(running), Some((port, token))) = (server.as_ref(), wanted) {
+        if running.matches(port, token) {
+            return Ok(());
+        }
+    }
+
+    if let Some(running) = server.take() {
+        running.stop();
+        log::info!("HTTP API stopped");
+    }
+    if let Some((port, token)) = wanted {
+        let started = HttpApiServer::start(app.clone(), port, token.to_string())
+            .map_err(|e| format!("Failed to start HTTP API on 127.0.0.1:{}: {}", port, e))?;
+        *server = Some(started);
+        log::info!("HTTP API listening on 127.0.0.1:{}", port);
+    }
+    Ok(())
+}
+
+fn handle_connection(app: &AppHandle, mut stream: TcpStream, token: &str) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let response = match read_head(&mut stream)?.as_deref().and_then(parse_request) {
+        None => error_response(400, "Malformed request"),
+        Some(request) if !token_matches(request.token.as_deref(), token) => {
+            error_response(401, "Missing or invalid token")
+        }
+        Some(request) => dispatch(app, &request),
+    };
+    write_response(stream, response)
+}
+
+fn write_response(mut stream: TcpStream, (status, body): (u16, String)) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason_phrase(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Read up to the blank line ending the headers. `None` if the client closed
+/// early or sent too much.
+fn read_head(stream: &mut TcpStream) -> io::Result<Option<String>> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    loop {
+        let read = stream.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buffer[..read]);
+        if let Some(end) = head.windows(4).position(|w| w == b"\r\n\r\n") {
+            head.truncate(end);
+            return Ok(String::from_utf8(head).ok());
+        }
+        if head.len() > MAX_REQUEST_BYTES {
+            return Ok(None);
+        }
+    }
+}
+
+fn dispatch(app: &AppHandle, request: &HttpRequest) -> (u16, String) {
+    let state = app.state::<AppState>();
+    match route(&request.method, &request.path) {
+        Route::Devices => {
+            let mut hid = match state.hid_manager.lock() {
+                Ok(hid) => hid,
+                Err(e) => return error_response(500, &e.to_string()),
+            };
+            match hid.list_devices_cached() {
+                Ok(devices) => json_response(200, &IpcResult::ok(devices)),
+                Err(e) => error_response(500, &e.to_string()),
+            }
+        }
+        Route::Bindings => match state.config_manager.lock() {
+            Ok(config) => json_response(200, &IpcResult::ok(config.get_all_bindings())),
+            Err(e) => error_response(500, &e.to_string()),
+        },
+        Route::Trigger(binding_id) => trigger(&state, &binding_id),
+        Route::NotFound => error_response(404, "Not found"),
+        Route::MethodNotAllowed => error_response(405, "Method not allowed"),
+    }
+}
+
+fn trigger(state: &AppState, binding_id: &str) -> (u16, String) {
+    let binding = {
+        let mut config = match state.config_manager.lock() {
+            Ok(config) => config,
+            Err(e) => return error_response(500, &e.to_string()),
+        };
+        let Some(binding) = config.get_binding_by_id(binding_id) else {
+            return error_response(404, &format!("Binding not found: {}", binding_id));
+        };
+        // Same gates as a physical press
+        if !config.is_remapping_enabled() {
+            return error_response(409, "Remapping is paused");
+        }
+        if !binding.enabled {
+            return error_response(409, &format!("Binding {} is disabled", binding_id));
+        }
+
+        config.add_log(
+            LogEntryLevel::Info,
+            format!("HTTP API triggered binding {}", binding_id),
+            Some("HTTP".to_string()),
+        );
+        binding
+    };
+
+    let result = ActionExecutor::new(state.config_manager.clone()).run_binding(&binding, "HTTP");
+    let status = if result.succeeded { 200 } else { 500 };
+    json_response(status, &IpcResult::ok(result))
+}
+
+fn json_response<T: Serialize>(status: u16, result: &IpcResult<T>) -> (u16, String) {
+    match serde_json::to_string(result) {
+        Ok(body) => (status, body),
+        Err(e) => error_response(500, &format!("Failed to serialize response: {}", e)),
+    }
+}
+
+fn error_response(status: u16, error: &str) -> (u16, String) {
+    let result = IpcResult::<()>::err(error.to_string());
+    (status, serde_json::to_string(&result).unwrap_or_default())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        let request = parse_request("POST /trigger/abc?x=1 HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer s3cret").unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/trigger/abc");
+        assert_eq!(request.token.as_deref(), Some("s3cret"));
+
+        let request = parse_request("GET /devices HTTP/1.0\r\nauthorization: bearer  tok ").unwrap();
+        assert_eq!(request.token.as_deref(), Some("tok"));
+
+        assert_eq!(parse_request("GET /devices HTTP/1.1").unwrap().token, None);
+        assert!(parse_request("GET /devices\r\n").is_none());
+        assert!(parse_request("hello").is_none());
+    }
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route("GET", "/devices"), Route::Devices);
+        assert_eq!(route("GET", "/bindings/"), Route::Bindings);
+        assert_eq!(route("POST", "/trigger/abc-123"), Route::Trigger("abc-123".to_string()));
+        assert_eq!(route("GET", "/trigger/abc-123"), Route::MethodNotAllowed);
+        assert_eq!(route("POST", "/devices"), Route::MethodNotAllowed);
+        assert_eq!(route("POST", "/trigger/"), Route::NotFound);
+        assert_eq!(route("GET", "/"), Route::NotFound);
+    }
+
+    #[test]
+    fn test_connection_slots() {
+        let slots = ConnectionSlots::new(2);
+        let first = slots.try_acquire().unwrap();
+        let _second = slots.try_acquire().unwrap();
+        assert!(slots.try_acquire().is_none());
+
+        drop(first);
+        assert!(slots.try_acquire().is_some());
+    }
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches(Some("abc"), "abc"));
+        assert!(!token_matches(Some("abd"), "abc"));
+        assert!(!token_matches(Some("ab"), "abc"));
+        assert!(!token_matches(None, "abc"));
+        assert!(!token_matches(Some(""), ""));
+    }
+}
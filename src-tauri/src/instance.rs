@@ -0,0 +1,145 @@
+// ============================================
+// Single-Instance Guard
+// Two running copies would both register Raw Input and double-fire every
+// action, so only the instance holding the lock runs the background listener
+// ============================================
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Exclusive lock on a file in the config directory.
+/// The OS releases it when the process exits, so a crash never leaves it stale.
+#[derive(Debug)]
+pub enum InstanceLock {
+    /// We hold the lock (the file handle must stay open)
+    Primary(File, PathBuf),
+    /// Another process holds the lock
+    Secondary(PathBuf),
+}
+
+impl InstanceLock {
+    pub fn acquire(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+
+        match file.try_lock() {
+            Ok(()) => Ok(InstanceLock::Primary(file, path.to_path_buf())),
+            Err(TryLockError::WouldBlock) => Ok(InstanceLock::Secondary(path.to_path_buf())),
+            Err(TryLockError::Error(e)) => Err(e),
+        }
+    }
+
+    pub fn is_primary(&self) -> bool {
+        matches!(self, InstanceLock::Primary(..))
+    }
+
+    /// Give up the lock on a normal quit, once everything that must not run
+    /// twice has stopped, so a relaunch doesn't race our exit. A crash skips
+    /// this; the OS drops the lock with the process.
+    pub fn release(&mut self) {
+        if let InstanceLock::Primary(file, path) = self {
+            if let Err(e) = file.unlock() {
+                log::warn!("Failed to unlock instance lock: {}", e);
+            }
+            *self = InstanceLock::Secondary(std::mem::take(path));
+        }
+    }
+
+    /// If we're secondary, try again in case the other instance has exited.
+    /// Returns whether another instance still holds the lock.
+    pub fn other_instance_running(&mut self) -> bool {
+        if let InstanceLock::Secondary(path) = self {
+            match InstanceLock::acquire(path) {
+                Ok(lock) => *self = lock,
+                Err(e) => log::error!("Failed to re-check instance lock: {}", e),
+            }
+        }
+        !self.is_primary()
+    }
+}
+
+/// Bring the already-running instance's main window to the front
+#[cfg(target_os = "windows")]
+pub fn focus_existing_window() -> bool {
+    use windows::core::w;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        FindWindowW, SetForegroundWindow, ShowWindow, SW_RESTORE, SW_SHOW,
+    };
+
+    unsafe {
+        match FindWindowW(None, w!("USB Configurator")) {
+            Ok(hwnd) if !hwnd.0.is_null() => {
+                // The window may be hidden in the tray
+                let _ = ShowWindow(hwnd, SW_SHOW);
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+                SetForegroundWindow(hwnd).as_bool()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn focus_existing_window() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_lock_path() -> PathBuf {
+        std::env::temp_dir().join(format!("usb-configurator-lock-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_first_acquire_is_primary() {
+        let path = temp_lock_path();
+        let lock = InstanceLock::acquire(&path).unwrap();
+        assert!(lock.is_primary());
+
+        drop(lock);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_second_acquire_is_secondary_until_released() {
+        let path = temp_lock_path();
+        let first = InstanceLock::acquire(&path).unwrap();
+        let mut second = InstanceLock::acquire(&path).unwrap();
+
+        assert!(first.is_primary());
+        assert!(!second.is_primary());
+        assert!(second.other_instance_running());
+
+        // Once the first instance goes away, the second can take over
+        drop(first);
+        assert!(!second.other_instance_running());
+        assert!(second.is_primary());
+
+        drop(second);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_release_lets_another_instance_take_over() {
+        let path = temp_lock_path();
+        let mut first = InstanceLock::acquire(&path).unwrap();
+        first.release();
+        assert!(!first.is_primary());
+
+        // The handle is still open, but the lock is free
+        let second = InstanceLock::acquire(&path).unwrap();
+        assert!(second.is_primary());
+
+        // Releasing twice is harmless
+        first.release();
+
+        drop(second);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,58 @@
+// ============================================
+// Key Auto-Repeat
+// Raw Input delivers the OS's typematic repeats as ordinary key-down events
+// with no repeat flag, so a held key would look like a burst of presses.
+// A key-down for a key that hasn't been released yet is a repeat.
+// ============================================
+
+use std::collections::HashSet;
+
+/// Keys currently held down, per device
+#[derive(Debug, Default)]
+pub struct KeyRepeatTracker {
+    held: HashSet<(isize, u16)>, // (device handle, virtual key)
+}
+
+impl KeyRepeatTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a key-down. Returns true if it is an auto-repeat of a key the
+    /// device is still holding, false for a genuine press.
+    pub fn key_down(&mut self, device: isize, vkey: u16) -> bool {
+        !self.held.insert((device, vkey))
+    }
+
+    /// Record a key-up, so the next key-down counts as a new press
+    pub fn key_up(&mut self, device: isize, vkey: u16) {
+        self.held.remove(&(device, vkey));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_held_key_repeats_until_released() {
+        let mut tracker = KeyRepeatTracker::new();
+
+        assert!(!tracker.key_down(1, 0x41)); // genuine press
+        assert!(tracker.key_down(1, 0x41)); // OS auto-repeat
+        assert!(tracker.key_down(1, 0x41));
+
+        tracker.key_up(1, 0x41);
+        assert!(!tracker.key_down(1, 0x41)); // pressed again
+    }
+
+    #[test]
+    fn test_repeat_is_per_device_and_key() {
+        let mut tracker = KeyRepeatTracker::new();
+
+        assert!(!tracker.key_down(1, 0x41));
+        assert!(!tracker.key_down(2, 0x41)); // same key, other device
+        assert!(!tracker.key_down(1, 0x42)); // other key, same device
+        assert!(tracker.key_down(2, 0x41));
+    }
+}
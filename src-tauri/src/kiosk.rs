@@ -0,0 +1,88 @@
+// ============================================
+// Kiosk Lock
+// Locks the configuration so bindings and settings can't be changed in a
+// shared environment without the passphrase. Only a salted SHA-256 of the
+// passphrase is stored.
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Persisted lock state (lives in config.json, never sent to the frontend)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLock {
+    pub locked: bool,
+    pub passphrase_salt: Option<String>,
+    pub passphrase_hash: Option<String>,
+}
+
+impl ConfigLock {
+    /// A new lock; an empty or missing passphrase locks without one
+    pub fn new(passphrase: Option<&str>) -> Self {
+        match passphrase.filter(|p| !p.is_empty()) {
+            Some(passphrase) => {
+                let salt = uuid::Uuid::new_v4().to_string();
+                let hash = hash_passphrase(&salt, passphrase);
+                Self {
+                    locked: true,
+                    passphrase_salt: Some(salt),
+                    passphrase_hash: Some(hash),
+                }
+            }
+            None => Self {
+                locked: true,
+                passphrase_salt: None,
+                passphrase_hash: None,
+            },
+        }
+    }
+
+    pub fn has_passphrase(&self) -> bool {
+        self.passphrase_hash.is_some()
+    }
+
+    /// Check a passphrase. A lock without a passphrase accepts anything.
+    pub fn verify(&self, passphrase: &str) -> bool {
+        match (&self.passphrase_salt, &self.passphrase_hash) {
+            (Some(salt), Some(hash)) => hash_passphrase(salt, passphrase) == *hash,
+            _ => true,
+        }
+    }
+}
+
+fn hash_passphrase(salt: &str, passphrase: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(passphrase.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_passphrase() {
+        let lock = ConfigLock::new(Some("open sesame"));
+        assert!(lock.locked);
+        assert!(lock.has_passphrase());
+        assert!(lock.verify("open sesame"));
+        assert!(!lock.verify("Open Sesame"));
+        assert!(!lock.verify(""));
+        // Only the hash is stored
+        assert_ne!(lock.passphrase_hash.as_deref(), Some("open sesame"));
+    }
+
+    #[test]
+    fn test_lock_without_passphrase() {
+        let lock = ConfigLock::new(Some(""));
+        assert!(lock.locked);
+        assert!(!lock.has_passphrase());
+        assert!(lock.verify("anything"));
+    }
+}
@@ -0,0 +1,451 @@
+// ============================================
+// Launch With Window State
+// std::process can't choose a new window's initial show state, so LaunchApp
+// actions with a non-default `window_state` go through ShellExecuteExW
+// ============================================
+
+use crate::types::{ActionConfig, ActionType, LogEntryLevel, WindowState};
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::process::{Child, Command};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "windows")]
+use windows::core::HSTRING;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::Shell::{ShellExecuteExW, SEE_MASK_NOASYNC, SHELLEXECUTEINFOW};
+
+/// How often a process with a timeout is checked for exit
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+// ShowWindow nCmdShow values
+const SW_HIDE: i32 = 0;
+const SW_SHOWNORMAL: i32 = 1;
+const SW_SHOWMINIMIZED: i32 = 2;
+const SW_SHOWMAXIMIZED: i32 = 3;
+
+/// The `nShow` value passed to ShellExecuteExW
+pub fn show_command(state: WindowState) -> i32 {
+    match state {
+        WindowState::Normal => SW_SHOWNORMAL,
+        WindowState::Minimized => SW_SHOWMINIMIZED,
+        WindowState::Maximized => SW_SHOWMAXIMIZED,
+        WindowState::Hidden => SW_HIDE,
+    }
+}
+
+/// Whether a launch needs ShellExecuteExW; Normal keeps the plain spawn path
+pub fn needs_window_state(state: Option<WindowState>) -> bool {
+    state.is_some_and(|s| s != WindowState::Normal)
+}
+
+/// An action's working directory, if one is set. Errors if it doesn't
+/// exist, so the failure names the directory instead of surfacing as a
+/// confusing "file not found" from the spawn.
+pub fn working_directory(action: &ActionConfig) -> std::io::Result<Option<&Path>> {
+    let Some(dir) = action.working_directory.as_deref().map(str::trim).filter(|d| !d.is_empty()) else {
+        return Ok(None);
+    };
+    let dir = Path::new(dir);
+    if !dir.is_dir() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("Working directory {} does not exist", dir.display()),
+        ));
+    }
+    Ok(Some(dir))
+}
+
+/// Spawn `cmd` in the action's working directory with its extra environment
+/// variables
+pub fn spawn_with_options(mut cmd: Command, action: &ActionConfig) -> std::io::Result<Child> {
+    if let Some(dir) = working_directory(action)? {
+        cmd.current_dir(dir);
+    }
+    if let Some(env) = &action.env {
+        cmd.envs(env.iter().map(|(key, value)| (key, value)));
+    }
+    cmd.spawn()
+}
+
+/// An action's `timeout_ms`, if it has a non-zero one
+pub fn action_timeout(action: &ActionConfig) -> Option<Duration> {
+    action.timeout_ms.filter(|&ms| ms > 0).map(Duration::from_millis)
+}
+
+/// How a process waited on by `wait_with_timeout` ended
+#[derive(Debug, PartialEq)]
+pub enum WaitOutcome {
+    /// Exited by itself; `None` if it was ended by a signal
+    Exited(Option<i32>),
+    /// Still running at the timeout, so it was killed
+    Killed,
+    Failed(String),
+}
+
+/// Wait for `child`, killing it if it's still running after `timeout`.
+/// Only `child` itself is killed, not processes it started.
+pub fn wait_with_timeout(child: &mut Child, timeout: Duration) -> WaitOutcome {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return WaitOutcome::Exited(status.code()),
+            Ok(None) if Instant::now() >= deadline => {
+                return match child.kill() {
+                    Ok(()) => {
+                        let _ = child.wait();
+                        WaitOutcome::Killed
+                    }
+                    Err(e) => WaitOutcome::Failed(format!("Failed to kill process: {}", e)),
+                };
+            }
+            Ok(None) => thread::sleep(EXIT_POLL_INTERVAL),
+            Err(e) => return WaitOutcome::Failed(format!("Failed to wait for process: {}", e)),
+        }
+    }
+}
+
+/// Wait for `child` on a watcher thread so the caller isn't blocked, and
+/// hand the outcome to `on_done`
+pub fn watch_with_timeout(
+    mut child: Child,
+    timeout: Duration,
+    on_done: impl FnOnce(WaitOutcome) + Send + 'static,
+) -> JoinHandle<()> {
+    thread::spawn(move || on_done(wait_with_timeout(&mut child, timeout)))
+}
+
+/// Spawn `cmd` with the action's options and wait for it to exit, killing it
+/// if it's still running after `timeout`. Returns the exit code, or -1 if it
+/// was ended by a signal.
+pub fn run_to_exit(cmd: Command, action: &ActionConfig, timeout: Option<Duration>) -> Result<i32, String> {
+    let mut child = spawn_with_options(cmd, action).map_err(|e| format!("Failed to start: {}", e))?;
+    let Some(timeout) = timeout else {
+        let status = child.wait().map_err(|e| format!("Failed to wait for process: {}", e))?;
+        return Ok(status.code().unwrap_or(-1));
+    };
+    match wait_with_timeout(&mut child, timeout) {
+        WaitOutcome::Exited(code) => Ok(code.unwrap_or(-1)),
+        WaitOutcome::Killed => Err(format!("Timed out after {}ms and was stopped", timeout.as_millis())),
+        WaitOutcome::Failed(e) => Err(e),
+    }
+}
+
+/// Log level and message for a watched process
+pub fn describe_wait_outcome(path: &str, timeout: Duration, outcome: &WaitOutcome) -> (LogEntryLevel, String) {
+    match outcome {
+        WaitOutcome::Exited(Some(0)) => (LogEntryLevel::Info, format!("{} finished within its timeout", path)),
+        WaitOutcome::Exited(Some(code)) => (LogEntryLevel::Warn, format!("{} exited with code {}", path, code)),
+        WaitOutcome::Exited(None) => (LogEntryLevel::Warn, format!("{} was terminated without an exit code", path)),
+        WaitOutcome::Killed => (
+            LogEntryLevel::Warn,
+            format!("{} was still running after {}ms and was killed", path, timeout.as_millis()),
+        ),
+        WaitOutcome::Failed(e) => (LogEntryLevel::Error, format!("{}: {}", path, e)),
+    }
+}
+
+/// Program that shows a folder in the OS file manager
+fn file_manager() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Show `dir` in Explorer, Finder or the desktop's file manager. Doesn't
+/// wait: explorer.exe exits non-zero even when it opened the folder.
+pub fn open_in_file_manager(dir: &Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    Command::new(file_manager())
+        .arg(dir)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", dir.display(), e))
+}
+
+/// Start `path` with `arguments` (passed through as one string) in the given
+/// state. ShellExecuteExW can't take extra environment variables, so only
+/// the working directory applies here.
+pub fn launch_with_window_state(
+    path: &str,
+    arguments: &str,
+    directory: Option<&Path>,
+    state: WindowState,
+) -> Result<(), String> {
+    shell_execute(None, path, arguments, directory, state)
+}
+
+/// Whether an action asks to run elevated. Only actions that start a
+/// process can be.
+pub fn wants_elevation(action: &ActionConfig) -> bool {
+    action.run_as_admin == Some(true)
+        && matches!(
+            action.r#type,
+            ActionType::LaunchApp | ActionType::RunScript | ActionType::SystemCommand
+        )
+}
+
+/// File and parameters an elevated action runs. Scripts and system commands
+/// go through cmd, as they do when not elevated.
+pub fn elevated_command(action: &ActionConfig) -> (String, String) {
+    let target = match action.r#type {
+        ActionType::RunScript => format!("/C \"{}\"", action.executable_path),
+        ActionType::SystemCommand => format!("/C {}", action.executable_path),
+        _ => return (action.executable_path.clone(), action.arguments.clone()),
+    };
+    let parameters = if action.arguments.is_empty() {
+        target
+    } else {
+        format!("{} {}", target, action.arguments)
+    };
+    ("cmd".to_string(), parameters)
+}
+
+/// Start an action with the `runas` verb so Windows prompts for elevation.
+/// Success only means the shell accepted the request; unlike a spawn, the
+/// elevated process can't be observed.
+pub fn launch_elevated(action: &ActionConfig) -> Result<(), String> {
+    let directory = working_directory(action).map_err(|e| e.to_string())?;
+    let (file, parameters) = elevated_command(action);
+    shell_execute(
+        Some("runas"),
+        &file,
+        &parameters,
+        directory,
+        action.window_state.unwrap_or_default(),
+    )
+}
+
+fn shell_execute(
+    verb: Option<&str>,
+    path: &str,
+    arguments: &str,
+    directory: Option<&Path>,
+    state: WindowState,
+) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let verb = verb.map(HSTRING::from);
+        let file = HSTRING::from(path);
+        let parameters = HSTRING::from(arguments);
+        let directory = directory.map(HSTRING::from);
+        let mut info = SHELLEXECUTEINFOW {
+            cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+            fMask: SEE_MASK_NOASYNC,
+            lpFile: (&file).into(),
+            lpParameters: (&parameters).into(),
+            nShow: show_command(state),
+            ..Default::default()
+        };
+        if let Some(verb) = &verb {
+            info.lpVerb = verb.into();
+        }
+        if let Some(directory) = &directory {
+            info.lpDirectory = directory.into();
+        }
+
+        unsafe { ShellExecuteExW(&mut info) }.map_err(|e| format!("Failed to launch {}: {}", path, e))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, arguments, directory, state);
+        Err(match verb {
+            Some(_) => "Elevation is only supported on Windows".to_string(),
+            None => "Window states are only supported on Windows".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_show_command_mapping() {
+        assert_eq!(show_command(WindowState::Normal), 1);
+        assert_eq!(show_command(WindowState::Minimized), 2);
+        assert_eq!(show_command(WindowState::Maximized), 3);
+        assert_eq!(show_command(WindowState::Hidden), 0);
+    }
+
+    #[test]
+    fn test_needs_window_state() {
+        assert!(!needs_window_state(None));
+        assert!(!needs_window_state(Some(WindowState::Normal)));
+        assert!(needs_window_state(Some(WindowState::Maximized)));
+        assert!(needs_window_state(Some(WindowState::Hidden)));
+    }
+
+    fn action_in(dir: Option<&str>) -> ActionConfig {
+        ActionConfig {
+            r#type: ActionType::SystemCommand,
+            executable_path: "pwd".to_string(),
+            working_directory: dir.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_action_timeout() {
+        let mut action = action_in(None);
+        assert_eq!(action_timeout(&action), None);
+        action.timeout_ms = Some(0);
+        assert_eq!(action_timeout(&action), None);
+        action.timeout_ms = Some(1500);
+        assert_eq!(action_timeout(&action), Some(Duration::from_millis(1500)));
+    }
+
+    /// A command that sleeps for `seconds`
+    fn sleep_command(seconds: u32) -> Command {
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut cmd = Command::new("ping");
+            cmd.args(["-n", &(seconds + 1).to_string(), "127.0.0.1"]);
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", &format!("sleep {}", seconds)]);
+            cmd
+        };
+        cmd.stdout(std::process::Stdio::null());
+        cmd
+    }
+
+    /// A process that sleeps for `seconds`, or `None` where there's no
+    /// shell to start one with
+    fn sleeping_child(seconds: u32) -> Option<Child> {
+        sleep_command(seconds).spawn().ok()
+    }
+
+    #[test]
+    fn test_watch_kills_long_running_process() {
+        let Some(child) = sleeping_child(10) else {
+            return; // No shell available
+        };
+        let started = Instant::now();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let watcher = watch_with_timeout(child, Duration::from_millis(200), move |outcome| {
+            let _ = sender.send(outcome);
+        });
+
+        // The caller isn't held up while the process runs
+        assert!(started.elapsed() < Duration::from_millis(200));
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(WaitOutcome::Killed));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        watcher.join().unwrap();
+    }
+
+    #[test]
+    fn test_run_to_exit_stops_hung_process() {
+        let started = Instant::now();
+        let result = run_to_exit(sleep_command(10), &action_in(None), Some(Duration::from_millis(200)));
+        if result.as_ref().is_err_and(|e| e.starts_with("Failed to start")) {
+            return; // No shell available
+        }
+        assert_eq!(result, Err("Timed out after 200ms and was stopped".to_string()));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_run_to_exit_uses_working_directory_and_env() {
+        if cfg!(target_os = "windows") {
+            return;
+        }
+        let dir = std::fs::canonicalize(std::env::temp_dir()).unwrap();
+        let mut action = action_in(Some(dir.to_str().unwrap()));
+        action.env = Some(vec![("EXPECTED_DIR".to_string(), dir.to_string_lossy().into_owned())]);
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "[ \"$(pwd -P)\" = \"$EXPECTED_DIR\" ] && exit 7"]);
+        let result = run_to_exit(cmd, &action, None);
+        if result.as_ref().is_err_and(|e| e.starts_with("Failed to start")) {
+            return;
+        }
+        assert_eq!(result, Ok(7));
+    }
+
+    #[test]
+    fn test_wait_lets_quick_process_finish() {
+        let Some(mut child) = sleeping_child(0) else {
+            return;
+        };
+        assert_eq!(wait_with_timeout(&mut child, Duration::from_secs(5)), WaitOutcome::Exited(Some(0)));
+    }
+
+    #[test]
+    fn test_describe_wait_outcome() {
+        let timeout = Duration::from_millis(500);
+        let (level, message) = describe_wait_outcome("backup.bat", timeout, &WaitOutcome::Killed);
+        assert_eq!(level, LogEntryLevel::Warn);
+        assert_eq!(message, "backup.bat was still running after 500ms and was killed");
+        assert_eq!(describe_wait_outcome("x", timeout, &WaitOutcome::Exited(Some(0))).0, LogEntryLevel::Info);
+        assert_eq!(describe_wait_outcome("x", timeout, &WaitOutcome::Exited(Some(2))).0, LogEntryLevel::Warn);
+    }
+
+    #[test]
+    fn test_working_directory_must_exist() {
+        let temp = std::env::temp_dir();
+        let existing = temp.to_string_lossy().to_string();
+        assert_eq!(working_directory(&action_in(Some(&existing))).unwrap(), Some(temp.as_path()));
+        assert_eq!(working_directory(&action_in(None)).unwrap(), None);
+        assert_eq!(working_directory(&action_in(Some("  "))).unwrap(), None);
+
+        let missing = temp.join("usb-configurator-no-such-dir");
+        let err = working_directory(&action_in(Some(&missing.to_string_lossy()))).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(err.to_string().contains("usb-configurator-no-such-dir"));
+    }
+
+    #[test]
+    fn test_elevated_command() {
+        let mut action = action_in(None);
+        action.run_as_admin = Some(true);
+        action.executable_path = "ipconfig".to_string();
+        action.arguments = "/flushdns".to_string();
+        assert!(wants_elevation(&action));
+        assert_eq!(elevated_command(&action), ("cmd".to_string(), "/C ipconfig /flushdns".to_string()));
+
+        action.r#type = ActionType::RunScript;
+        action.executable_path = r"C:\Scripts\fix net.bat".to_string();
+        action.arguments = String::new();
+        assert_eq!(
+            elevated_command(&action),
+            ("cmd".to_string(), r#"/C "C:\Scripts\fix net.bat""#.to_string())
+        );
+
+        action.r#type = ActionType::LaunchApp;
+        action.executable_path = r"C:\Tools\regedit.exe".to_string();
+        action.arguments = "/m".to_string();
+        assert_eq!(
+            elevated_command(&action),
+            (r"C:\Tools\regedit.exe".to_string(), "/m".to_string())
+        );
+
+        // Only process launches elevate
+        action.r#type = ActionType::Hotkey;
+        assert!(!wants_elevation(&action));
+        action.r#type = ActionType::LaunchApp;
+        action.run_as_admin = Some(false);
+        assert!(!wants_elevation(&action));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_with_options_sets_dir_and_env() {
+        let temp = std::env::temp_dir().canonicalize().unwrap();
+        let mut action = action_in(Some(&temp.to_string_lossy()));
+        action.env = Some(vec![("USB_CONFIGURATOR_TEST".to_string(), "42".to_string())]);
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "pwd -P; echo $USB_CONFIGURATOR_TEST"]).stdout(std::process::Stdio::piped());
+        let output = spawn_with_options(cmd, &action).unwrap().wait_with_output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout, format!("{}\n42\n", temp.display()));
+    }
+}
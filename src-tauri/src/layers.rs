@@ -0,0 +1,153 @@
+// ============================================
+// Layers
+// A binding can set/clear/toggle a named layer; other bindings can require
+// a layer to be active before they fire (like a Fn/shift key on a keypad)
+// ============================================
+
+use crate::types::{ActionType, DeviceBinding};
+use std::collections::HashSet;
+
+/// Currently active layers, tracked by the background listener
+#[derive(Debug, Default, Clone)]
+pub struct LayerState {
+    active: HashSet<String>,
+}
+
+impl LayerState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restore from a persisted list of layer names
+    pub fn from_names(names: &[String]) -> Self {
+        Self {
+            active: names.iter().cloned().collect(),
+        }
+    }
+
+    pub fn set(&mut self, layer: &str) {
+        self.active.insert(layer.to_string());
+    }
+
+    pub fn clear(&mut self, layer: &str) {
+        self.active.remove(layer);
+    }
+
+    /// Flip a layer on/off, returning whether it is now active
+    pub fn toggle(&mut self, layer: &str) -> bool {
+        if self.active.remove(layer) {
+            false
+        } else {
+            self.active.insert(layer.to_string());
+            true
+        }
+    }
+
+    pub fn is_active(&self, layer: &str) -> bool {
+        self.active.contains(layer)
+    }
+
+    /// Active layer names, sorted for stable persistence
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.active.iter().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Apply a layer action (SetLayer/ClearLayer/ToggleLayer) using the layer
+    /// name stored in `executable_path`. Returns false for non-layer actions.
+    pub fn apply(&mut self, action_type: &ActionType, layer: &str) -> bool {
+        match action_type {
+            ActionType::SetLayer => self.set(layer),
+            ActionType::ClearLayer => self.clear(layer),
+            ActionType::ToggleLayer => {
+                self.toggle(layer);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Whether a binding's `required_layer` (if any) is currently active
+    pub fn binding_allowed(&self, binding: &DeviceBinding) -> bool {
+        required_layer(binding).is_none_or(|layer| self.is_active(layer))
+    }
+}
+
+/// The layer a binding requires, if any (an empty name counts as none)
+pub fn required_layer(binding: &DeviceBinding) -> Option<&str> {
+    binding.required_layer.as_deref().filter(|layer| !layer.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ActionConfig, TriggerType};
+
+    fn binding(required_layer: Option<&str>) -> DeviceBinding {
+        let mut binding = DeviceBinding::new(
+            "1234:5678".to_string(),
+            "1234".to_string(),
+            "5678".to_string(),
+            TriggerType::SinglePress,
+            ActionConfig {
+                r#type: ActionType::Hotkey,
+                executable_path: "Ctrl+C".to_string(),
+                ..Default::default()
+            },
+        );
+        binding.required_layer = required_layer.map(|s| s.to_string());
+        binding
+    }
+
+    #[test]
+    fn test_set_clear_toggle() {
+        let mut layers = LayerState::new();
+        assert!(!layers.is_active("fn"));
+
+        layers.set("fn");
+        assert!(layers.is_active("fn"));
+        layers.clear("fn");
+        assert!(!layers.is_active("fn"));
+
+        assert!(layers.toggle("fn"));
+        assert!(layers.is_active("fn"));
+        assert!(!layers.toggle("fn"));
+        assert!(!layers.is_active("fn"));
+    }
+
+    #[test]
+    fn test_apply_layer_actions() {
+        let mut layers = LayerState::new();
+        assert!(layers.apply(&ActionType::SetLayer, "media"));
+        assert!(layers.is_active("media"));
+        assert!(layers.apply(&ActionType::ToggleLayer, "media"));
+        assert!(!layers.is_active("media"));
+        assert!(!layers.apply(&ActionType::Hotkey, "media"));
+    }
+
+    #[test]
+    fn test_layer_gated_matching() {
+        let mut layers = LayerState::new();
+        let ungated = binding(None);
+        let gated = binding(Some("fn"));
+
+        assert!(layers.binding_allowed(&ungated));
+        assert!(!layers.binding_allowed(&gated));
+
+        layers.set("fn");
+        assert!(layers.binding_allowed(&ungated));
+        assert!(layers.binding_allowed(&gated));
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        let mut layers = LayerState::new();
+        layers.set("b");
+        layers.set("a");
+        assert_eq!(layers.names(), vec!["a".to_string(), "b".to_string()]);
+
+        let restored = LayerState::from_names(&layers.names());
+        assert!(restored.is_active("a") && restored.is_active("b"));
+    }
+}
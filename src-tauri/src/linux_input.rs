@@ -0,0 +1,214 @@
+// ============================================
+// Linux Input Monitoring
+// Reads EV_KEY events from /dev/input/event* (evdev) and resolves the
+// originating device's VID:PID from sysfs. The event nodes are usually
+// readable only by root and the `input` group.
+// ============================================
+
+use crate::hid::HidError;
+use crate::input_monitor::InputMonitor;
+use crate::types::{DeviceStatus, HidDevice};
+use evdev::{Device, EventType};
+use std::io::ErrorKind;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const INPUT_DIR: &str = "/dev/input";
+const SYSFS_INPUT_CLASS: &str = "/sys/class/input";
+
+/// EV_KEY value for a key going down (0 is release, 2 is auto-repeat)
+const KEY_PRESSED: i32 = 1;
+
+/// Pause between passes over the (non-blocking) event nodes
+const POLL_INTERVAL_MS: u64 = 10;
+
+pub struct LinuxInputMonitor {
+    devices: Vec<(PathBuf, Device)>,
+    monitoring_active: Arc<AtomicBool>,
+}
+
+impl LinuxInputMonitor {
+    /// Open every event node that reports key events. Fails with a
+    /// `PermissionDenied` explaining the fix when nodes exist but none could
+    /// be opened, rather than monitoring nothing.
+    pub fn open(monitoring_active: Arc<AtomicBool>) -> Result<Self, HidError> {
+        let entries = std::fs::read_dir(INPUT_DIR)
+            .map_err(|e| HidError::InitError(format!("Cannot read {}: {}", INPUT_DIR, e)))?;
+
+        let mut devices = Vec::new();
+        let mut denied = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !is_event_node(&path) {
+                continue;
+            }
+
+            match Device::open(&path) {
+                Ok(device) => {
+                    if !device.supported_events().contains(EventType::KEY) {
+                        continue;
+                    }
+                    if let Err(e) = set_nonblocking(&device) {
+                        log::warn!("Cannot make {} non-blocking: {}", path.display(), e);
+                        continue;
+                    }
+                    devices.push((path, device));
+                }
+                Err(e) if e.kind() == ErrorKind::PermissionDenied => denied += 1,
+                Err(e) => log::debug!("Cannot open {}: {}", path.display(), e),
+            }
+        }
+
+        if devices.is_empty() && denied > 0 {
+            return Err(HidError::PermissionDenied(format!(
+                "cannot read any of {} input devices in {}. Add your user to the 'input' group \
+                 (then log out and back in) or install a udev rule granting access",
+                denied, INPUT_DIR
+            )));
+        }
+
+        log::info!("Monitoring {} evdev input devices", devices.len());
+        Ok(Self {
+            devices,
+            monitoring_active,
+        })
+    }
+}
+
+impl InputMonitor for LinuxInputMonitor {
+    fn start_monitoring(&mut self) -> Receiver<HidDevice> {
+        let (tx, rx) = channel();
+        let monitoring = self.monitoring_active.clone();
+        let mut devices = std::mem::take(&mut self.devices);
+        monitoring.store(true, Ordering::SeqCst);
+
+        thread::spawn(move || {
+            log::info!("evdev monitoring thread started");
+
+            while monitoring.load(Ordering::SeqCst) {
+                for (path, device) in devices.iter_mut() {
+                    let pressed = match device.fetch_events() {
+                        Ok(mut events) => events
+                            .find(|e| e.event_type() == EventType::KEY && e.value() == KEY_PRESSED)
+                            .is_some(),
+                        Err(e) if e.kind() == ErrorKind::WouldBlock => false,
+                        Err(e) => {
+                            // Typically the device was unplugged
+                            log::trace!("Read error on {}: {}", path.display(), e);
+                            false
+                        }
+                    };
+                    if !pressed {
+                        continue;
+                    }
+
+                    let detected = device_from_evdev(path, device);
+                    log::info!(
+                        "Input detected from: {} ({}:{})",
+                        detected.name,
+                        detected.vendor_id,
+                        detected.product_id
+                    );
+
+                    // Stop after the first detection, like the other monitors
+                    monitoring.store(false, Ordering::SeqCst);
+                    let _ = tx.send(detected);
+                    return;
+                }
+
+                thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+            }
+
+            log::info!("evdev monitoring thread stopped");
+        });
+
+        rx
+    }
+
+    fn stop_monitoring(&self) {
+        self.monitoring_active.store(false, Ordering::SeqCst);
+    }
+
+    fn name(&self) -> &str {
+        "evdev"
+    }
+}
+
+fn is_event_node(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with("event"))
+}
+
+fn set_nonblocking(device: &Device) -> std::io::Result<()> {
+    let fd = device.as_raw_fd();
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags < 0 || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Parse a sysfs id file (`vendor`, `product`), which holds 4 hex digits
+fn parse_sysfs_hex(contents: &str) -> Option<u16> {
+    u16::from_str_radix(contents.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// Read `/sys/class/input/eventN/device/id/<field>`
+fn sysfs_id(event_node: &Path, field: &str) -> Option<u16> {
+    let name = event_node.file_name()?;
+    let path = Path::new(SYSFS_INPUT_CLASS).join(name).join("device/id").join(field);
+    parse_sysfs_hex(&std::fs::read_to_string(path).ok()?)
+}
+
+/// Build a `HidDevice` for an event node. VID:PID come from sysfs, falling
+/// back to the IDs evdev reports for the node.
+fn device_from_evdev(path: &Path, device: &Device) -> HidDevice {
+    let input_id = device.input_id();
+    let vendor_id = format!("{:04X}", sysfs_id(path, "vendor").unwrap_or(input_id.vendor()));
+    let product_id = format!("{:04X}", sysfs_id(path, "product").unwrap_or(input_id.product()));
+
+    HidDevice {
+        id: format!("{}:{}", vendor_id, product_id),
+        name: device.name().unwrap_or("Unknown Device").to_string(),
+        vendor_id,
+        product_id,
+        interface_number: 0,
+        total_interfaces: 1,
+        status: DeviceStatus::Connected,
+        manufacturer: None,
+        // The kernel exposes the USB serial as the device's "uniq" string
+        serial_number: device.unique_name().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string),
+        consumer_usage: None,
+        raw_report: Vec::new(),
+        is_repeat: false,
+        button_code: None, // evdev key codes aren't Windows virtual keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sysfs_hex() {
+        assert_eq!(parse_sysfs_hex("046d\n"), Some(0x046D));
+        assert_eq!(parse_sysfs_hex("0xAF88"), Some(0xAF88));
+        assert_eq!(parse_sysfs_hex(""), None);
+        assert_eq!(parse_sysfs_hex("zzzz"), None);
+    }
+
+    #[test]
+    fn test_is_event_node() {
+        assert!(is_event_node(Path::new("/dev/input/event3")));
+        assert!(!is_event_node(Path::new("/dev/input/mice")));
+        assert!(!is_event_node(Path::new("/dev/input/by-id")));
+    }
+}
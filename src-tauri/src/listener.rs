@@ -1,104 +1,494 @@
-use crate::config::ConfigManager;
+use crate::config::{build_device_id, endpoint_device_id, BindingQuery, ConfigManager};
+use crate::consumer;
+use crate::confirm;
+use crate::executor::{describe_action, ActionExecutor, Completion, ExecutionContext};
+use crate::hid;
+use crate::layers::LayerState;
+use crate::notifications::{self, NotificationThrottle};
+use crate::plugins;
 use crate::rawinput::RawInputMonitor;
-use crate::types::{ActionConfig, ActionType, LogEntryLevel, TriggerType};
+use crate::types::{AppSettings, HidDevice, LogEntryLevel, TriggerType};
 use std::collections::HashMap;
-use std::process::Command;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use windows::Win32::System::Diagnostics::Debug::MessageBeep;
+use windows::Win32::UI::WindowsAndMessaging::MB_ICONWARNING;
 
-/// Parse arguments string respecting quoted sections
-/// Examples:
-///   `arg1 arg2` -> ["arg1", "arg2"]
-///   `"path with spaces" arg2` -> ["path with spaces", "arg2"]
-///   `arg1 "quoted arg"` -> ["arg1", "quoted arg"]
-fn parse_arguments(args: &str) -> Vec<String> {
-    let mut result = Vec::new();
-    let mut current = String::new();
-    let mut in_quotes = false;
-
-    for c in args.chars() {
-        match c {
-            '"' => in_quotes = !in_quotes,
-            ' ' | '\t' if !in_quotes => {
-                if !current.is_empty() {
-                    result.push(current.clone());
-                    current.clear();
+/// Reports longer than this are cut short when logged
+const MAX_LOGGED_REPORT_BYTES: usize = 64;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ActionConfig, ActionType, DeviceBinding};
+
+    #[test]
+    fn test_trigger_for_press_count() {
+        assert_eq!(trigger_for_press_count(1), TriggerType::SinglePress);
+        assert_eq!(trigger_for_press_count(2), TriggerType::DoublePress);
+        assert_eq!(trigger_for_press_count(3), TriggerType::TriplePress);
+        assert_eq!(trigger_for_press_count(5), TriggerType::TriplePress);
+    }
+
+    fn key_binding(trigger: TriggerType, button_code: u16) -> DeviceBinding {
+        let mut binding = DeviceBinding::new(
+            "1234:5678".to_string(),
+            "1234".to_string(),
+            "5678".to_string(),
+            trigger,
+            ActionConfig {
+                r#type: ActionType::LaunchApp,
+                executable_path: "notepad.exe".to_string(),
+                ..Default::default()
+            },
+        );
+        binding.button_code = Some(button_code);
+        binding
+    }
+
+    #[test]
+    fn test_each_burst_runs_exactly_one_binding() {
+        let dir = std::env::temp_dir().join(format!("usb-configurator-test-{}", uuid::Uuid::new_v4()));
+        let mut config = ConfigManager::with_dir(dir.clone()).unwrap();
+        for trigger in [TriggerType::SinglePress, TriggerType::DoublePress, TriggerType::TriplePress] {
+            config.save_binding(key_binding(trigger, 0x41)).unwrap();
+        }
+        let query = BindingQuery {
+            button_code: Some(0x41),
+            ..BindingQuery::device("1234:5678")
+        };
+        assert_eq!(highest_bound_count(&config, &query), 3);
+
+        let window = Duration::from_millis(300);
+        let gap = Duration::from_millis(100);
+        for presses in 1..=3u32 {
+            let start = Instant::now();
+            let mut bursts = PressBursts::default();
+            let mut completed = Vec::new();
+            for i in 0..presses {
+                let now = start + gap * i;
+                completed.extend(bursts.take_expired(window, now));
+                if bursts.press("1234:5678#41", (), window, now) >= highest_bound_count(&config, &query) {
+                    completed.extend(bursts.take("1234:5678#41"));
                 }
             }
-            _ => current.push(c),
+            // No further press: the window runs out
+            let last_press = start + gap * (presses - 1);
+            completed.extend(bursts.take_expired(window, last_press + window));
+
+            // Matched the way the listener does
+            let runs: Vec<TriggerType> = completed
+                .into_iter()
+                .filter_map(|(count, ())| {
+                    let trigger = trigger_for_press_count(count);
+                    config
+                        .find_binding(&BindingQuery { trigger_type: Some(&trigger), ..query })
+                        .filter(|binding| binding.trigger_type == trigger)
+                        .map(|binding| binding.trigger_type)
+                })
+                .collect();
+            assert_eq!(runs, vec![trigger_for_press_count(presses)], "{} press(es)", presses);
         }
+
+        let _ = std::fs::remove_dir_all(dir);
     }
 
-    if !current.is_empty() {
-        result.push(current);
+    #[test]
+    fn test_single_press_only_key_fires_without_waiting() {
+        let dir = std::env::temp_dir().join(format!("usb-configurator-test-{}", uuid::Uuid::new_v4()));
+        let mut config = ConfigManager::with_dir(dir.clone()).unwrap();
+        config.save_binding(key_binding(TriggerType::SinglePress, 0x42)).unwrap();
+        config.save_binding(key_binding(TriggerType::DoublePress, 0x43)).unwrap();
+
+        let query = |button_code| BindingQuery {
+            button_code: Some(button_code),
+            ..BindingQuery::device("1234:5678")
+        };
+        assert_eq!(highest_bound_count(&config, &query(0x42)), 1);
+        assert_eq!(highest_bound_count(&config, &query(0x43)), 2);
+        assert_eq!(highest_bound_count(&config, &query(0x44)), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
     }
 
-    result
-}
+    #[test]
+    fn test_press_bursts_expire_per_key() {
+        let window = Duration::from_millis(300);
+        let start = Instant::now();
+        let mut bursts = PressBursts::default();
+
+        assert_eq!(bursts.press("a", 1, window, start), 1);
+        assert_eq!(bursts.press("b", 1, window, start + Duration::from_millis(100)), 1);
+        assert_eq!(bursts.press("a", 2, window, start + Duration::from_millis(200)), 2);
+        assert_eq!(bursts.next_deadline(window), Some(start + Duration::from_millis(400)));
+
+        assert_eq!(bursts.take_expired(window, start + Duration::from_millis(450)), vec![(1, 1)]);
+        assert_eq!(bursts.take_expired(window, start + Duration::from_millis(500)), vec![(2, 2)]);
+        assert_eq!(bursts.next_deadline(window), None);
+
+        // A press after the window starts over
+        assert_eq!(bursts.press("a", 3, window, start + Duration::from_secs(1)), 1);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_arming_gate_delays_presses() {
+        let start = Instant::now();
+        let gate = ArmingGate::new(start, Duration::from_millis(500));
+
+        assert!(!gate.is_armed(start));
+        assert!(!gate.is_armed(start + Duration::from_millis(499)));
+        assert!(gate.is_armed(start + Duration::from_millis(500)));
+        assert!(gate.is_armed(start + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_activity_throttle_limits_per_window() {
+        let start = Instant::now();
+        let mut throttle = ActivityThrottle::new(Duration::from_millis(1000), 3);
+
+        assert!(throttle.should_emit("AF88:6688", start));
+        assert!(throttle.should_emit("AF88:6688", start + Duration::from_millis(10)));
+        assert!(throttle.should_emit("AF88:6688", start + Duration::from_millis(20)));
+        assert!(!throttle.should_emit("AF88:6688", start + Duration::from_millis(30)));
+        assert!(!throttle.should_emit("AF88:6688", start + Duration::from_millis(999)));
+
+        // Other devices have their own window
+        assert!(throttle.should_emit("1234:5678", start + Duration::from_millis(30)));
+
+        // A new window starts once the old one has elapsed
+        assert!(throttle.should_emit("AF88:6688", start + Duration::from_millis(1000)));
+    }
 
     #[test]
-    fn test_parse_simple_args() {
-        let result = parse_arguments("arg1 arg2 arg3");
-        assert_eq!(result, vec!["arg1", "arg2", "arg3"]);
+    fn test_arm_then_fire_within_window() {
+        let start = Instant::now();
+        let window = Duration::from_secs(5);
+        let mut tracker = ArmToFireTracker::default();
+
+        assert_eq!(tracker.press("shutdown", window, start), ArmStep::Armed);
+        assert_eq!(tracker.press("shutdown", window, start + Duration::from_secs(2)), ArmStep::Fire);
+        // Firing disarms, so the next press arms again
+        assert_eq!(tracker.press("shutdown", window, start + Duration::from_secs(3)), ArmStep::Armed);
     }
 
     #[test]
-    fn test_parse_quoted_path() {
-        let result = parse_arguments("\"C:\\Program Files\\App\\app.exe\" --flag");
-        assert_eq!(result, vec!["C:\\Program Files\\App\\app.exe", "--flag"]);
+    fn test_arm_times_out_and_disarms() {
+        let start = Instant::now();
+        let window = Duration::from_secs(5);
+        let mut tracker = ArmToFireTracker::default();
+
+        assert_eq!(tracker.press("shutdown", window, start), ArmStep::Armed);
+        // Too late: re-arms instead of firing
+        assert_eq!(tracker.press("shutdown", window, start + Duration::from_secs(6)), ArmStep::Armed);
+        assert_eq!(tracker.press("shutdown", window, start + Duration::from_secs(7)), ArmStep::Fire);
+
+        // Arm state is per binding
+        assert_eq!(tracker.press("other", window, start + Duration::from_secs(7)), ArmStep::Armed);
     }
 
     #[test]
-    fn test_parse_mixed_args() {
-        let result = parse_arguments("normal \"quoted arg\" another");
-        assert_eq!(result, vec!["normal", "quoted arg", "another"]);
+    fn test_cooldown_skips_bursts_per_binding() {
+        let start = Instant::now();
+        let cooldown = Duration::from_millis(DEFAULT_COOLDOWN_MS);
+        let mut tracker = CooldownTracker::default();
+
+        assert!(!tracker.is_cooling_down("pedal", cooldown, start));
+        tracker.record("pedal", start);
+        assert!(tracker.is_cooling_down("pedal", cooldown, start + Duration::from_millis(20)));
+        assert!(!tracker.is_cooling_down("pedal", cooldown, start + Duration::from_millis(150)));
+
+        // Other bindings and a zero cooldown are unaffected
+        assert!(!tracker.is_cooling_down("other", cooldown, start));
+        assert!(!tracker.is_cooling_down("pedal", Duration::ZERO, start));
     }
 
     #[test]
-    fn test_parse_empty() {
-        let result = parse_arguments("");
-        assert!(result.is_empty());
+    fn test_restart_backoff_doubles_up_to_cap_and_resets() {
+        let mut backoff = RestartBackoff::new();
+        let delays: Vec<u64> = (0..7).map(|_| backoff.next_delay().as_millis() as u64).collect();
+        assert_eq!(delays, vec![250, 500, 1000, 2000, 4000, 5000, 5000]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_millis(RESTART_BACKOFF_MIN_MS));
     }
 
     #[test]
-    fn test_parse_multiple_spaces() {
-        let result = parse_arguments("arg1    arg2");
-        assert_eq!(result, vec!["arg1", "arg2"]);
+    fn test_arming_gate_zero_delay_is_armed_immediately() {
+        let start = Instant::now();
+        let gate = ArmingGate::new(start, Duration::ZERO);
+        assert!(gate.is_armed(start));
     }
 }
 
-/// Constants for trigger detection
-const DOUBLE_PRESS_WINDOW_MS: u64 = 400; // Max time between presses for double-press
+/// Cooldown for bindings that don't set `cooldown_ms`. Absorbs the burst of
+/// keydowns cheap remap devices send for one press without eating
+/// deliberate rapid presses.
+const DEFAULT_COOLDOWN_MS: u64 = 150;
+
+/// `device-activity` events are limited per device to this many per window
+const ACTIVITY_EVENTS_PER_WINDOW: u32 = 4;
+const ACTIVITY_WINDOW_MS: u64 = 1000;
+
+/// Wait before restarting Raw Input after its message loop ends, doubling per
+/// consecutive failure up to the max
+const RESTART_BACKOFF_MIN_MS: u64 = 250;
+const RESTART_BACKOFF_MAX_MS: u64 = 5000;
 
-/// Tracks button press state for a device
+/// Presses of one key in quick succession
 #[derive(Debug)]
-struct DevicePressState {
-    last_press_time: Instant,
-    press_count: u32,
+struct Burst<T> {
+    count: u32,
+    last_press: Instant,
+    input: T, // Latest press
+}
+
+/// Groups presses per key into bursts. A burst is complete once the
+/// multi-press window passes without another press, or sooner when the
+/// caller sees no binding waits for more presses; only then is its count
+/// matched to a trigger, so one burst runs one binding.
+#[derive(Debug)]
+struct PressBursts<T> {
+    pending: HashMap<String, Burst<T>>, // press key -> burst
+}
+
+impl<T> Default for PressBursts<T> {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+        }
+    }
+}
+
+impl<T> PressBursts<T> {
+    /// Count a press of `key` and return the burst's count so far. Expired
+    /// bursts must be taken first, or they're counted as continued.
+    fn press(&mut self, key: &str, input: T, window: Duration, now: Instant) -> u32 {
+        let count = match self.pending.get(key) {
+            Some(burst) if now.duration_since(burst.last_press) < window => burst.count + 1,
+            _ => 1,
+        };
+        self.pending.insert(key.to_string(), Burst { count, last_press: now, input });
+        count
+    }
+
+    /// End `key`'s burst now, returning its count and latest press
+    fn take(&mut self, key: &str) -> Option<(u32, T)> {
+        self.pending.remove(key).map(|burst| (burst.count, burst.input))
+    }
+
+    /// End the bursts that went a whole window without another press
+    fn take_expired(&mut self, window: Duration, now: Instant) -> Vec<(u32, T)> {
+        let expired: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, burst)| now.duration_since(burst.last_press) >= window)
+            .map(|(key, _)| key.clone())
+            .collect();
+        expired.iter().filter_map(|key| self.take(key)).collect()
+    }
+
+    /// When the first pending burst expires
+    fn next_deadline(&self, window: Duration) -> Option<Instant> {
+        self.pending.values().map(|burst| burst.last_press + window).min()
+    }
+}
+
+/// A press waiting for its burst to complete
+struct PendingPress {
+    device: HidDevice,
+    device_id: String,
 }
 
-impl DevicePressState {
+/// Trigger matched by a burst of N presses. Counts past three stay a
+/// triple press.
+fn trigger_for_press_count(count: u32) -> TriggerType {
+    match count {
+        0 | 1 => TriggerType::SinglePress,
+        2 => TriggerType::DoublePress,
+        _ => TriggerType::TriplePress,
+    }
+}
+
+/// The most presses any enabled binding for this input waits for, so a
+/// burst can run as soon as it can't grow into another binding's trigger
+fn highest_bound_count(config: &ConfigManager, query: &BindingQuery) -> u32 {
+    [3, 2]
+        .into_iter()
+        .find(|&count| {
+            let trigger = trigger_for_press_count(count);
+            config
+                .find_binding(&BindingQuery { trigger_type: Some(&trigger), ..*query })
+                .is_some_and(|binding| binding.enabled && binding.trigger_type == trigger)
+        })
+        .unwrap_or(1)
+}
+
+/// Ignores presses until a start delay has elapsed, so configured apps don't
+/// launch while the rest of the system is still booting
+#[derive(Debug)]
+struct ArmingGate {
+    armed_at: Instant,
+}
+
+impl ArmingGate {
+    fn new(start: Instant, delay: Duration) -> Self {
+        Self {
+            armed_at: start + delay,
+        }
+    }
+
+    fn is_armed(&self, now: Instant) -> bool {
+        now >= self.armed_at
+    }
+}
+
+/// Limits `device-activity` events per device to a fixed number per window,
+/// so a chattering device can't flood the frontend
+#[derive(Debug)]
+struct ActivityThrottle {
+    window: Duration,
+    max_per_window: u32,
+    windows: HashMap<String, (Instant, u32)>, // device ID -> (window start, events sent)
+}
+
+impl ActivityThrottle {
+    fn new(window: Duration, max_per_window: u32) -> Self {
+        Self {
+            window,
+            max_per_window,
+            windows: HashMap::new(),
+        }
+    }
+
+    fn should_emit(&mut self, device_id: &str, now: Instant) -> bool {
+        let (started, count) = self
+            .windows
+            .entry(device_id.to_string())
+            .or_insert((now, 0));
+
+        if now.duration_since(*started) >= self.window {
+            *started = now;
+            *count = 0;
+        }
+
+        if *count < self.max_per_window {
+            *count += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Result of a press on an arm-to-fire binding
+#[derive(Debug, PartialEq)]
+enum ArmStep {
+    /// First press (or the previous arm expired): armed, don't execute
+    Armed,
+    /// Second press inside the window: execute
+    Fire,
+}
+
+/// Per-binding arm state for `ArmToFire` bindings
+#[derive(Debug, Default)]
+struct ArmToFireTracker {
+    armed: HashMap<String, Instant>, // binding ID -> when it was armed
+}
+
+impl ArmToFireTracker {
+    fn press(&mut self, binding_id: &str, window: Duration, now: Instant) -> ArmStep {
+        match self.armed.remove(binding_id) {
+            Some(armed_at) if now.duration_since(armed_at) <= window => ArmStep::Fire,
+            _ => {
+                // Not armed, or the arm timed out and this press re-arms
+                self.armed.insert(binding_id.to_string(), now);
+                ArmStep::Armed
+            }
+        }
+    }
+}
+
+/// Per-binding debounce: remembers when each binding last executed
+#[derive(Debug, Default)]
+struct CooldownTracker {
+    last_run: HashMap<String, Instant>, // binding ID -> last execution
+}
+
+impl CooldownTracker {
+    fn is_cooling_down(&self, binding_id: &str, cooldown: Duration, now: Instant) -> bool {
+        self.last_run
+            .get(binding_id)
+            .is_some_and(|last| now.duration_since(*last) < cooldown)
+    }
+
+    fn record(&mut self, binding_id: &str, now: Instant) {
+        self.last_run.insert(binding_id.to_string(), now);
+    }
+}
+
+/// Listener state a completed burst reads and updates
+struct PressContext {
+    notification_throttle: NotificationThrottle,
+    arm_tracker: ArmToFireTracker,
+    cooldowns: CooldownTracker,
+    manufacturers: HashMap<String, Option<String>>, // Per device ID, looked up once via hidapi
+    layers: LayerState,
+}
+
+impl PressContext {
+    /// The device's manufacturer string, if any binding for it needs one
+    fn manufacturer(&mut self, config: &ConfigManager, device_id: &str) -> Option<String> {
+        if !config.has_manufacturer_constraint(device_id) {
+            return None;
+        }
+        self.manufacturers
+            .entry(device_id.to_string())
+            .or_insert_with(|| hid::lookup_manufacturer(device_id))
+            .clone()
+    }
+}
+
+/// Backoff between Raw Input restarts. Starts over once input flows again.
+#[derive(Debug)]
+struct RestartBackoff {
+    next: Duration,
+}
+
+impl RestartBackoff {
     fn new() -> Self {
         Self {
-            last_press_time: Instant::now(),
-            press_count: 0,
+            next: Duration::from_millis(RESTART_BACKOFF_MIN_MS),
         }
     }
+
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(Duration::from_millis(RESTART_BACKOFF_MAX_MS));
+        delay
+    }
+
+    fn reset(&mut self) {
+        self.next = Duration::from_millis(RESTART_BACKOFF_MIN_MS);
+    }
 }
 
 /// Background listener that monitors for device input and executes configured actions
 pub struct BackgroundListener {
     config_manager: Arc<Mutex<ConfigManager>>,
+    executor: ActionExecutor,
+    app_handle: AppHandle,
 }
 
 impl BackgroundListener {
-    pub fn new(config_manager: Arc<Mutex<ConfigManager>>) -> Self {
-        Self { config_manager }
+    pub fn new(config_manager: Arc<Mutex<ConfigManager>>, app_handle: AppHandle) -> Self {
+        Self {
+            executor: ActionExecutor::new(config_manager.clone()),
+            config_manager,
+            app_handle,
+        }
     }
 
     /// Start the background listener in a separate thread
@@ -111,204 +501,363 @@ impl BackgroundListener {
 
     fn run_listener(self) {
         let mut monitor = RawInputMonitor::new();
-        let rx = monitor.start_monitoring_persistent();
-
-        // Track press state per device
-        let mut device_states: HashMap<String, DevicePressState> = HashMap::new();
-
-        log::info!("Background listener active, waiting for device input...");
+        let mut rx = monitor.start_monitoring_persistent();
+        let mut restart_backoff = RestartBackoff::new();
+
+        let mut bursts: PressBursts<PendingPress> = PressBursts::default();
+        let mut presses = PressContext {
+            notification_throttle: NotificationThrottle::new(),
+            arm_tracker: ArmToFireTracker::default(),
+            cooldowns: CooldownTracker::default(),
+            manufacturers: HashMap::new(),
+            layers: match self.config_manager.lock() {
+                Ok(config) => LayerState::from_names(&config.get_active_layers()),
+                Err(_) => LayerState::new(),
+            },
+        };
+        let mut activity_throttle = ActivityThrottle::new(
+            Duration::from_millis(ACTIVITY_WINDOW_MS),
+            ACTIVITY_EVENTS_PER_WINDOW,
+        );
+        let defaults = AppSettings::default();
+        let mut use_serial = defaults.use_serial_in_device_id;
+        let mut multi_press_window = Duration::from_millis(defaults.multi_press_window_ms);
+
+        let start_delay = match self.config_manager.lock() {
+            Ok(config) => Duration::from_millis(config.get_settings().listener_start_delay_ms),
+            Err(_) => Duration::ZERO,
+        };
+        let arming = ArmingGate::new(Instant::now(), start_delay);
+
+        if start_delay.is_zero() {
+            log::info!("Background listener active, waiting for device input...");
+        } else {
+            log::info!("Background listener arming in {}ms", start_delay.as_millis());
+            let config_manager = self.config_manager.clone();
+            thread::spawn(move || {
+                thread::sleep(start_delay);
+                if let Ok(mut config) = config_manager.lock() {
+                    config.add_log(
+                        LogEntryLevel::Info,
+                        format!("Background listener active after {}ms start delay", start_delay.as_millis()),
+                        Some("Listener".to_string()),
+                    );
+                }
+            });
+        }
 
-        while let Ok(device) = rx.recv() {
-            let device_id = format!("{}:{}", device.vendor_id, device.product_id);
+        loop {
+            // Wake up when a pending burst's window runs out
+            let received = match bursts.next_deadline(multi_press_window) {
+                Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
             let now = Instant::now();
 
-            log::info!("Device input detected: {}", device_id);
-
-            // Get or create device state
-            let state = device_states
-                .entry(device_id.clone())
-                .or_insert_with(DevicePressState::new);
-
-            // Check time since last press
-            let time_since_last = now.duration_since(state.last_press_time);
-            let is_double_press = time_since_last < Duration::from_millis(DOUBLE_PRESS_WINDOW_MS)
-                && state.press_count >= 1;
-
-            // Update state
-            if is_double_press {
-                state.press_count += 1;
-            } else {
-                state.press_count = 1;
+            if received.is_ok() {
+                // Read per press so changed settings apply without a restart
+                if let Ok(config) = self.config_manager.lock() {
+                    let settings = config.get_settings();
+                    use_serial = settings.use_serial_in_device_id;
+                    multi_press_window = Duration::from_millis(settings.multi_press_window_ms);
+                }
             }
-            state.last_press_time = now;
-
-            // Determine which trigger type matched
-            let detected_trigger = if state.press_count >= 2 {
-                TriggerType::DoublePress
-            } else {
-                TriggerType::SinglePress
-            };
 
-            log::info!(
-                "Press #{} for {} ({}ms since last) -> {:?}",
-                state.press_count,
-                device_id,
-                time_since_last.as_millis(),
-                detected_trigger
-            );
-
-            // Look up binding for this device
-            if let Ok(mut config) = self.config_manager.lock() {
-                // Log that we detected input
-                config.add_log(
-                    LogEntryLevel::Info,
-                    format!(
-                        "{:?} on device {}",
-                        detected_trigger, device_id
-                    ),
-                    Some(device_id.clone()),
-                );
+            // Bursts that weren't continued in time are complete
+            for (count, press) in bursts.take_expired(multi_press_window, now) {
+                self.fire_burst(&press, count, &mut presses, &rx);
+            }
 
-                if let Some(binding) = config.get_binding(&device_id) {
-                    if binding.enabled {
-                        // Check if the binding's trigger type matches what we detected
-                        let should_execute = match (&binding.trigger_type, &detected_trigger) {
-                            // Single press: execute only on first press (not on double)
-                            (TriggerType::SinglePress, TriggerType::SinglePress) => true,
-                            // Double press: execute only when double press detected
-                            (TriggerType::DoublePress, TriggerType::DoublePress) => true,
-                            // Long press: not yet implemented
-                            (TriggerType::LongPress, _) => false,
-                            // Other combinations don't match
-                            _ => false,
-                        };
-
-                        if should_execute {
-                            let action = binding.action.clone();
-                            let action_desc = format!(
-                                "{}: {}",
-                                match action.r#type {
-                                    ActionType::LaunchApp => "Launch App",
-                                    ActionType::RunScript => "Run Script",
-                                    ActionType::SystemCommand => "System Command",
-                                    ActionType::Hotkey => "Hotkey",
-                                },
-                                action.executable_path
-                            );
-
-                            config.add_log(
-                                LogEntryLevel::Info,
-                                format!("Executing ({:?}): {}", detected_trigger, action_desc),
-                                Some(device_id.clone()),
-                            );
-
-                            drop(config); // Release lock before executing
-                            self.execute_action(&action, &device_id);
-
-                            // Reset press count after executing double-press
-                            if detected_trigger == TriggerType::DoublePress {
-                                if let Some(s) = device_states.get_mut(&device_id) {
-                                    s.press_count = 0;
-                                }
-                            }
-                        } else {
-                            log::debug!(
-                                "Trigger type mismatch: binding expects {:?}, detected {:?}",
-                                binding.trigger_type,
-                                detected_trigger
-                            );
-                        }
-                    } else {
+            // The channel closes when the Raw Input message loop dies; bring
+            // up a fresh monitor rather than stop remapping for good
+            let device = match received {
+                Ok(device) => {
+                    restart_backoff.reset();
+                    device
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) if crate::shutdown::is_shutting_down() => {
+                    log::info!("Background listener stopped");
+                    return;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    let delay = restart_backoff.next_delay();
+                    log::warn!("Raw input monitor stopped; restarting in {}ms", delay.as_millis());
+                    if let Ok(mut config) = self.config_manager.lock() {
                         config.add_log(
                             LogEntryLevel::Warn,
-                            format!("Binding disabled for device {}", device_id),
-                            Some(device_id.clone()),
+                            format!("Raw input stopped unexpectedly; restarting in {}ms", delay.as_millis()),
+                            Some("Listener".to_string()),
                         );
                     }
-                } else {
-                    config.add_log(
-                        LogEntryLevel::Warn,
-                        format!("No binding configured for device {}", device_id),
-                        Some(device_id.clone()),
-                    );
+                    thread::sleep(delay);
+                    monitor = RawInputMonitor::new();
+                    rx = monitor.start_monitoring_persistent();
+                    log::info!("Raw input monitor restarted");
+                    continue;
                 }
-            }
-        }
-
-        log::warn!("Background listener stopped");
-    }
+            };
 
-    fn execute_action(&self, action: &ActionConfig, device_id: &str) {
-        log::info!("Executing: {} {}", action.executable_path, action.arguments);
+            let device_id = endpoint_device_id(
+                build_device_id(
+                    &device.vendor_id,
+                    &device.product_id,
+                    device.serial_number.as_deref(),
+                    use_serial,
+                ),
+                device.interface_number,
+                device.total_interfaces,
+            );
 
-        let result = match action.r#type {
-            ActionType::LaunchApp => {
-                // Launch executable directly (supports paths with spaces)
-                let mut cmd = Command::new(&action.executable_path);
-                if !action.arguments.is_empty() {
-                    cmd.args(parse_arguments(&action.arguments));
+            // Lets the UI show which device is sending input, bound or not
+            if activity_throttle.should_emit(&device_id, now) {
+                if let Err(e) = self.app_handle.emit("device-activity", serde_json::json!({
+                    "deviceId": device_id,
+                    "timestamp": chrono::Utc::now().to_rfc3339(),
+                })) {
+                    log::error!("Failed to emit device-activity event: {}", e);
                 }
-                cmd.spawn()
             }
-            ActionType::RunScript => {
-                // Run script through cmd with proper quoting
-                let quoted_path = format!("\"{}\"", action.executable_path);
-                Command::new("cmd")
-                    .args(["/C", &quoted_path])
-                    .args(parse_arguments(&action.arguments))
-                    .spawn()
+
+            if !arming.is_armed(now) {
+                log::debug!("Ignoring input from {} during listener start delay", device_id);
+                continue;
             }
-            ActionType::SystemCommand => {
-                // Run system command through cmd
-                Command::new("cmd")
-                    .args(["/C", &action.executable_path])
-                    .args(parse_arguments(&action.arguments))
-                    .spawn()
+
+            // A held key's auto-repeat isn't a new press and mustn't count toward double-press
+            if device.is_repeat {
+                log::debug!("Ignoring auto-repeat from {}", device_id);
+                continue;
             }
-            ActionType::Hotkey => {
-                // Execute hotkey and log result separately (doesn't spawn process)
-                match crate::hotkey::execute_hotkey(&action.executable_path) {
-                    Ok(_) => {
-                        if let Ok(mut config) = self.config_manager.lock() {
-                            config.add_log(
-                                LogEntryLevel::Success,
-                                format!("Hotkey executed: {}", action.executable_path),
-                                Some(device_id.to_string()),
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        if let Ok(mut config) = self.config_manager.lock() {
-                            config.add_log(
-                                LogEntryLevel::Error,
-                                format!("Hotkey failed: {}", e),
-                                Some(device_id.to_string()),
-                            );
-                        }
-                    }
+
+            log::info!("Device input detected: {}", device_id);
+
+            // Presses are counted per key, so two different keys in quick
+            // succession aren't taken as a double press
+            let press_key = match device.button_code {
+                Some(code) => format!("{}#{:02X}", device_id, code),
+                None => device_id.clone(),
+            };
+
+            let max_count = {
+                let Ok(config) = self.config_manager.lock() else {
+                    continue;
+                };
+                if !config.is_remapping_enabled() {
+                    log::debug!("Remapping paused; ignoring press on {}", device_id);
+                    continue;
+                }
+                let manufacturer = presses.manufacturer(&config, &device_id);
+                let consumer_usage = device.consumer_usage.map(consumer::consumer_usage_id);
+                highest_bound_count(
+                    &config,
+                    &BindingQuery {
+                        device_id: &device_id,
+                        manufacturer: manufacturer.as_deref(),
+                        consumer_usage: consumer_usage.as_deref(),
+                        button_code: device.button_code,
+                        trigger_type: None,
+                        layers: Some(&presses.layers),
+                    },
+                )
+            };
+
+            let count = bursts.press(&press_key, PendingPress { device, device_id }, multi_press_window, now);
+            log::info!("Press #{} for {} (bindings wait for up to {})", count, press_key, max_count);
+
+            // No binding waits for more presses, so don't wait out the window
+            if count >= max_count {
+                if let Some((count, press)) = bursts.take(&press_key) {
+                    self.fire_burst(&press, count, &mut presses, &rx);
                 }
-                return;
             }
+        }
+    }
+
+    /// Run the binding for a completed burst of `count` presses, if any.
+    /// Each press count matches only its own trigger, so a triple press runs
+    /// just the triple-press binding. Long press is never detected yet.
+    fn fire_burst(&self, press: &PendingPress, count: u32, presses: &mut PressContext, rx: &Receiver<HidDevice>) {
+        let PendingPress { device, device_id } = press;
+        let detected_trigger = trigger_for_press_count(count);
+        let now = Instant::now();
+
+        let Ok(mut config) = self.config_manager.lock() else {
+            return;
         };
+        if !config.is_remapping_enabled() {
+            log::debug!("Remapping paused; ignoring press on {}", device_id);
+            return;
+        }
 
-        // Log the result
-        if let Ok(mut config) = self.config_manager.lock() {
-            match result {
-                Ok(_) => {
-                    config.add_log(
-                        LogEntryLevel::Success,
-                        format!("Action executed: {}", action.executable_path),
-                        Some(device_id.to_string()),
-                    );
+        // Log that we detected input
+        config.add_log(
+            LogEntryLevel::Info,
+            format!(
+                "{:?} on device {}",
+                detected_trigger, device_id
+            ),
+            Some(device_id.clone()),
+        );
+
+        if !device.raw_report.is_empty() && config.is_verbose_device(device_id) {
+            let hex = hid::format_report_hex(&device.raw_report, MAX_LOGGED_REPORT_BYTES);
+            log::debug!("Raw report from {}: {}", device_id, hex);
+            config.add_log(
+                LogEntryLevel::Debug,
+                format!("Raw report: {}", hex),
+                Some(device_id.clone()),
+            );
+        }
+
+        let manufacturer = presses.manufacturer(&config, device_id);
+        let consumer_usage = device.consumer_usage.map(consumer::consumer_usage_id);
+        let query = BindingQuery {
+            device_id,
+            manufacturer: manufacturer.as_deref(),
+            consumer_usage: consumer_usage.as_deref(),
+            button_code: device.button_code,
+            trigger_type: Some(&detected_trigger),
+            layers: Some(&presses.layers),
+        };
+
+        // Only the active profile's bindings are matched, and ones
+        // needing a layer only while it's active
+        let Some(binding) = config.find_binding(&query) else {
+            config.add_log(
+                LogEntryLevel::Warn,
+                format!(
+                    "No binding configured for device {} in profile {}",
+                    device_id,
+                    config.active_profile_id()
+                ),
+                Some(device_id.clone()),
+            );
+            return;
+        };
+        if !binding.enabled {
+            config.add_log(
+                LogEntryLevel::Warn,
+                format!("Binding disabled for device {}", device_id),
+                Some(device_id.clone()),
+            );
+            return;
+        }
+        if binding.trigger_type != detected_trigger {
+            log::debug!(
+                "Trigger type mismatch: binding expects {:?}, detected {:?}",
+                binding.trigger_type,
+                detected_trigger
+            );
+            return;
+        }
+
+        let Some(action) = ActionExecutor::resolve_action(&mut config, &binding, device_id) else {
+            return;
+        };
+        let action_desc = describe_action(&action);
+
+        let cooldown = Duration::from_millis(binding.cooldown_ms.unwrap_or(DEFAULT_COOLDOWN_MS));
+        if presses.cooldowns.is_cooling_down(&binding.id, cooldown, now) {
+            log::debug!(
+                "Skipping {} for {}: within {}ms cooldown",
+                action_desc,
+                device_id,
+                cooldown.as_millis()
+            );
+            return;
+        }
+
+        if let Some(arm) = &binding.arm_to_fire {
+            let window = Duration::from_secs(arm.window_secs as u64);
+            if presses.arm_tracker.press(&binding.id, window, now) == ArmStep::Armed {
+                config.add_log(
+                    LogEntryLevel::Warn,
+                    format!("Armed: press again within {}s to run {}", arm.window_secs, action_desc),
+                    Some(device_id.clone()),
+                );
+                if let Err(e) = self.app_handle.emit("binding-armed", serde_json::json!({
+                    "bindingId": binding.id,
+                    "deviceId": device_id,
+                    "windowSecs": arm.window_secs,
+                })) {
+                    log::error!("Failed to emit binding-armed event: {}", e);
                 }
-                Err(e) => {
-                    config.add_log(
-                        LogEntryLevel::Error,
-                        format!("Action failed: {}", e),
-                        Some(device_id.to_string()),
-                    );
+                if arm.beep {
+                    let _ = unsafe { MessageBeep(MB_ICONWARNING) };
                 }
+                return;
+            }
+        }
+
+        if binding.confirm_dialog {
+            drop(config); // Don't block the UI while waiting
+            let outcome = confirm::confirm_action(
+                &self.app_handle,
+                &action_desc,
+                Duration::from_secs(confirm::CONFIRM_TIMEOUT_SECS),
+            );
+            // Presses made while the dialog was open aren't meant as new triggers
+            let discarded = rx.try_iter().count();
+            if discarded > 0 {
+                log::debug!("Discarded {} input(s) received during confirmation", discarded);
+            }
+
+            config = match self.config_manager.lock() {
+                Ok(config) => config,
+                Err(_) => return,
+            };
+            if !outcome.should_execute() {
+                config.add_log(
+                    LogEntryLevel::Info,
+                    format!("Not executed ({:?}): {}", outcome, action_desc),
+                    Some(device_id.clone()),
+                );
+                return;
             }
         }
+
+        presses.cooldowns.record(&binding.id, Instant::now());
+        config.add_log(
+            LogEntryLevel::Info,
+            format!("Executing ({:?}): {}", detected_trigger, action_desc),
+            Some(device_id.clone()),
+        );
+        config.record_usage(&action.r#type, &detected_trigger);
+
+        if presses.layers.apply(&action.r#type, &action.executable_path) {
+            // Layer changes are listener state, not an external action
+            config.add_log(
+                LogEntryLevel::Success,
+                format!("Active layers: {:?}", presses.layers.names()),
+                Some(device_id.clone()),
+            );
+            if let Err(e) = config.save_active_layers(presses.layers.names()) {
+                log::error!("Failed to persist active layers: {}", e);
+            }
+            drop(config);
+        } else {
+            drop(config); // Release lock before executing
+            let context = ExecutionContext {
+                device_id: device_id.clone(),
+                trigger: detected_trigger.clone(),
+                binding_id: Some(binding.id.clone()),
+                button: plugins::button_id(device.button_code, consumer_usage.as_deref()),
+                source: device_id.clone(),
+                completion: Completion::Detach,
+            };
+            // The outcome is logged by the executor
+            let _ = self.executor.execute_action(&action, &context);
+        }
+
+        if binding.notify_on_execute
+            && presses.notification_throttle.should_notify(&binding.id, Instant::now())
+        {
+            notifications::show_notification(
+                &self.app_handle,
+                "USB Configurator",
+                &action_desc,
+            );
+        }
     }
 }
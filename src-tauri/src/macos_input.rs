@@ -0,0 +1,321 @@
+// ============================================
+// macOS Input Monitoring
+// Detects presses through an IOHIDManager input-value callback. Unlike a
+// CGEventTap, each value carries the IOHIDDevice it came from, so a press can
+// be mapped back to VID:PID. Needs the Input Monitoring permission
+// (System Settings > Privacy & Security) on macOS 10.15+.
+// ============================================
+
+use crate::input_monitor::InputMonitor;
+use crate::types::{DeviceStatus, HidDevice};
+use std::ffi::{c_char, c_void, CString};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+type CFTypeRef = *const c_void;
+type CFStringRef = *const c_void;
+type CFAllocatorRef = *const c_void;
+type CFDictionaryRef = *const c_void;
+type CFRunLoopRef = *mut c_void;
+type CFIndex = isize;
+type IOHIDManagerRef = *mut c_void;
+type IOHIDDeviceRef = *mut c_void;
+type IOHIDElementRef = *mut c_void;
+type IOHIDValueRef = *mut c_void;
+type IOReturn = i32;
+
+type IOHIDValueCallback =
+    extern "C" fn(context: *mut c_void, result: IOReturn, sender: *mut c_void, value: IOHIDValueRef);
+
+const K_IO_RETURN_SUCCESS: IOReturn = 0;
+const K_IOHID_OPTIONS_TYPE_NONE: u32 = 0; // Shared access; never seizes the keyboard
+const K_CF_NUMBER_SINT32_TYPE: CFIndex = 3;
+const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+const K_CF_RUN_LOOP_RUN_FINISHED: i32 = 1;
+
+/// How long each run-loop slice lasts before the stop flag is re-checked
+const RUN_LOOP_SLICE_SECS: f64 = 0.1;
+
+// HID usage pages whose presses count as device input
+const USAGE_PAGE_KEYBOARD: u32 = 0x07;
+const USAGE_PAGE_BUTTON: u32 = 0x09;
+const USAGE_PAGE_CONSUMER: u32 = 0x0C;
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    static kCFRunLoopDefaultMode: CFStringRef;
+    fn CFRunLoopGetCurrent() -> CFRunLoopRef;
+    fn CFRunLoopRunInMode(mode: CFStringRef, seconds: f64, return_after_source_handled: u8) -> i32;
+    fn CFStringCreateWithCString(alloc: CFAllocatorRef, c_str: *const c_char, encoding: u32) -> CFStringRef;
+    fn CFStringGetCString(string: CFStringRef, buffer: *mut c_char, buffer_size: CFIndex, encoding: u32) -> u8;
+    fn CFGetTypeID(cf: CFTypeRef) -> usize;
+    fn CFNumberGetTypeID() -> usize;
+    fn CFStringGetTypeID() -> usize;
+    fn CFNumberGetValue(number: CFTypeRef, the_type: CFIndex, value_ptr: *mut c_void) -> u8;
+    fn CFRelease(cf: CFTypeRef);
+}
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOHIDManagerCreate(allocator: CFAllocatorRef, options: u32) -> IOHIDManagerRef;
+    fn IOHIDManagerSetDeviceMatching(manager: IOHIDManagerRef, matching: CFDictionaryRef);
+    fn IOHIDManagerRegisterInputValueCallback(
+        manager: IOHIDManagerRef,
+        callback: Option<IOHIDValueCallback>,
+        context: *mut c_void,
+    );
+    fn IOHIDManagerScheduleWithRunLoop(manager: IOHIDManagerRef, run_loop: CFRunLoopRef, mode: CFStringRef);
+    fn IOHIDManagerUnscheduleFromRunLoop(manager: IOHIDManagerRef, run_loop: CFRunLoopRef, mode: CFStringRef);
+    fn IOHIDManagerOpen(manager: IOHIDManagerRef, options: u32) -> IOReturn;
+    fn IOHIDManagerClose(manager: IOHIDManagerRef, options: u32) -> IOReturn;
+    fn IOHIDValueGetElement(value: IOHIDValueRef) -> IOHIDElementRef;
+    fn IOHIDValueGetIntegerValue(value: IOHIDValueRef) -> CFIndex;
+    fn IOHIDElementGetDevice(element: IOHIDElementRef) -> IOHIDDeviceRef;
+    fn IOHIDElementGetUsagePage(element: IOHIDElementRef) -> u32;
+    fn IOHIDElementGetUsage(element: IOHIDElementRef) -> u32;
+    fn IOHIDDeviceGetProperty(device: IOHIDDeviceRef, key: CFStringRef) -> CFTypeRef;
+}
+
+/// State shared with the input-value callback through its context pointer
+struct CallbackContext {
+    tx: Sender<HidDevice>,
+    monitoring: Arc<AtomicBool>,
+}
+
+pub struct MacInputMonitor {
+    monitoring_active: Arc<AtomicBool>,
+}
+
+impl MacInputMonitor {
+    pub fn new() -> Self {
+        Self::with_flag(Arc::new(AtomicBool::new(false)))
+    }
+
+    /// Share a stop flag with another monitor, so stopping that one (e.g.
+    /// `HidManager::stop_monitoring`) also ends this one
+    pub fn with_flag(monitoring_active: Arc<AtomicBool>) -> Self {
+        Self { monitoring_active }
+    }
+}
+
+impl InputMonitor for MacInputMonitor {
+    fn start_monitoring(&mut self) -> Receiver<HidDevice> {
+        let (tx, rx) = channel();
+        let monitoring = self.monitoring_active.clone();
+        monitoring.store(true, Ordering::SeqCst);
+
+        thread::spawn(move || {
+            log::info!("macOS input monitoring thread started");
+            unsafe { run_monitor(tx, monitoring) };
+            log::info!("macOS input monitoring thread stopped");
+        });
+
+        rx
+    }
+
+    fn stop_monitoring(&self) {
+        self.monitoring_active.store(false, Ordering::SeqCst);
+    }
+
+    fn name(&self) -> &str {
+        "IOHIDManager"
+    }
+}
+
+/// Whether an input value is a press worth reporting. Keyboard usages below
+/// 0x04 are error/rollover codes, and a value of 0 is a release.
+fn is_press(usage_page: u32, usage: u32, value: CFIndex) -> bool {
+    if value == 0 {
+        return false;
+    }
+    match usage_page {
+        USAGE_PAGE_KEYBOARD => (0x04..=0xE7).contains(&usage),
+        USAGE_PAGE_BUTTON | USAGE_PAGE_CONSUMER => usage > 0 && usage <= u16::MAX as u32,
+        _ => false,
+    }
+}
+
+/// Run an IOHIDManager on this thread until the first press or until
+/// `monitoring` is cleared
+unsafe fn run_monitor(tx: Sender<HidDevice>, monitoring: Arc<AtomicBool>) {
+    let manager = IOHIDManagerCreate(std::ptr::null(), K_IOHID_OPTIONS_TYPE_NONE);
+    if manager.is_null() {
+        log::error!("Failed to create IOHIDManager");
+        monitoring.store(false, Ordering::SeqCst);
+        return;
+    }
+
+    // No matching dictionary means every HID device
+    IOHIDManagerSetDeviceMatching(manager, std::ptr::null());
+
+    let context = Box::into_raw(Box::new(CallbackContext {
+        tx,
+        monitoring: monitoring.clone(),
+    }));
+    IOHIDManagerRegisterInputValueCallback(manager, Some(input_value_callback), context as *mut c_void);
+
+    let run_loop = CFRunLoopGetCurrent();
+    IOHIDManagerScheduleWithRunLoop(manager, run_loop, kCFRunLoopDefaultMode);
+
+    let result = IOHIDManagerOpen(manager, K_IOHID_OPTIONS_TYPE_NONE);
+    if result != K_IO_RETURN_SUCCESS {
+        log::error!(
+            "IOHIDManagerOpen failed (0x{:08X}); check the Input Monitoring permission",
+            result as u32
+        );
+        monitoring.store(false, Ordering::SeqCst);
+    }
+
+    while monitoring.load(Ordering::SeqCst) {
+        if CFRunLoopRunInMode(kCFRunLoopDefaultMode, RUN_LOOP_SLICE_SECS, 1) == K_CF_RUN_LOOP_RUN_FINISHED {
+            // No sources attached; don't spin
+            thread::sleep(Duration::from_secs_f64(RUN_LOOP_SLICE_SECS));
+        }
+    }
+
+    IOHIDManagerRegisterInputValueCallback(manager, None, std::ptr::null_mut());
+    IOHIDManagerUnscheduleFromRunLoop(manager, run_loop, kCFRunLoopDefaultMode);
+    if result == K_IO_RETURN_SUCCESS {
+        IOHIDManagerClose(manager, K_IOHID_OPTIONS_TYPE_NONE);
+    }
+    CFRelease(manager as CFTypeRef);
+    drop(Box::from_raw(context));
+}
+
+extern "C" fn input_value_callback(
+    context: *mut c_void,
+    _result: IOReturn,
+    _sender: *mut c_void,
+    value: IOHIDValueRef,
+) {
+    if context.is_null() || value.is_null() {
+        return;
+    }
+
+    unsafe {
+        let context = &*(context as *const CallbackContext);
+        if !context.monitoring.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let element = IOHIDValueGetElement(value);
+        if element.is_null() {
+            return;
+        }
+        let usage_page = IOHIDElementGetUsagePage(element);
+        let usage = IOHIDElementGetUsage(element);
+        if !is_press(usage_page, usage, IOHIDValueGetIntegerValue(value)) {
+            return;
+        }
+
+        let device = IOHIDElementGetDevice(element);
+        if device.is_null() {
+            return;
+        }
+        let Some(detected) = device_from_iohid(device, usage_page, usage) else {
+            return;
+        };
+
+        log::info!(
+            "Input detected from: {} ({}:{})",
+            detected.name,
+            detected.vendor_id,
+            detected.product_id
+        );
+
+        // Stop after the first detection, like the other monitors
+        context.monitoring.store(false, Ordering::SeqCst);
+        let _ = context.tx.send(detected);
+    }
+}
+
+/// Build a `HidDevice` from an IOHIDDevice's properties, or `None` if it
+/// doesn't report a vendor/product ID
+unsafe fn device_from_iohid(device: IOHIDDeviceRef, usage_page: u32, usage: u32) -> Option<HidDevice> {
+    let vendor_id = format!("{:04X}", number_property(device, "VendorID")? as u16);
+    let product_id = format!("{:04X}", number_property(device, "ProductID")? as u16);
+
+    Some(HidDevice {
+        id: format!("{}:{}", vendor_id, product_id),
+        name: string_property(device, "Product").unwrap_or_else(|| "Unknown Device".to_string()),
+        vendor_id,
+        product_id,
+        interface_number: 0,
+        total_interfaces: 1,
+        status: DeviceStatus::Connected,
+        manufacturer: string_property(device, "Manufacturer"),
+        serial_number: string_property(device, "SerialNumber"),
+        consumer_usage: (usage_page == USAGE_PAGE_CONSUMER).then_some(usage as u16),
+        raw_report: Vec::new(),
+        is_repeat: false,
+        button_code: None, // Keyboard usages aren't Windows virtual keys
+    })
+}
+
+unsafe fn with_cf_key<T>(key: &str, f: impl FnOnce(CFStringRef) -> T) -> Option<T> {
+    let key = CString::new(key).ok()?;
+    let cf_key = CFStringCreateWithCString(std::ptr::null(), key.as_ptr(), K_CF_STRING_ENCODING_UTF8);
+    if cf_key.is_null() {
+        return None;
+    }
+    let result = f(cf_key);
+    CFRelease(cf_key);
+    Some(result)
+}
+
+/// A numeric device property. The returned value is owned by the device.
+unsafe fn number_property(device: IOHIDDeviceRef, key: &str) -> Option<i32> {
+    with_cf_key(key, |cf_key| {
+        let value = IOHIDDeviceGetProperty(device, cf_key);
+        if value.is_null() || CFGetTypeID(value) != CFNumberGetTypeID() {
+            return None;
+        }
+        let mut number: i32 = 0;
+        (CFNumberGetValue(value, K_CF_NUMBER_SINT32_TYPE, &mut number as *mut i32 as *mut c_void) != 0)
+            .then_some(number)
+    })
+    .flatten()
+}
+
+/// A string device property, trimmed; empty strings count as missing
+unsafe fn string_property(device: IOHIDDeviceRef, key: &str) -> Option<String> {
+    with_cf_key(key, |cf_key| {
+        let value = IOHIDDeviceGetProperty(device, cf_key);
+        if value.is_null() || CFGetTypeID(value) != CFStringGetTypeID() {
+            return None;
+        }
+        // HID string descriptors are at most 126 UTF-16 units
+        let mut buffer = [0 as c_char; 512];
+        if CFStringGetCString(value, buffer.as_mut_ptr(), buffer.len() as CFIndex, K_CF_STRING_ENCODING_UTF8) == 0 {
+            return None;
+        }
+        let text = std::ffi::CStr::from_ptr(buffer.as_ptr()).to_string_lossy().trim().to_string();
+        (!text.is_empty()).then_some(text)
+    })
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_press_keyboard() {
+        assert!(is_press(USAGE_PAGE_KEYBOARD, 0x04, 1)); // A
+        assert!(!is_press(USAGE_PAGE_KEYBOARD, 0x04, 0)); // Release
+        assert!(!is_press(USAGE_PAGE_KEYBOARD, 0x01, 1)); // ErrorRollOver
+        assert!(!is_press(USAGE_PAGE_KEYBOARD, 0xFFFF_FFFF, 1));
+    }
+
+    #[test]
+    fn test_is_press_buttons_and_consumer() {
+        assert!(is_press(USAGE_PAGE_BUTTON, 1, 1));
+        assert!(is_press(USAGE_PAGE_CONSUMER, 0xCD, 1)); // Play/Pause
+        assert!(!is_press(USAGE_PAGE_CONSUMER, 0, 1));
+        // Pointer motion and other pages aren't presses
+        assert!(!is_press(0x01, 0x30, 5));
+    }
+}
@@ -1,36 +1,118 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod args;
+mod autostart;
+mod buttons;
+mod cli;
+mod clipboard;
 mod commands;
 mod config;
+mod config_watch;
+mod consumer;
+mod confirm;
+mod delay;
+mod executor;
+mod explain;
 mod hid;
+mod hotkey;
+mod http_api;
 mod input_monitor;
+mod instance;
+mod key_repeat;
+mod kiosk;
+mod launch;
+mod layers;
+mod mouse;
+mod notifications;
+mod plugins;
+mod power;
+mod script;
+mod shell;
+mod shutdown;
+mod summary;
 mod types;
+mod validation;
+mod volume;
 
 #[cfg(windows)]
 mod rawinput;
 
-#[cfg(windows)]
-mod hotkey;
+#[cfg(target_os = "macos")]
+mod macos_input;
+
+#[cfg(target_os = "linux")]
+mod linux_input;
+
 
 #[cfg(windows)]
 mod listener;
 
+#[cfg(windows)]
+mod foreground;
+
 use config::ConfigManager;
 use hid::HidManager;
+use instance::InstanceLock;
 use std::sync::{Arc, Mutex};
 use tauri::Manager;
 
 pub struct AppState {
     pub config_manager: Arc<Mutex<ConfigManager>>,
     pub hid_manager: Mutex<HidManager>,
+    pub instance_lock: Mutex<InstanceLock>,
+    pub http_api: Mutex<Option<http_api::HttpApiServer>>,
 }
 
 fn main() {
     env_logger::init();
 
-    let config_manager = ConfigManager::new().expect("Failed to initialize config manager");
+    // `--list-devices`, `--run-binding <id>` etc. run headless and exit
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run_from_args(&args) {
+        std::process::exit(code);
+    }
+
+    let (config_dir, config_dir_source) = ConfigManager::resolve_dir().expect("Failed to locate config directory");
+    let mut config_manager = ConfigManager::with_dir(config_dir).expect("Failed to initialize config manager");
+    log::info!("Config directory: {} ({})", config_manager.config_dir().display(), config_dir_source);
+    config_manager.add_log(
+        types::LogEntryLevel::Info,
+        format!("Using config directory {} ({})", config_manager.config_dir().display(), config_dir_source),
+        Some("System".to_string()),
+    );
+
+    // Only one instance may run the background listener, or every press fires twice
+    let instance_lock = InstanceLock::acquire(&config_manager.config_dir().join("instance.lock"))
+        .expect("Failed to acquire instance lock");
+    let is_primary = instance_lock.is_primary();
+    if !is_primary {
+        match config_manager.get_settings().second_instance_behavior {
+            types::SecondInstanceBehavior::FocusExisting => {
+                log::warn!("Another instance is already running, focusing it and exiting");
+                instance::focus_existing_window();
+                return;
+            }
+            types::SecondInstanceBehavior::Warn => {
+                log::warn!("Another instance is already running; background listener disabled");
+                config_manager.add_log(
+                    types::LogEntryLevel::Warn,
+                    "Another instance is already running - background listener disabled in this one".to_string(),
+                    Some("System".to_string()),
+                );
+            }
+        }
+    }
+
+    // Repair drift between the setting and the Run key (e.g. the app was moved)
+    match autostart::sync_autostart(config_manager.get_settings().start_with_windows) {
+        Ok(true) => log::info!("Auto-start entry updated to match settings"),
+        Ok(false) => {}
+        Err(e) => log::warn!("Failed to sync auto-start: {}", e),
+    }
+
     let mut hid_manager = HidManager::new().expect("Failed to initialize HID manager");
+    hid_manager.set_use_serial_in_device_id(config_manager.get_settings().use_serial_in_device_id);
 
     // Initialize HID manager with configured device IDs from saved bindings
     for device_id in config_manager.get_configured_device_ids() {
@@ -40,13 +122,17 @@ fn main() {
     // Wrap config_manager in Arc for sharing with background listener
     let config_manager = Arc::new(Mutex::new(config_manager));
     let config_manager_for_listener = config_manager.clone();
+    let config_manager_for_flush = config_manager.clone();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(AppState {
             config_manager,
             hid_manager: Mutex::new(hid_manager),
+            instance_lock: Mutex::new(instance_lock),
+            http_api: Mutex::new(None),
         })
         .setup(move |app| {
             log::info!("USB Configurator starting...");
@@ -54,26 +140,71 @@ fn main() {
             // Start background listener for configured devices
             #[cfg(windows)]
             {
-                let listener = listener::BackgroundListener::new(config_manager_for_listener.clone());
-                listener.start();
-                log::info!("Background listener started");
+                if is_primary {
+                    let listener = listener::BackgroundListener::new(
+                        config_manager_for_listener.clone(),
+                        app.handle().clone(),
+                    );
+                    listener.start();
+                    log::info!("Background listener started");
+
+                    foreground::start_profile_watcher(
+                        config_manager_for_listener.clone(),
+                        app.handle().clone(),
+                    );
+                }
+            }
+
+            // Pick up edits made to config.json outside the app
+            if is_primary {
+                config_watch::start_config_watcher(
+                    config_manager_for_listener.clone(),
+                    app.handle().clone(),
+                );
             }
 
+            // Local HTTP control API, if enabled
+            let settings = config_manager_for_flush
+                .lock()
+                .map(|config| config.get_settings())
+                .unwrap_or_default();
+            if let Err(e) = http_api::sync_http_api(app.handle(), &settings) {
+                log::warn!("{}", e);
+                if let Ok(mut config) = config_manager_for_flush.lock() {
+                    config.add_log(types::LogEntryLevel::Warn, e, Some("System".to_string()));
+                }
+            }
+
+            // Write out log entries add_log is still buffering
+            std::thread::spawn(move || loop {
+                std::thread::sleep(config::LOG_FLUSH_INTERVAL);
+                if let Ok(mut config) = config_manager_for_flush.lock() {
+                    let _ = config.flush_logs();
+                }
+            });
+
             // Initialize system tray if available
             #[cfg(desktop)]
             {
                 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
-                use tauri::menu::{Menu, MenuItem};
+                use tauri::menu::{CheckMenuItem, Menu, MenuItem};
+
+                let paused = !app.state::<AppState>()
+                    .config_manager
+                    .lock()
+                    .map(|config| config.is_remapping_enabled())
+                    .unwrap_or(true);
 
                 let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
+                let pause_item = CheckMenuItem::with_id(app, "pause", "Pause Remapping", true, paused, None::<&str>)?;
                 let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-                let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+                let menu = Menu::with_items(app, &[&show_item, &pause_item, &quit_item])?;
 
                 let _tray = TrayIconBuilder::new()
                     .icon(app.default_window_icon().unwrap().clone())
                     .menu(&menu)
                     .show_menu_on_left_click(false)
-                    .on_menu_event(|app, event| {
+                    .on_menu_event(move |app, event| {
                         match event.id.as_ref() {
                             "show" => {
                                 if let Some(window) = app.get_webview_window("main") {
@@ -81,7 +212,28 @@ fn main() {
                                     let _ = window.set_focus();
                                 }
                             }
+                            "pause" => {
+                                let state = app.state::<AppState>();
+                                if let Ok(mut config) = state.config_manager.lock() {
+                                    let enabled = !config.is_remapping_enabled();
+                                    match config.set_remapping_enabled(enabled) {
+                                        Ok(()) => config.add_log(
+                                            types::LogEntryLevel::Info,
+                                            commands::remapping_transition_message(enabled).to_string(),
+                                            Some("System".to_string()),
+                                        ),
+                                        Err(e) => config.add_log(
+                                            types::LogEntryLevel::Error,
+                                            format!("Failed to toggle remapping: {}", e),
+                                            Some("System".to_string()),
+                                        ),
+                                    }
+                                    // Keep the check mark on the saved state
+                                    let _ = pause_item.set_checked(!config.is_remapping_enabled());
+                                };
+                            }
                             "quit" => {
+                                shutdown::shutdown(app);
                                 app.exit(0);
                             }
                             _ => {}
@@ -105,28 +257,77 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             // Device commands
             commands::list_devices,
+            commands::list_devices_cached,
             commands::refresh_devices,
             commands::get_device_info,
+            commands::export_device_list,
+            commands::find_stale_bindings,
+            commands::resync_hid_configured,
+            commands::rescan_configured_devices,
+            commands::detect_active_interface,
+            commands::capture_hid_report,
+            commands::send_hid_report,
+            commands::list_raw_input_devices,
             // Monitoring commands
             commands::start_monitoring,
             commands::stop_monitoring,
             commands::get_monitoring_state,
             // Binding commands
             commands::get_all_bindings,
+            commands::get_bindings_grouped,
             commands::get_binding,
             commands::save_binding,
+            commands::create_binding_template,
+            commands::set_binding_trigger,
+            commands::set_binding_enabled,
+            commands::rewrite_action_paths,
             commands::delete_binding,
+            commands::export_bindings_markdown,
             // Settings commands
             commands::get_settings,
             commands::save_settings,
+            commands::set_device_verbose,
+            commands::get_config_dir,
+            commands::open_config_dir,
+            commands::move_config_dir,
+            commands::export_config,
+            commands::import_config,
+            // Kiosk lock commands
+            commands::get_lock_status,
+            commands::lock,
+            commands::unlock,
+            commands::remove_lock,
             // Action commands
             commands::test_action,
+            commands::test_binding,
+            commands::preview_hotkey,
+            commands::check_hotkey_conflicts,
+            commands::validate_action,
+            commands::validate_all_bindings,
+            commands::explain_device,
+            // Plugin commands
+            commands::get_plugins,
+            commands::register_plugin,
+            commands::unregister_plugin,
+            // Profile commands
+            commands::list_profiles,
+            commands::create_profile,
+            commands::switch_profile,
+            commands::delete_profile,
+            commands::get_profile_rules,
+            commands::save_profile_rules,
             // Log commands
             commands::get_logs,
             commands::clear_logs,
+            commands::compact_logs,
+            // Usage stats commands
+            commands::get_usage_stats,
+            commands::reset_usage_stats,
             // System commands
             commands::open_file_dialog,
+            commands::get_system_volume,
             commands::get_app_version,
+            commands::get_instance_status,
         ])
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
@@ -134,6 +335,11 @@ fn main() {
                 api.prevent_close();
             }
         })
-        .run(tauri::generate_context!())
-        .expect("Error while running USB Configurator");
+        .build(tauri::generate_context!())
+        .expect("Error while building USB Configurator")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                shutdown::shutdown(app);
+            }
+        });
 }
@@ -0,0 +1,335 @@
+// ============================================
+// Mouse Actions
+// Moves the cursor with SendInput using MOUSEEVENTF_VIRTUALDESK, so absolute
+// coordinates span every monitor rather than just the primary one, and sends
+// clicks, wheel scrolls and relative moves for MouseAction bindings
+// ============================================
+
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_HWHEEL,
+    MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP,
+    MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_VIRTUALDESK,
+    MOUSEEVENTF_WHEEL, MOUSEINPUT, MOUSE_EVENT_FLAGS,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+};
+
+/// Upper bound of SendInput's normalized absolute coordinates
+pub const NORMALIZED_MAX: i32 = 65535;
+
+/// Wheel movement of one notch (WHEEL_DELTA)
+pub const WHEEL_DELTA: i32 = 120;
+
+/// Most notches one scroll action may send
+const MAX_SCROLL_NOTCHES: i32 = 100;
+
+/// Bounding rectangle of all monitors, in pixels. `left`/`top` are negative
+/// when a monitor sits left of or above the primary one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VirtualDesktop {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Where a MouseMove action points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseTarget {
+    /// Virtual-desktop pixels (the primary monitor's top-left is 0,0)
+    Pixels(i32, i32),
+    /// 0-65535 across the whole virtual desktop
+    Normalized(i32, i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// What a MouseAction binding does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseAction {
+    Click(MouseButton),
+    Scroll(ScrollDirection, i32),
+    /// Pixels relative to the current position (subject to pointer acceleration)
+    MoveBy(i32, i32),
+}
+
+/// Parse a MouseAction spec from `executable_path`: `click:left|right|middle`,
+/// `scroll:up|down|left|right[:notches]` or `move:dx,dy`
+pub fn parse_mouse_action(spec: &str) -> Result<MouseAction, String> {
+    let spec = spec.trim();
+    let (kind, rest) = spec.split_once(':').unwrap_or((spec, ""));
+
+    match kind.to_ascii_lowercase().as_str() {
+        "click" => {
+            let button = match rest.trim().to_ascii_lowercase().as_str() {
+                "" | "left" => MouseButton::Left,
+                "right" => MouseButton::Right,
+                "middle" => MouseButton::Middle,
+                other => return Err(format!("Unknown mouse button: {}", other)),
+            };
+            Ok(MouseAction::Click(button))
+        }
+        "scroll" => {
+            let (direction, notches) = rest.split_once(':').unwrap_or((rest, "1"));
+            let direction = match direction.trim().to_ascii_lowercase().as_str() {
+                "up" => ScrollDirection::Up,
+                "down" => ScrollDirection::Down,
+                "left" => ScrollDirection::Left,
+                "right" => ScrollDirection::Right,
+                other => return Err(format!("Unknown scroll direction: {}", other)),
+            };
+            let notches = notches
+                .trim()
+                .parse::<i32>()
+                .ok()
+                .filter(|n| (1..=MAX_SCROLL_NOTCHES).contains(n))
+                .ok_or_else(|| format!("Scroll amount must be 1-{} notches: {}", MAX_SCROLL_NOTCHES, notches))?;
+            Ok(MouseAction::Scroll(direction, notches))
+        }
+        "move" => {
+            let (dx, dy) = rest
+                .split_once(',')
+                .and_then(|(x, y)| Some((x.trim().parse::<i32>().ok()?, y.trim().parse::<i32>().ok()?)))
+                .ok_or_else(|| format!("Invalid relative move (expected \"move:dx,dy\"): {}", spec))?;
+            Ok(MouseAction::MoveBy(dx, dy))
+        }
+        _ => Err(format!(
+            "Unknown mouse action (expected click:, scroll: or move:): {}",
+            spec
+        )),
+    }
+}
+
+/// Wheel data for a scroll: whether it's the horizontal wheel, and the signed
+/// delta (positive is up/right, as SendInput expects)
+pub fn wheel_delta(direction: ScrollDirection, notches: i32) -> (bool, i32) {
+    match direction {
+        ScrollDirection::Up => (false, notches * WHEEL_DELTA),
+        ScrollDirection::Down => (false, -notches * WHEEL_DELTA),
+        ScrollDirection::Right => (true, notches * WHEEL_DELTA),
+        ScrollDirection::Left => (true, -notches * WHEEL_DELTA),
+    }
+}
+
+/// Send a MouseAction
+pub fn perform_mouse_action(action: MouseAction) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let input = |dx: i32, dy: i32, data: i32, flags: MOUSE_EVENT_FLAGS| INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dx,
+                    dy,
+                    mouseData: data as u32, // Negative wheel deltas are sent as-is
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+
+        let inputs = match action {
+            MouseAction::Click(button) => {
+                let (down, up) = match button {
+                    MouseButton::Left => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP),
+                    MouseButton::Right => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP),
+                    MouseButton::Middle => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP),
+                };
+                vec![input(0, 0, 0, down), input(0, 0, 0, up)]
+            }
+            MouseAction::Scroll(direction, notches) => {
+                let (horizontal, delta) = wheel_delta(direction, notches);
+                let flags = if horizontal { MOUSEEVENTF_HWHEEL } else { MOUSEEVENTF_WHEEL };
+                vec![input(0, 0, delta, flags)]
+            }
+            MouseAction::MoveBy(dx, dy) => vec![input(dx, dy, 0, MOUSEEVENTF_MOVE)],
+        };
+
+        let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+        if sent as usize != inputs.len() {
+            return Err(format!("SendInput sent {} of {} mouse events", sent, inputs.len()));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = action;
+        Err("Mouse actions are unsupported on this platform (Windows only)".to_string())
+    }
+}
+
+/// Parse a MouseMove action: `executable_path` holds "x,y" and `arguments`
+/// is "normalized" for 0-65535 values, otherwise the values are pixels
+pub fn parse_mouse_target(position: &str, arguments: &str) -> Result<MouseTarget, String> {
+    let (x, y) = position
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse::<i32>().ok()?, y.trim().parse::<i32>().ok()?)))
+        .ok_or_else(|| format!("Invalid mouse position (expected \"x,y\"): {}", position))?;
+
+    if arguments.trim().eq_ignore_ascii_case("normalized") {
+        let range = 0..=NORMALIZED_MAX;
+        if !range.contains(&x) || !range.contains(&y) {
+            return Err(format!("Normalized coordinates must be 0-{}: {}", NORMALIZED_MAX, position));
+        }
+        Ok(MouseTarget::Normalized(x, y))
+    } else {
+        Ok(MouseTarget::Pixels(x, y))
+    }
+}
+
+/// Map a pixel to SendInput's normalized range over the virtual desktop.
+/// Points outside the desktop are clamped to its edge.
+pub fn pixel_to_normalized(x: i32, y: i32, desktop: VirtualDesktop) -> (i32, i32) {
+    fn axis(value: i32, origin: i32, extent: i32) -> i32 {
+        let span = (extent - 1).max(1) as i64;
+        let offset = (value - origin).clamp(0, extent - 1) as i64;
+        (offset * NORMALIZED_MAX as i64 / span) as i32
+    }
+    (
+        axis(x, desktop.left, desktop.width),
+        axis(y, desktop.top, desktop.height),
+    )
+}
+
+/// Normalized coordinates for a target on the given desktop
+pub fn normalized_target(target: MouseTarget, desktop: VirtualDesktop) -> (i32, i32) {
+    match target {
+        MouseTarget::Pixels(x, y) => pixel_to_normalized(x, y, desktop),
+        MouseTarget::Normalized(x, y) => (x, y),
+    }
+}
+
+/// Current virtual desktop bounds
+#[cfg(target_os = "windows")]
+pub fn virtual_desktop() -> VirtualDesktop {
+    unsafe {
+        VirtualDesktop {
+            left: GetSystemMetrics(SM_XVIRTUALSCREEN),
+            top: GetSystemMetrics(SM_YVIRTUALSCREEN),
+            width: GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            height: GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        }
+    }
+}
+
+/// Move the cursor to `target`
+pub fn move_mouse(target: MouseTarget) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let (dx, dy) = normalized_target(target, virtual_desktop());
+        let input = INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dx,
+                    dy,
+                    mouseData: 0,
+                    dwFlags: MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+
+        let sent = unsafe { SendInput(&[input], std::mem::size_of::<INPUT>() as i32) };
+        if sent != 1 {
+            return Err("SendInput failed to move the mouse".to_string());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = target;
+        Err("Mouse movement is only supported on Windows".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Secondary 1920x1080 monitor left of a 1920x1080 primary
+    const DUAL: VirtualDesktop = VirtualDesktop {
+        left: -1920,
+        top: 0,
+        width: 3840,
+        height: 1080,
+    };
+
+    #[test]
+    fn test_pixel_to_normalized_across_monitors() {
+        assert_eq!(pixel_to_normalized(-1920, 0, DUAL), (0, 0));
+        assert_eq!(pixel_to_normalized(1919, 1079, DUAL), (NORMALIZED_MAX, NORMALIZED_MAX));
+        // The primary monitor's origin is just past the middle of the desktop
+        assert_eq!(pixel_to_normalized(0, 0, DUAL), (32776, 0));
+        // Centre of the secondary monitor
+        assert_eq!(pixel_to_normalized(-960, 540, DUAL), (16388, 32797));
+        // Off-desktop points clamp to the edge
+        assert_eq!(pixel_to_normalized(-5000, 2000, DUAL), (0, NORMALIZED_MAX));
+    }
+
+    #[test]
+    fn test_parse_mouse_target() {
+        assert_eq!(parse_mouse_target("-960, 540", ""), Ok(MouseTarget::Pixels(-960, 540)));
+        assert_eq!(
+            parse_mouse_target("32768,32768", "normalized"),
+            Ok(MouseTarget::Normalized(32768, 32768))
+        );
+        assert!(parse_mouse_target("70000,0", "normalized").is_err());
+        assert!(parse_mouse_target("100", "").is_err());
+        assert!(parse_mouse_target("a,b", "").is_err());
+    }
+
+    #[test]
+    fn test_parse_mouse_action() {
+        assert_eq!(parse_mouse_action("click:middle"), Ok(MouseAction::Click(MouseButton::Middle)));
+        assert_eq!(parse_mouse_action("CLICK"), Ok(MouseAction::Click(MouseButton::Left)));
+        assert_eq!(
+            parse_mouse_action("scroll:up:3"),
+            Ok(MouseAction::Scroll(ScrollDirection::Up, 3))
+        );
+        assert_eq!(
+            parse_mouse_action("scroll:left"),
+            Ok(MouseAction::Scroll(ScrollDirection::Left, 1))
+        );
+        assert_eq!(parse_mouse_action("move:+10,-5"), Ok(MouseAction::MoveBy(10, -5)));
+
+        assert!(parse_mouse_action("click:back").is_err());
+        assert!(parse_mouse_action("scroll:up:0").is_err());
+        assert!(parse_mouse_action("scroll:sideways").is_err());
+        assert!(parse_mouse_action("move:10").is_err());
+        assert!(parse_mouse_action("wiggle").is_err());
+    }
+
+    #[test]
+    fn test_wheel_delta_signs() {
+        assert_eq!(wheel_delta(ScrollDirection::Up, 3), (false, 360));
+        assert_eq!(wheel_delta(ScrollDirection::Down, 1), (false, -120));
+        assert_eq!(wheel_delta(ScrollDirection::Right, 2), (true, 240));
+        assert_eq!(wheel_delta(ScrollDirection::Left, 1), (true, -120));
+    }
+
+    #[test]
+    fn test_normalized_target_passes_through() {
+        assert_eq!(normalized_target(MouseTarget::Normalized(10, 20), DUAL), (10, 20));
+    }
+}
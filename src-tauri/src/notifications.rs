@@ -0,0 +1,84 @@
+// ============================================
+// Desktop Notifications
+// Shows a native toast when a binding with notify_on_execute fires
+// ============================================
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// Minimum time between two notifications for the same binding
+const NOTIFICATION_THROTTLE_MS: u64 = 3000;
+
+/// Tracks when each binding last showed a notification so a held or
+/// repeating button doesn't flood the desktop with toasts
+#[derive(Debug)]
+pub struct NotificationThrottle {
+    interval: Duration,
+    last_shown: HashMap<String, Instant>,
+}
+
+impl NotificationThrottle {
+    pub fn new() -> Self {
+        Self::with_interval(Duration::from_millis(NOTIFICATION_THROTTLE_MS))
+    }
+
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_shown: HashMap::new(),
+        }
+    }
+
+    /// Returns true (and records `now`) if the binding may notify again
+    pub fn should_notify(&mut self, binding_id: &str, now: Instant) -> bool {
+        if let Some(last) = self.last_shown.get(binding_id) {
+            if now.duration_since(*last) < self.interval {
+                return false;
+            }
+        }
+
+        self.last_shown.insert(binding_id.to_string(), now);
+        true
+    }
+}
+
+/// Show a native desktop notification
+pub fn show_notification(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::error!("Failed to show notification: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_notification_allowed() {
+        let mut throttle = NotificationThrottle::new();
+        assert!(throttle.should_notify("bind-1", Instant::now()));
+    }
+
+    #[test]
+    fn test_repeat_within_interval_suppressed() {
+        let mut throttle = NotificationThrottle::with_interval(Duration::from_millis(1000));
+        let start = Instant::now();
+
+        assert!(throttle.should_notify("bind-1", start));
+        assert!(!throttle.should_notify("bind-1", start + Duration::from_millis(200)));
+        assert!(!throttle.should_notify("bind-1", start + Duration::from_millis(999)));
+        assert!(throttle.should_notify("bind-1", start + Duration::from_millis(1000)));
+    }
+
+    #[test]
+    fn test_bindings_throttled_independently() {
+        let mut throttle = NotificationThrottle::with_interval(Duration::from_millis(1000));
+        let start = Instant::now();
+
+        assert!(throttle.should_notify("bind-1", start));
+        assert!(throttle.should_notify("bind-2", start + Duration::from_millis(10)));
+        assert!(!throttle.should_notify("bind-1", start + Duration::from_millis(20)));
+    }
+}
@@ -0,0 +1,236 @@
+// ============================================
+// Plugin Actions
+// Lets users add action types without modifying the crate by registering an
+// external executable under a name.
+//
+// Protocol:
+//   - A `Plugin` action stores the registered plugin name in `executable_path`
+//     and passes `arguments` through untouched in the payload.
+//   - The executable is started with no command-line arguments and receives a
+//     single JSON document (`PluginPayload`, camelCase) on stdin, then stdin
+//     is closed.
+//   - Exit code 0 means success; anything else is a failure.
+//   - Trimmed stdout is logged as the result message (stderr on failure).
+//   - A plugin still running after the action's `timeout_ms` (default
+//     `PLUGIN_TIMEOUT_MS`) is killed and counts as a failure.
+// ============================================
+
+use crate::launch::{self, WaitOutcome};
+use crate::shell::{read_in_background, OUTPUT_GRACE};
+use crate::types::TriggerType;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// How long a plugin may run before it's killed, unless the action sets its
+/// own `timeout_ms`
+pub const PLUGIN_TIMEOUT_MS: u64 = 30_000;
+
+/// A user-registered plugin executable
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginRegistration {
+    pub name: String,
+    pub executable_path: String,
+}
+
+/// JSON document written to the plugin's stdin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginPayload {
+    pub device: PluginDevice,
+    /// Per-button identifier, when the device reports one
+    pub button: Option<String>,
+    pub trigger: TriggerType,
+    pub context: PluginContext,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDevice {
+    pub device_id: String,
+    pub vendor_id: String,
+    pub product_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginContext {
+    pub binding_id: Option<String>,
+    pub arguments: String,
+    pub timestamp: String,
+    pub app_version: String,
+}
+
+impl PluginPayload {
+    pub fn new(
+        device_id: &str,
+        trigger: TriggerType,
+        binding_id: Option<String>,
+        arguments: &str,
+    ) -> Self {
+        let (vendor_id, product_id) = device_id.split_once(':').unwrap_or((device_id, ""));
+        Self {
+            device: PluginDevice {
+                device_id: device_id.to_string(),
+                vendor_id: vendor_id.to_string(),
+                product_id: product_id.split(':').next().unwrap_or("").to_string(),
+            },
+            button: None,
+            trigger,
+            context: PluginContext {
+                binding_id,
+                arguments: arguments.to_string(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+        }
+    }
+
+    pub fn with_button(mut self, button: Option<String>) -> Self {
+        self.button = button;
+        self
+    }
+}
+
+/// The payload's `button` for a press: the consumer usage name for media
+/// keys, otherwise the key code as hex
+pub fn button_id(button_code: Option<u16>, consumer_usage: Option<&str>) -> Option<String> {
+    consumer_usage
+        .map(str::to_string)
+        .or_else(|| button_code.map(|code| format!("0x{:02X}", code)))
+}
+
+/// Look up a plugin by name (case-insensitive)
+pub fn find_plugin<'a>(plugins: &'a [PluginRegistration], name: &str) -> Option<&'a PluginRegistration> {
+    plugins.iter().find(|p| p.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Run a plugin to completion, returning its stdout on success. Kills it and
+/// errors if it's still running after `timeout`.
+pub fn run_plugin(plugin: &PluginRegistration, payload: &PluginPayload, timeout: Duration) -> Result<String, String> {
+    let json = serde_json::to_string(payload).map_err(|e| e.to_string())?;
+
+    let mut child = Command::new(&plugin.executable_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start plugin '{}': {}", plugin.name, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A plugin that ignores stdin may close it early; that's not an error
+        let _ = stdin.write_all(json.as_bytes());
+    }
+
+    // Drain both pipes while waiting, or a chatty plugin blocks on a full pipe
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let code = match launch::wait_with_timeout(&mut child, timeout) {
+        WaitOutcome::Exited(code) => code,
+        WaitOutcome::Killed => {
+            return Err(format!(
+                "Plugin '{}' timed out after {}ms and was stopped",
+                plugin.name,
+                timeout.as_millis()
+            ))
+        }
+        WaitOutcome::Failed(e) => return Err(format!("Plugin '{}' failed: {}", plugin.name, e)),
+    };
+
+    if code == Some(0) {
+        Ok(stdout.recv_timeout(OUTPUT_GRACE).unwrap_or_default().trim().to_string())
+    } else {
+        let stderr = stderr.recv_timeout(OUTPUT_GRACE).unwrap_or_default().trim().to_string();
+        Err(format!(
+            "Plugin '{}' exited with {}: {}",
+            plugin.name,
+            code.map(|c| c.to_string()).unwrap_or_else(|| "signal".to_string()),
+            stderr
+        ))
+    }
+}
+
+/// How long a plugin action may run
+pub fn plugin_timeout(action: &crate::types::ActionConfig) -> Duration {
+    launch::action_timeout(action).unwrap_or(Duration::from_millis(PLUGIN_TIMEOUT_MS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_serialization() {
+        let payload = PluginPayload::new(
+            "AF88:6688",
+            TriggerType::DoublePress,
+            Some("bind-1".to_string()),
+            "--mode fast",
+        );
+        let json = serde_json::to_value(&payload).unwrap();
+
+        assert_eq!(json["device"]["deviceId"], "AF88:6688");
+        assert_eq!(json["device"]["vendorId"], "AF88");
+        assert_eq!(json["device"]["productId"], "6688");
+        assert!(json["button"].is_null());
+        assert_eq!(json["trigger"], "double-press");
+        assert_eq!(json["context"]["bindingId"], "bind-1");
+        assert_eq!(json["context"]["arguments"], "--mode fast");
+        assert_eq!(json["context"]["appVersion"], env!("CARGO_PKG_VERSION"));
+        assert!(json["context"]["timestamp"].is_string());
+    }
+
+    #[test]
+    fn test_button_id() {
+        assert_eq!(button_id(Some(0x41), None).as_deref(), Some("0x41"));
+        assert_eq!(button_id(None, Some("volume-up")).as_deref(), Some("volume-up"));
+        assert_eq!(button_id(None, None), None);
+
+        let payload = PluginPayload::new("AF88:6688", TriggerType::SinglePress, None, "")
+            .with_button(button_id(Some(0xB3), None));
+        assert_eq!(serde_json::to_value(&payload).unwrap()["button"], "0xB3");
+    }
+
+    #[test]
+    fn test_payload_device_id_with_interface() {
+        let payload = PluginPayload::new("AF88:6688:1", TriggerType::SinglePress, None, "");
+        assert_eq!(payload.device.vendor_id, "AF88");
+        assert_eq!(payload.device.product_id, "6688");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hung_plugin_is_stopped_after_timeout() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("usb-configurator-plugin-{}.sh", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "#!/bin/sh\nsleep 10\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let plugin = PluginRegistration {
+            name: "Hang".to_string(),
+            executable_path: path.to_string_lossy().into_owned(),
+        };
+        let payload = PluginPayload::new("AF88:6688", TriggerType::SinglePress, None, "");
+
+        let started = std::time::Instant::now();
+        let result = run_plugin(&plugin, &payload, Duration::from_millis(200));
+        let _ = std::fs::remove_file(&path);
+
+        assert!(result.unwrap_err().contains("timed out after 200ms"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_find_plugin_case_insensitive() {
+        let plugins = vec![PluginRegistration {
+            name: "OBS".to_string(),
+            executable_path: "obs-plugin.exe".to_string(),
+        }];
+        assert!(find_plugin(&plugins, "obs").is_some());
+        assert!(find_plugin(&plugins, " OBS ").is_some());
+        assert!(find_plugin(&plugins, "spotify").is_none());
+    }
+}
@@ -0,0 +1,120 @@
+// ============================================
+// Power Conditions
+// Gates or branches a binding's action on AC vs battery power
+// ============================================
+
+use crate::types::{ActionConfig, PowerCondition, PowerSource};
+
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+/// Which action a binding should run given the current power source
+#[derive(Debug, PartialEq)]
+pub enum PowerBranch<'a> {
+    /// No condition, condition met, or power state unknown
+    Primary,
+    /// Condition not met; run the alternate action instead
+    Alternate(&'a ActionConfig),
+    /// Condition not met and no alternate configured
+    Skip,
+}
+
+/// Decide which branch runs. An unknown power source never blocks the
+/// primary action, so desktops without battery info behave as before.
+pub fn resolve_power_branch(condition: Option<&PowerCondition>, source: PowerSource) -> PowerBranch<'_> {
+    let Some(condition) = condition else {
+        return PowerBranch::Primary;
+    };
+
+    if source == PowerSource::Unknown || source == condition.run_on {
+        return PowerBranch::Primary;
+    }
+
+    match &condition.otherwise {
+        Some(action) => PowerBranch::Alternate(action),
+        None => PowerBranch::Skip,
+    }
+}
+
+/// Map `SYSTEM_POWER_STATUS::ACLineStatus` to a power source
+pub fn power_source_from_ac_line_status(status: u8) -> PowerSource {
+    match status {
+        0 => PowerSource::Battery,
+        1 => PowerSource::Ac,
+        _ => PowerSource::Unknown, // 255 = unknown
+    }
+}
+
+/// Current power source of this machine
+pub fn current_power_source() -> PowerSource {
+    #[cfg(target_os = "windows")]
+    {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        match unsafe { GetSystemPowerStatus(&mut status) } {
+            Ok(()) => power_source_from_ac_line_status(status.ACLineStatus),
+            Err(e) => {
+                log::warn!("GetSystemPowerStatus failed: {}", e);
+                PowerSource::Unknown
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        PowerSource::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ActionType;
+
+    fn alternate() -> ActionConfig {
+        ActionConfig {
+            r#type: ActionType::Hotkey,
+            executable_path: "VolumeMute".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_no_condition_always_primary() {
+        assert_eq!(resolve_power_branch(None, PowerSource::Battery), PowerBranch::Primary);
+    }
+
+    #[test]
+    fn test_ac_only_gate() {
+        let condition = PowerCondition {
+            run_on: PowerSource::Ac,
+            otherwise: None,
+        };
+        assert_eq!(resolve_power_branch(Some(&condition), PowerSource::Ac), PowerBranch::Primary);
+        assert_eq!(resolve_power_branch(Some(&condition), PowerSource::Battery), PowerBranch::Skip);
+        assert_eq!(resolve_power_branch(Some(&condition), PowerSource::Unknown), PowerBranch::Primary);
+    }
+
+    #[test]
+    fn test_branch_to_alternate_on_battery() {
+        let action = alternate();
+        let condition = PowerCondition {
+            run_on: PowerSource::Ac,
+            otherwise: Some(action.clone()),
+        };
+        match resolve_power_branch(Some(&condition), power_source_from_ac_line_status(0)) {
+            PowerBranch::Alternate(a) => assert_eq!(a.executable_path, "VolumeMute"),
+            other => panic!("expected alternate branch, got {:?}", other),
+        }
+        assert_eq!(
+            resolve_power_branch(Some(&condition), power_source_from_ac_line_status(1)),
+            PowerBranch::Primary
+        );
+    }
+
+    #[test]
+    fn test_ac_line_status_mapping() {
+        assert_eq!(power_source_from_ac_line_status(0), PowerSource::Battery);
+        assert_eq!(power_source_from_ac_line_status(1), PowerSource::Ac);
+        assert_eq!(power_source_from_ac_line_status(255), PowerSource::Unknown);
+    }
+}
@@ -1,13 +1,44 @@
+use crate::buttons::{self, ButtonStateTracker};
+use crate::consumer;
 use crate::input_monitor::InputMonitor;
+use crate::key_repeat::KeyRepeatTracker;
 use crate::types::{DeviceStatus, HidDevice};
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
-use std::sync::Arc;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
 static MONITOR_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Message windows of running message loops (HWND values), so shutdown can
+/// end every loop, including ones owned by other threads
+static ACTIVE_WINDOWS: Mutex<Vec<isize>> = Mutex::new(Vec::new());
+
+thread_local! {
+    // Window procedures run on the message loop thread, one tracker per loop
+    static KEY_REPEATS: RefCell<KeyRepeatTracker> = RefCell::new(KeyRepeatTracker::new());
+    // Serial per device path; opening the device on every press would be slow
+    static SERIAL_NUMBERS: RefCell<HashMap<String, Option<String>>> = RefCell::new(HashMap::new());
+    static GAMEPAD_BUTTONS: RefCell<ButtonStateTracker> = RefCell::new(ButtonStateTracker::new());
+    // Top-level usage and preparsed report descriptor per HID device handle
+    static HID_DEVICES: RefCell<HashMap<isize, Option<HidDeviceData>>> = RefCell::new(HashMap::new());
+}
+
+/// What's needed to decode a non-keyboard HID device's reports
+struct HidDeviceData {
+    usage_page: u16,
+    usage: u16,
+    preparsed: Vec<u8>,
+}
+use windows::core::PCWSTR;
 use windows::Win32::Devices::HumanInterfaceDevice::*;
 use windows::Win32::Foundation::*;
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+};
 use windows::Win32::System::LibraryLoader::*;
 use windows::Win32::UI::Input::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
@@ -18,6 +49,12 @@ pub struct RawInputDevice {
     pub product_id: u16,
     pub device_handle: isize,
     pub device_name: String,
+    pub consumer_usage: Option<u16>, // Set for consumer-control (media key) input
+    pub raw_report: Vec<u8>,         // Payload after the RAWINPUTHEADER
+    pub is_repeat: bool,             // OS auto-repeat of a held key
+    pub button_code: Option<u16>,    // Virtual key of keyboard input
+    pub serial_number: Option<String>, // HID serial string, if the device reports one
+    pub interface_number: Option<u8>,  // MI_xx from the device name; only composite devices have one
 }
 
 pub struct RawInputMonitor {
@@ -117,24 +154,36 @@ impl RawInputMonitor {
 
         println!("🔵 [RawInput] Created message window: {:?}", hwnd);
 
+        if let Ok(mut windows) = ACTIVE_WINDOWS.lock() {
+            windows.push(hwnd.0 as isize);
+        }
+
         // Store the channel sender in window user data
         let tx_ptr = Box::into_raw(Box::new(tx));
         SetWindowLongPtrW(hwnd, GWLP_USERDATA, tx_ptr as isize);
 
-        // Register for raw keyboard input
-        let rid = RAWINPUTDEVICE {
-            usUsagePage: HID_USAGE_PAGE_GENERIC,
-            usUsage: HID_USAGE_GENERIC_KEYBOARD,
+        // Register for raw keyboard, consumer-control (media key), mouse and
+        // game controller input
+        let rid = |usage_page: u16, usage: u16| RAWINPUTDEVICE {
+            usUsagePage: usage_page,
+            usUsage: usage,
             dwFlags: RIDEV_INPUTSINK, // Receive input even when not focused
             hwndTarget: hwnd,
         };
-
-        if let Err(e) = RegisterRawInputDevices(&[rid], std::mem::size_of::<RAWINPUTDEVICE>() as u32) {
+        let rids = [
+            rid(HID_USAGE_PAGE_GENERIC, HID_USAGE_GENERIC_KEYBOARD),
+            rid(HID_USAGE_PAGE_CONSUMER, HID_USAGE_CONSUMERCTRL),
+            rid(HID_USAGE_PAGE_GENERIC, HID_USAGE_GENERIC_MOUSE),
+            rid(HID_USAGE_PAGE_GENERIC, buttons::HID_USAGE_GENERIC_JOYSTICK),
+            rid(HID_USAGE_PAGE_GENERIC, buttons::HID_USAGE_GENERIC_GAMEPAD),
+        ];
+
+        if let Err(e) = RegisterRawInputDevices(&rids, std::mem::size_of::<RAWINPUTDEVICE>() as u32) {
             println!("❌ [RawInput] RegisterRawInputDevices failed: {:?}", e);
             return Err(e);
         }
 
-        println!("✅ [RawInput] Registered for raw keyboard input");
+        log::info!("Raw Input registered for keyboard, consumer-control, mouse and game controller input");
 
         // Message loop
         let mut msg = MSG::default();
@@ -156,6 +205,11 @@ impl RawInputMonitor {
         println!("🔵 [RawInput] Message loop ended");
 
         // Cleanup
+        if let Ok(mut windows) = ACTIVE_WINDOWS.lock() {
+            windows.retain(|&w| w != hwnd.0 as isize);
+        }
+        let _ = DestroyWindow(hwnd);
+        let _ = UnregisterClassW(windows::core::PCWSTR(class_name.as_ptr()), h_instance);
         let _ = Box::from_raw(tx_ptr);
 
         Ok(())
@@ -220,23 +274,102 @@ impl RawInputMonitor {
                 // Only process keyboard input
                 if raw.header.dwType == RIM_TYPEKEYBOARD.0 {
                     let keyboard = &raw.data.keyboard;
+                    let device = raw.header.hDevice.0 as isize;
+
+                    // Key-ups only matter for telling repeats from new presses
+                    if keyboard.Message == WM_KEYUP || keyboard.Message == WM_SYSKEYUP {
+                        KEY_REPEATS.with(|t| t.borrow_mut().key_up(device, keyboard.VKey));
+                    }
 
                     // Only process key down events
                     if keyboard.Message == WM_KEYDOWN {
+                        let is_repeat = KEY_REPEATS.with(|t| t.borrow_mut().key_down(device, keyboard.VKey));
                         println!("⌨️  [RawInput] Key down detected from device handle: {:?}", raw.header.hDevice);
 
                         // Get device info
-                        if let Some(device_info) = Self::get_device_info(raw.header.hDevice) {
+                        if let Some(mut device_info) = Self::get_device_info(raw.header.hDevice) {
                             println!("🎯 [RawInput] Device: {:04X}:{:04X} - {}",
                                 device_info.vendor_id,
                                 device_info.product_id,
                                 device_info.device_name
                             );
 
+                            device_info.raw_report = buffer[std::mem::size_of::<RAWINPUTHEADER>()..].to_vec();
+                            device_info.is_repeat = is_repeat;
+                            device_info.button_code = Some(keyboard.VKey);
+
                             // Send to channel
                             let _ = tx.send(device_info);
                         }
                     }
+                } else if raw.header.dwType == RIM_TYPEMOUSE.0 {
+                    // Button presses only; movement and wheel carry no buttons
+                    let flags = raw.data.mouse.Anonymous.Anonymous.usButtonFlags;
+                    for button in buttons::mouse_buttons_down(flags) {
+                        if let Some(mut device_info) = Self::get_device_info(raw.header.hDevice) {
+                            log::debug!("Raw Input mouse button 0x{:02X} from {:04X}:{:04X}",
+                                button,
+                                device_info.vendor_id,
+                                device_info.product_id
+                            );
+                            device_info.raw_report = buffer[std::mem::size_of::<RAWINPUTHEADER>()..].to_vec();
+                            device_info.button_code = Some(button);
+                            let _ = tx.send(device_info);
+                        }
+                    }
+                } else if raw.header.dwType == RIM_TYPEHID.0 {
+                    let hid = &raw.data.hid;
+                    let report_size = hid.dwSizeHid as usize;
+                    let reports = std::slice::from_raw_parts(
+                        hid.bRawData.as_ptr(),
+                        report_size * hid.dwCount as usize,
+                    );
+                    let device = raw.header.hDevice.0 as isize;
+                    let is_game_controller = Self::with_hid_device_data(raw.header.hDevice, |data| {
+                        buttons::is_game_controller(data.usage_page, data.usage)
+                    })
+                    .unwrap_or(false);
+
+                    if report_size > 0 && is_game_controller {
+                        // Gamepad reports: one event per newly pressed button
+                        for report in reports.chunks_exact(report_size) {
+                            let Some(held) = Self::with_hid_device_data(raw.header.hDevice, |data| {
+                                Self::held_buttons(data, report)
+                            })
+                            .flatten() else {
+                                continue;
+                            };
+                            let pressed = GAMEPAD_BUTTONS.with(|t| t.borrow_mut().update(device, &held));
+                            for usage in pressed {
+                                if let Some(mut device_info) = Self::get_device_info(raw.header.hDevice) {
+                                    log::debug!("Raw Input gamepad button {} from {:04X}:{:04X}",
+                                        usage,
+                                        device_info.vendor_id,
+                                        device_info.product_id
+                                    );
+                                    device_info.button_code = Some(buttons::gamepad_button_code(usage));
+                                    device_info.raw_report = report.to_vec();
+                                    let _ = tx.send(device_info);
+                                }
+                            }
+                        }
+                    } else if report_size > 0 {
+                        // Consumer-control reports: one event per pressed usage
+                        for report in reports.chunks_exact(report_size) {
+                            for usage in consumer::parse_consumer_report(report) {
+                                if let Some(mut device_info) = Self::get_device_info(raw.header.hDevice) {
+                                    log::debug!("Raw Input consumer usage {} from {:04X}:{:04X}",
+                                        consumer::consumer_usage_id(usage),
+                                        device_info.vendor_id,
+                                        device_info.product_id
+                                    );
+                                    device_info.consumer_usage = Some(usage);
+                                    device_info.raw_report = report.to_vec();
+                                    let _ = tx.send(device_info);
+                                }
+                            }
+                        }
+                    }
                 }
 
                 DefWindowProcW(hwnd, msg, wparam, lparam)
@@ -250,6 +383,77 @@ impl RawInputMonitor {
         }
     }
 
+    /// Run `f` on a HID device's cached usage and report descriptor, reading
+    /// them on first use. `None` if the device can't be queried.
+    unsafe fn with_hid_device_data<T>(device_handle: HANDLE, f: impl FnOnce(&HidDeviceData) -> T) -> Option<T> {
+        let key = device_handle.0 as isize;
+        HID_DEVICES.with(|devices| {
+            let mut devices = devices.borrow_mut();
+            let data = devices.entry(key).or_insert_with(|| Self::read_hid_device_data(device_handle));
+            data.as_ref().map(f)
+        })
+    }
+
+    unsafe fn read_hid_device_data(device_handle: HANDLE) -> Option<HidDeviceData> {
+        let mut info = RID_DEVICE_INFO {
+            cbSize: std::mem::size_of::<RID_DEVICE_INFO>() as u32,
+            ..Default::default()
+        };
+        let mut size = info.cbSize;
+        let result = GetRawInputDeviceInfoW(
+            device_handle,
+            RIDI_DEVICEINFO,
+            Some(&mut info as *mut _ as *mut _),
+            &mut size,
+        );
+        if result == u32::MAX || info.dwType != RIM_TYPEHID {
+            return None;
+        }
+
+        let mut preparsed_size: u32 = 0;
+        GetRawInputDeviceInfoW(device_handle, RIDI_PREPARSEDDATA, None, &mut preparsed_size);
+        let mut preparsed = vec![0u8; preparsed_size as usize];
+        let result = GetRawInputDeviceInfoW(
+            device_handle,
+            RIDI_PREPARSEDDATA,
+            Some(preparsed.as_mut_ptr() as *mut _),
+            &mut preparsed_size,
+        );
+        if result == u32::MAX {
+            log::warn!("Raw Input: reading the report descriptor failed");
+            preparsed.clear();
+        }
+
+        Some(HidDeviceData {
+            usage_page: info.Anonymous.hid.usUsagePage,
+            usage: info.Anonymous.hid.usUsage,
+            preparsed,
+        })
+    }
+
+    /// Button page usages held down in one input report
+    unsafe fn held_buttons(data: &HidDeviceData, report: &[u8]) -> Option<Vec<u16>> {
+        if data.preparsed.is_empty() {
+            return None;
+        }
+        let mut usages = [0u16; 128];
+        let mut count = usages.len() as u32;
+        let mut report = report.to_vec();
+        let status = HidP_GetUsages(
+            HidP_Input,
+            HID_USAGE_PAGE_BUTTON,
+            0,
+            usages.as_mut_ptr(),
+            &mut count,
+            PHIDP_PREPARSED_DATA(data.preparsed.as_ptr() as isize),
+            &mut report,
+        );
+        if status != HIDP_STATUS_SUCCESS {
+            return None;
+        }
+        Some(usages[..count as usize].to_vec())
+    }
+
     unsafe fn get_device_info(device_handle: HANDLE) -> Option<RawInputDevice> {
         // Get device name
         let mut name_size: u32 = 0;
@@ -284,27 +488,178 @@ impl RawInputMonitor {
         // Parse VID and PID from device name
         // Format: \\?\HID#VID_XXXX&PID_YYYY&...
         let (vid, pid) = Self::parse_vid_pid(&device_name)?;
+        let serial_number = Self::read_serial_number(&device_name);
+        let interface_number = Self::parse_interface_number(&device_name);
 
         Some(RawInputDevice {
             vendor_id: vid,
             product_id: pid,
             device_handle: device_handle.0 as isize,
             device_name,
+            consumer_usage: None,
+            raw_report: Vec::new(),
+            is_repeat: false,
+            button_code: None,
+            serial_number,
+            interface_number,
         })
     }
 
+    /// Serial string of the HID interface at `device_name`, cached per path
+    unsafe fn read_serial_number(device_name: &str) -> Option<String> {
+        if let Some(cached) = SERIAL_NUMBERS.with(|s| s.borrow().get(device_name).cloned()) {
+            return cached;
+        }
+
+        let path: Vec<u16> = device_name.encode_utf16().chain(std::iter::once(0)).collect();
+        // No access rights are needed to query the device's strings
+        let serial = match CreateFileW(
+            PCWSTR(path.as_ptr()),
+            0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            HANDLE::default(),
+        ) {
+            Ok(file) => {
+                // USB string descriptors hold at most 126 UTF-16 units
+                let mut buffer = [0u16; 127];
+                let found = HidD_GetSerialNumberString(
+                    file,
+                    buffer.as_mut_ptr() as *mut _,
+                    std::mem::size_of_val(&buffer) as u32,
+                );
+                let _ = CloseHandle(file);
+
+                if found.as_bool() {
+                    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+                    Some(String::from_utf16_lossy(&buffer[..len])).filter(|s| !s.trim().is_empty())
+                } else {
+                    None
+                }
+            }
+            Err(e) => {
+                log::debug!("Cannot open {} to read its serial: {}", device_name, e);
+                None
+            }
+        };
+
+        SERIAL_NUMBERS.with(|s| s.borrow_mut().insert(device_name.to_string(), serial.clone()));
+        serial
+    }
+
+    /// Interface of a composite device from the `MI_xx` (hex) part of its name
+    fn parse_interface_number(device_name: &str) -> Option<u8> {
+        parse_name_field(device_name, "MI").and_then(|n| u8::try_from(n).ok())
+    }
+
+    /// VID and PID from a device name, in either order and any case, e.g.
+    /// `\\?\HID#VID_046D&PID_C52B&MI_01#...` or the Bluetooth form
+    /// `...}_VID&0002046d_PID&b342&Col01#...`
     fn parse_vid_pid(device_name: &str) -> Option<(u16, u16)> {
-        // Look for VID_XXXX and PID_YYYY in the device name
-        let vid_start = device_name.find("VID_")?;
-        let pid_start = device_name.find("PID_")?;
+        Some((parse_name_field(device_name, "VID")?, parse_name_field(device_name, "PID")?))
+    }
+}
+
+/// Hex value of a `KEY_xxxx` or `KEY&xxxx` field in a device name, matched
+/// case-insensitively. The value is the run of hex digits after the
+/// separator, so trailing `&`, `#` or `_` parts are ignored. Bluetooth names
+/// put a 4-digit ID source before the ID (`VID&0002046d`); only the ID is
+/// kept. `None` if the field is missing, empty or too long.
+fn parse_name_field(device_name: &str, key: &str) -> Option<u16> {
+    // ASCII uppercasing keeps byte offsets, so slices line up with the original
+    let upper = device_name.to_ascii_uppercase();
+    let mut search_from = 0;
+    while let Some(found) = upper.get(search_from..)?.find(key) {
+        let start = search_from + found + key.len();
+        search_from = start;
+
+        let rest = upper.get(start..)?;
+        let Some(separator) = rest.chars().next().filter(|c| *c == '_' || *c == '&') else {
+            continue; // e.g. "PID" inside another word
+        };
+        let digits: String = rest[separator.len_utf8()..]
+            .chars()
+            .take_while(|c| c.is_ascii_hexdigit())
+            .collect();
+        let digits = match (separator, digits.len()) {
+            (_, 1..=4) => digits.as_str(),
+            ('&', 8) => &digits[4..],
+            _ => return None,
+        };
+        return u16::from_str_radix(digits, 16).ok();
+    }
+    None
+}
+
+impl RawInputDevice {
+    fn into_hid_device(self) -> HidDevice {
+        HidDevice {
+            id: format!("{:04X}:{:04X}", self.vendor_id, self.product_id),
+            name: self.device_name,
+            vendor_id: format!("{:04X}", self.vendor_id),
+            product_id: format!("{:04X}", self.product_id),
+            interface_number: self.interface_number.unwrap_or(0),
+            // Windows names MI_xx only for composite devices; the
+            // path doesn't say how many interfaces there are
+            total_interfaces: self.interface_number.map_or(1, |n| n.saturating_add(1).max(2)),
+            status: DeviceStatus::Connected,
+            manufacturer: None,
+            serial_number: self.serial_number,
+            consumer_usage: self.consumer_usage,
+            raw_report: self.raw_report,
+            is_repeat: self.is_repeat,
+            button_code: self.button_code,
+        }
+    }
+}
 
-        let vid_str = &device_name[vid_start + 4..vid_start + 8];
-        let pid_str = &device_name[pid_start + 4..pid_start + 8];
+/// Every device Raw Input knows about that has a VID/PID, keyboards and mice
+/// included, with the device path as its name. Unlike hidapi this doesn't
+/// open the devices, so exclusively held ones show up too.
+pub fn list_raw_input_devices() -> Result<Vec<HidDevice>, String> {
+    let entry_size = std::mem::size_of::<RAWINPUTDEVICELIST>() as u32;
+    unsafe {
+        let mut count: u32 = 0;
+        if GetRawInputDeviceList(None, &mut count, entry_size) == u32::MAX {
+            return Err(format!("GetRawInputDeviceList failed: {}", windows::core::Error::from_win32()));
+        }
+
+        let mut list = vec![RAWINPUTDEVICELIST::default(); count as usize];
+        let found = GetRawInputDeviceList(Some(list.as_mut_ptr()), &mut count, entry_size);
+        if found == u32::MAX {
+            return Err(format!("GetRawInputDeviceList failed: {}", windows::core::Error::from_win32()));
+        }
+        list.truncate(found as usize);
+
+        let mut devices: Vec<HidDevice> = list
+            .iter()
+            .filter_map(|entry| RawInputMonitor::get_device_info(entry.hDevice))
+            .map(RawInputDevice::into_hid_device)
+            .collect();
+        devices.sort_by(|a, b| a.id.cmp(&b.id).then_with(|| a.name.cmp(&b.name)));
+        Ok(devices)
+    }
+}
 
-        let vid = u16::from_str_radix(vid_str, 16).ok()?;
-        let pid = u16::from_str_radix(pid_str, 16).ok()?;
+/// End every Raw Input message loop by posting `WM_QUIT` to its window,
+/// waiting up to `timeout` for the loops to clean up. Their receivers close,
+/// and the monitors' `monitoring_active` flags go false as the threads exit.
+pub fn stop_all_monitors(timeout: Duration) {
+    let windows = ACTIVE_WINDOWS.lock().map(|w| w.clone()).unwrap_or_default();
+    for window in &windows {
+        unsafe {
+            let _ = PostMessageW(HWND(*window as *mut _), WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+    }
 
-        Some((vid, pid))
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline && ACTIVE_WINDOWS.lock().is_ok_and(|w| !w.is_empty()) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    if !windows.is_empty() {
+        log::info!("Stopped {} Raw Input monitor(s)", windows.len());
     }
 }
 
@@ -318,17 +673,7 @@ impl RawInputMonitor {
         // Spawn thread to convert RawInputDevice to HidDevice
         thread::spawn(move || {
             while let Ok(raw_device) = raw_rx.recv() {
-                let hid_device = HidDevice {
-                    id: format!("{:04X}:{:04X}", raw_device.vendor_id, raw_device.product_id),
-                    name: raw_device.device_name.clone(),
-                    vendor_id: format!("{:04X}", raw_device.vendor_id),
-                    product_id: format!("{:04X}", raw_device.product_id),
-                    interface_number: 0,
-                    total_interfaces: 1,
-                    status: DeviceStatus::Connected,
-                    manufacturer: None,
-                    serial_number: None,
-                };
+                let hid_device = raw_device.into_hid_device();
 
                 println!("🔄 [RawInput] Device input: {} ({}:{})",
                     hid_device.name, hid_device.vendor_id, hid_device.product_id);
@@ -350,17 +695,7 @@ impl InputMonitor for RawInputMonitor {
         // Spawn thread to convert RawInputDevice to HidDevice
         thread::spawn(move || {
             while let Ok(raw_device) = raw_rx.recv() {
-                let hid_device = HidDevice {
-                    id: format!("{:04X}:{:04X}", raw_device.vendor_id, raw_device.product_id),
-                    name: raw_device.device_name.clone(),
-                    vendor_id: format!("{:04X}", raw_device.vendor_id),
-                    product_id: format!("{:04X}", raw_device.product_id),
-                    interface_number: 0,
-                    total_interfaces: 1,
-                    status: DeviceStatus::Connected,
-                    manufacturer: None,
-                    serial_number: None,
-                };
+                let hid_device = raw_device.into_hid_device();
 
                 println!("🔄 [RawInput] Converted device: {} ({}:{})",
                     hid_device.name, hid_device.vendor_id, hid_device.product_id);
@@ -382,3 +717,47 @@ impl InputMonitor for RawInputMonitor {
         "RawInput"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vid_pid_usb() {
+        let name = r"\\?\HID#VID_046D&PID_C52B&MI_01&Col02#8&2a8e1a8c&0&0001#{4d1e55b2-f16f-11cf-88cb-001111000030}";
+        assert_eq!(RawInputMonitor::parse_vid_pid(name), Some((0x046D, 0xC52B)));
+        assert_eq!(RawInputMonitor::parse_interface_number(name), Some(1));
+    }
+
+    #[test]
+    fn test_parse_vid_pid_lowercase_and_reordered() {
+        let name = r"\\?\hid#pid_a0c2&vid_1a86&mi_00#7&1f3e4b&0&0000#{884b96c3-56ef-11d1-bc8c-00a0c91405dd}";
+        assert_eq!(RawInputMonitor::parse_vid_pid(name), Some((0x1A86, 0xA0C2)));
+        assert_eq!(RawInputMonitor::parse_interface_number(name), Some(0));
+    }
+
+    #[test]
+    fn test_parse_vid_pid_bluetooth() {
+        let name = r"\\?\HID#{00001124-0000-1000-8000-00805f9b34fb}_VID&0002046d_PID&b342&Col01#9&1b2c3d4e&0&0000#{4d1e55b2-f16f-11cf-88cb-001111000030}";
+        assert_eq!(RawInputMonitor::parse_vid_pid(name), Some((0x046D, 0xB342)));
+        assert_eq!(RawInputMonitor::parse_interface_number(name), None);
+    }
+
+    #[test]
+    fn test_parse_vid_pid_short_ids_and_trailing_separators() {
+        assert_eq!(RawInputMonitor::parse_vid_pid(r"\\?\HID#VID_45E&PID_7A_#x"), Some((0x045E, 0x007A)));
+        assert_eq!(RawInputMonitor::parse_vid_pid("HID#VID_1234&PID_ABCD"), Some((0x1234, 0xABCD)));
+    }
+
+    #[test]
+    fn test_parse_vid_pid_malformed_is_none() {
+        // Used to slice past the end of the string and panic
+        assert_eq!(RawInputMonitor::parse_vid_pid(r"\\?\HID#VID_04"), None);
+        assert_eq!(RawInputMonitor::parse_vid_pid("VID_"), None);
+        assert_eq!(RawInputMonitor::parse_vid_pid("VID_ZZZZ&PID_0001"), None);
+        assert_eq!(RawInputMonitor::parse_vid_pid(r"\\?\ACPI#PNP0303#4&1d401fb5&0"), None);
+        assert_eq!(RawInputMonitor::parse_vid_pid("VID_123456&PID_0001"), None);
+        assert_eq!(RawInputMonitor::parse_interface_number("MI_1FF"), None);
+        assert_eq!(RawInputMonitor::parse_vid_pid("VID_é&PID_1"), None);
+    }
+}
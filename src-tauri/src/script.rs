@@ -0,0 +1,153 @@
+// ============================================
+// Script Blocks
+// Runs a multi-line script as one cmd/PowerShell process so environment
+// variables and the working directory carry over between lines.
+// ============================================
+
+use crate::launch;
+use crate::types::ActionConfig;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Shell used for a `ScriptBlock` action, chosen by the action's `arguments`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptShell {
+    Cmd,
+    PowerShell,
+}
+
+impl ScriptShell {
+    /// "powershell"/"pwsh" selects PowerShell; anything else (or nothing) is cmd
+    pub fn from_arguments(arguments: &str) -> Self {
+        match arguments.trim().to_lowercase().as_str() {
+            "powershell" | "pwsh" | "ps" => ScriptShell::PowerShell,
+            _ => ScriptShell::Cmd,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ScriptShell::Cmd => "cmd",
+            ScriptShell::PowerShell => "ps1",
+        }
+    }
+
+    /// Script file contents: CRLF line endings, echo off for cmd, and a BOM
+    /// so Windows PowerShell reads non-ASCII text as UTF-8
+    fn render(self, script: &str) -> String {
+        let body = script.lines().collect::<Vec<_>>().join("\r\n");
+        match self {
+            ScriptShell::Cmd => format!("@echo off\r\n{}\r\n", body),
+            ScriptShell::PowerShell => format!("\u{feff}{}\r\n", body),
+        }
+    }
+}
+
+/// A script written to a temp file; the file is removed when dropped
+#[derive(Debug)]
+pub struct TempScript {
+    path: PathBuf,
+}
+
+impl TempScript {
+    pub fn create(dir: &Path, script: &str, shell: ScriptShell) -> io::Result<Self> {
+        let path = dir.join(format!(
+            "usb-configurator-{}.{}",
+            uuid::Uuid::new_v4(),
+            shell.extension()
+        ));
+        fs::write(&path, shell.render(script))?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempScript {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            log::warn!("Failed to remove temp script {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Run a ScriptBlock action to completion in its working directory and
+/// environment, stopping it after its `timeout_ms`. Blocks, so callers should
+/// keep it off the listener thread. Returns the exit code.
+pub fn run_script_block(action: &ActionConfig) -> Result<i32, String> {
+    let script = action.executable_path.as_str();
+    let shell = ScriptShell::from_arguments(&action.arguments);
+    if script.trim().is_empty() {
+        return Err("Script is empty".to_string());
+    }
+
+    let temp = TempScript::create(&std::env::temp_dir(), script, shell)
+        .map_err(|e| format!("Failed to write temp script: {}", e))?;
+
+    let cmd = match shell {
+        ScriptShell::Cmd => {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(temp.path());
+            cmd
+        }
+        ScriptShell::PowerShell => {
+            let mut cmd = Command::new("powershell");
+            cmd.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"])
+                .arg(temp.path());
+            cmd
+        }
+    };
+
+    // `temp` lives until the process has exited or been stopped, then cleans up
+    launch::run_to_exit(cmd, action, launch::action_timeout(action))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_script_created_and_cleaned_up() {
+        let dir = std::env::temp_dir();
+        let script = TempScript::create(&dir, "cd C:\\Temp\nset FOO=bar\necho %FOO%", ScriptShell::Cmd).unwrap();
+        let path = script.path().to_path_buf();
+
+        assert!(path.exists());
+        assert_eq!(path.extension().unwrap(), "cmd");
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "@echo off\r\ncd C:\\Temp\r\nset FOO=bar\r\necho %FOO%\r\n");
+
+        drop(script);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_powershell_script_extension() {
+        let script = TempScript::create(&std::env::temp_dir(), "Get-Date", ScriptShell::PowerShell).unwrap();
+        assert_eq!(script.path().extension().unwrap(), "ps1");
+        assert!(fs::read_to_string(script.path()).unwrap().ends_with("Get-Date\r\n"));
+    }
+
+    #[test]
+    fn test_shell_from_arguments() {
+        assert_eq!(ScriptShell::from_arguments(""), ScriptShell::Cmd);
+        assert_eq!(ScriptShell::from_arguments("cmd"), ScriptShell::Cmd);
+        assert_eq!(ScriptShell::from_arguments(" PowerShell "), ScriptShell::PowerShell);
+        assert_eq!(ScriptShell::from_arguments("pwsh"), ScriptShell::PowerShell);
+    }
+
+    #[test]
+    fn test_empty_script_rejected() {
+        let action = ActionConfig {
+            r#type: crate::types::ActionType::ScriptBlock,
+            executable_path: "  \n ".to_string(),
+            arguments: "cmd".to_string(),
+            ..Default::default()
+        };
+        assert!(run_script_block(&action).is_err());
+    }
+}
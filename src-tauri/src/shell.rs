@@ -0,0 +1,198 @@
+// ============================================
+// Shell Capture Actions
+// A `ShellCapture` action runs a command line through the shell like
+// `SystemCommand`, but waits for it and logs its exit code and output, so a
+// failing command says why. A command that hangs is killed after a timeout.
+// ============================================
+
+use crate::args::parse_arguments;
+use crate::launch::{self, WaitOutcome};
+use crate::types::{ActionConfig, LogEntryLevel};
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// How long a ShellCapture command may run before it's killed, unless the
+/// action sets its own `timeout_ms`
+pub const SHELL_CAPTURE_TIMEOUT_MS: u64 = 30_000;
+
+/// stdout/stderr longer than this are cut short when logged
+const MAX_LOGGED_OUTPUT_CHARS: usize = 2000;
+
+/// How long to wait for the output pipes to close once the command exited.
+/// A background process it started can hold them open indefinitely.
+pub(crate) const OUTPUT_GRACE: Duration = Duration::from_millis(500);
+
+/// What a finished command left behind
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShellOutput {
+    /// `None` if the process was ended by a signal
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ShellOutput {
+    pub fn succeeded(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// The command an action runs: `cmd /C` on Windows, `sh -c` elsewhere
+pub fn shell_command(action: &ActionConfig) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", &action.executable_path]).args(parse_arguments(&action.arguments));
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &format!("{} {}", action.executable_path, action.arguments)]);
+        cmd
+    }
+}
+
+/// Run a ShellCapture action to completion. Blocks for up to the timeout,
+/// so callers should keep it off the listener thread.
+pub fn run_shell_capture(action: &ActionConfig) -> Result<ShellOutput, String> {
+    let timeout = launch::action_timeout(action).unwrap_or(Duration::from_millis(SHELL_CAPTURE_TIMEOUT_MS));
+    run_captured(shell_command(action), action, timeout)
+}
+
+/// Run `cmd` with the action's working directory and environment, collecting
+/// its output. Kills it and errors if it's still running after `timeout`.
+pub fn run_captured(mut cmd: Command, action: &ActionConfig, timeout: Duration) -> Result<ShellOutput, String> {
+    cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = launch::spawn_with_options(cmd, action).map_err(|e| format!("Failed to start command: {}", e))?;
+
+    // Drain both pipes while waiting, or a chatty command blocks on a full pipe
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let code = match launch::wait_with_timeout(&mut child, timeout) {
+        WaitOutcome::Exited(code) => code,
+        WaitOutcome::Killed => {
+            return Err(format!("Command timed out after {}ms and was stopped", timeout.as_millis()))
+        }
+        WaitOutcome::Failed(e) => return Err(e),
+    };
+
+    Ok(ShellOutput {
+        code,
+        stdout: stdout.recv_timeout(OUTPUT_GRACE).unwrap_or_default(),
+        stderr: stderr.recv_timeout(OUTPUT_GRACE).unwrap_or_default(),
+    })
+}
+
+pub(crate) fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        thread::spawn(move || {
+            let mut bytes = Vec::new();
+            let _ = pipe.read_to_end(&mut bytes);
+            let _ = sender.send(String::from_utf8_lossy(&bytes).into_owned());
+        });
+    }
+    receiver
+}
+
+/// Trimmed output, cut after `max` characters
+pub fn summarize_output(output: &str, max: usize) -> String {
+    let trimmed = output.trim();
+    let total = trimmed.chars().count();
+    if total <= max {
+        return trimmed.to_string();
+    }
+    let kept: String = trimmed.chars().take(max).collect();
+    format!("{} ... ({} characters total)", kept, total)
+}
+
+/// Log level and message for a ShellCapture run
+pub fn describe_result(result: &Result<ShellOutput, String>) -> (LogEntryLevel, String) {
+    let output = match result {
+        Ok(output) => output,
+        Err(e) => return (LogEntryLevel::Error, format!("Shell command failed: {}", e)),
+    };
+
+    let mut message = match output.code {
+        Some(code) => format!("Shell command exited with code {}", code),
+        None => "Shell command was terminated without an exit code".to_string(),
+    };
+    for (label, text) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+        let text = summarize_output(text, MAX_LOGGED_OUTPUT_CHARS);
+        if !text.is_empty() {
+            message.push_str(&format!("\n{}: {}", label, text));
+        }
+    }
+
+    let level = if output.succeeded() {
+        LogEntryLevel::Success
+    } else {
+        LogEntryLevel::Error
+    };
+    (level, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ActionType;
+    use std::time::Instant;
+
+    fn action(command: &str) -> ActionConfig {
+        ActionConfig {
+            r#type: ActionType::ShellCapture,
+            executable_path: command.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn output(code: Option<i32>, stdout: &str, stderr: &str) -> ShellOutput {
+        ShellOutput {
+            code,
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_run_shell_capture_collects_output_and_code() {
+        let result = run_shell_capture(&action("echo hello && exit 3")).unwrap();
+        assert_eq!(result.code, Some(3));
+        assert_eq!(result.stdout.trim(), "hello");
+        assert!(!result.succeeded());
+    }
+
+    #[test]
+    fn test_run_shell_capture_kills_on_timeout() {
+        let mut hang = action(if cfg!(target_os = "windows") { "ping -n 10 127.0.0.1" } else { "sleep 10" });
+        hang.timeout_ms = Some(200);
+        let started = Instant::now();
+        let result = run_shell_capture(&hang);
+        assert!(result.unwrap_err().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_summarize_output() {
+        assert_eq!(summarize_output("  done\r\n", 10), "done");
+        assert_eq!(summarize_output("abcdef", 3), "abc ... (6 characters total)");
+        assert_eq!(summarize_output("", 3), "");
+    }
+
+    #[test]
+    fn test_describe_result() {
+        let (level, message) = describe_result(&Ok(output(Some(0), "ok\n", "")));
+        assert_eq!(level, LogEntryLevel::Success);
+        assert_eq!(message, "Shell command exited with code 0\nstdout: ok");
+
+        let (level, message) = describe_result(&Ok(output(Some(1), "", "not found\n")));
+        assert_eq!(level, LogEntryLevel::Error);
+        assert_eq!(message, "Shell command exited with code 1\nstderr: not found");
+
+        let (level, message) = describe_result(&Err("Failed to start command: denied".to_string()));
+        assert_eq!(level, LogEntryLevel::Error);
+        assert!(message.contains("denied"));
+    }
+}
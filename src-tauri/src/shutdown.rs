@@ -0,0 +1,83 @@
+// ============================================
+// Graceful Shutdown
+// Stops monitors and background servers and writes out buffered state before
+// the process exits, so no thread is abandoned mid-loop and the last log
+// entries aren't lost
+// ============================================
+
+use crate::AppState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager};
+
+/// How long to wait for Raw Input message loops to exit
+#[cfg(windows)]
+const MONITOR_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Whether shutdown has begun. Supervisors check this so they don't restart
+/// what shutdown just stopped.
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Mark shutdown as started. Returns `false` if it already was.
+fn begin_shutdown() -> bool {
+    begin_once(&SHUTTING_DOWN)
+}
+
+/// Set `flag`, returning `false` if it was already set
+fn begin_once(flag: &AtomicBool) -> bool {
+    !flag.swap(true, Ordering::SeqCst)
+}
+
+/// Stop everything that runs in the background and flush state. Safe to call
+/// more than once (tray Quit runs it, then the exit event does); only the
+/// first call does anything.
+pub fn shutdown(app: &AppHandle) {
+    if !begin_shutdown() {
+        return;
+    }
+    log::info!("Shutting down...");
+    let state = app.state::<AppState>();
+
+    if let Ok(hid) = state.hid_manager.lock() {
+        if hid.is_monitoring() {
+            hid.stop_monitoring();
+        }
+    }
+
+    #[cfg(windows)]
+    crate::rawinput::stop_all_monitors(MONITOR_STOP_TIMEOUT);
+
+    if let Ok(mut server) = state.http_api.lock() {
+        if let Some(server) = server.take() {
+            server.stop();
+        }
+    }
+
+    if let Ok(mut config) = state.config_manager.lock() {
+        if let Err(e) = config.flush_logs() {
+            log::warn!("Failed to write logs on exit: {}", e);
+        }
+    }
+
+    // Last, so a relaunch only takes over once we're done
+    if let Ok(mut lock) = state.instance_lock.lock() {
+        lock.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shutdown_begins_once() {
+        // A local flag: setting SHUTTING_DOWN would stop other tests' loops
+        let flag = AtomicBool::new(false);
+        assert!(begin_once(&flag));
+        assert!(flag.load(Ordering::SeqCst));
+        assert!(!begin_once(&flag));
+    }
+}
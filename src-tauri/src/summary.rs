@@ -0,0 +1,113 @@
+// ============================================
+// Binding Summary
+// Renders bindings as Markdown for documenting a setup
+// ============================================
+
+use crate::types::DeviceBinding;
+use serde::Serialize;
+
+/// Render all bindings as a Markdown table, one row per binding.
+/// Bindings are grouped per device so multi-binding setups read top-down.
+pub fn bindings_to_markdown(bindings: &[DeviceBinding]) -> String {
+    let mut sorted: Vec<&DeviceBinding> = bindings.iter().collect();
+    sorted.sort_by(|a, b| a.device_id.cmp(&b.device_id).then(a.created_at.cmp(&b.created_at)));
+
+    let mut out = String::from("# Button Bindings\n\n");
+    if sorted.is_empty() {
+        out.push_str("_No bindings configured._\n");
+        return out;
+    }
+
+    out.push_str("| Device | Trigger | Action | Target | Enabled |\n");
+    out.push_str("| --- | --- | --- | --- | --- |\n");
+    for binding in sorted {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            escape_cell(&device_label(binding)),
+            serde_label(&binding.trigger_type),
+            serde_label(&binding.action.r#type),
+            escape_cell(&action_target(binding)),
+            if binding.enabled { "yes" } else { "no" },
+        ));
+    }
+    out
+}
+
+fn device_label(binding: &DeviceBinding) -> String {
+    match &binding.manufacturer {
+        Some(manufacturer) => format!("{} ({})", binding.device_id, manufacturer),
+        None => binding.device_id.clone(),
+    }
+}
+
+fn action_target(binding: &DeviceBinding) -> String {
+    let action = &binding.action;
+    let mut target = action.executable_path.trim().to_string();
+    if !action.arguments.trim().is_empty() {
+        target.push(' ');
+        target.push_str(action.arguments.trim());
+    }
+    if let Some(window) = &action.target_window {
+        target.push_str(&format!(" → {}", window));
+    }
+    if target.is_empty() {
+        "—".to_string()
+    } else {
+        target
+    }
+}
+
+/// The serialized (kebab-case) name of an enum, matching the config file
+pub fn serde_label<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Keep user text from breaking the table
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ActionConfig, ActionType, TriggerType};
+
+    fn binding(device_id: &str, action_type: ActionType, path: &str, args: &str) -> DeviceBinding {
+        DeviceBinding::new(
+            device_id.to_string(),
+            "AF88".to_string(),
+            "6688".to_string(),
+            TriggerType::DoublePress,
+            ActionConfig {
+                r#type: action_type,
+                executable_path: path.to_string(),
+                arguments: args.to_string(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_markdown_contains_binding_fields() {
+        let mut hotkey = binding("AF88:6688", ActionType::Hotkey, "Ctrl+Shift+M", "");
+        hotkey.manufacturer = Some("Acme".to_string());
+        hotkey.enabled = false;
+        let launch = binding("1234:5678", ActionType::LaunchApp, "C:\\Tools\\app.exe", "--flag a|b");
+
+        let md = bindings_to_markdown(&[hotkey, launch]);
+
+        assert!(md.contains("| Device | Trigger | Action | Target | Enabled |"));
+        assert!(md.contains("| AF88:6688 (Acme) | double-press | hotkey | Ctrl+Shift+M | no |"));
+        assert!(md.contains("| 1234:5678 | double-press | launch-app | C:\\Tools\\app.exe --flag a\\|b | yes |"));
+        // Sorted by device ID
+        assert!(md.find("1234:5678").unwrap() < md.find("AF88:6688").unwrap());
+    }
+
+    #[test]
+    fn test_markdown_empty() {
+        assert!(bindings_to_markdown(&[]).contains("No bindings configured"));
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +14,69 @@ pub struct HidDevice {
     pub status: DeviceStatus,
     pub manufacturer: Option<String>,
     pub serial_number: Option<String>,
+    /// Consumer-page usage (media key) that produced this input, if any
+    #[serde(default)]
+    pub consumer_usage: Option<u16>,
+    /// Raw report bytes of the input that produced this event (listener only)
+    #[serde(skip)]
+    pub raw_report: Vec<u8>,
+    /// OS auto-repeat of a held key rather than a new press (see `key_repeat.rs`)
+    #[serde(default)]
+    pub is_repeat: bool,
+    /// Virtual-key code of the key pressed, for keyboard-like devices
+    #[serde(default)]
+    pub button_code: Option<u16>,
+}
+
+/// Every field hidapi exposes for a single device interface.
+/// Used for support exports, so nothing is deduplicated or collapsed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HidInterfaceInfo {
+    pub path: String,
+    pub vendor_id: String,
+    pub product_id: String,
+    pub serial_number: Option<String>,
+    pub release_number: String,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub usage_page: u16,
+    pub usage: u16,
+    pub interface_number: i32,
+    pub bus_type: String,
+}
+
+/// Input activity seen on one interface during `detect_active_interface`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceActivity {
+    pub interface_number: i32,
+    pub usage_page: u16,
+    pub usage: u16,
+    pub path: String,
+    pub report_count: u32,
+    pub error: Option<String>, // Interface couldn't be opened or read
+    pub recommended: bool,     // Produced the most reports in the window
+}
+
+/// One input report read by `capture_hid_report`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedReport {
+    pub interface_number: i32,
+    pub usage_page: u16,
+    pub elapsed_ms: u64, // Since the capture started
+    pub length: usize,
+    pub hex: String, // Space-separated bytes, e.g. "02 CD 00"
+}
+
+/// Which kind of report `send_hid_report` writes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HidReportKind {
+    #[default]
+    Output,
+    Feature,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -33,6 +97,36 @@ pub struct DeviceBinding {
     pub trigger_type: TriggerType,
     pub action: ActionConfig,
     pub enabled: bool,
+    #[serde(default)]
+    pub notify_on_execute: bool,
+    /// Ask for a yes/no before running (see `confirm.rs`)
+    #[serde(default)]
+    pub confirm_dialog: bool,
+    /// Require an arming press before the firing press
+    #[serde(default)]
+    pub arm_to_fire: Option<ArmToFire>,
+    /// Only fire while this layer is active (see `layers.rs`)
+    #[serde(default)]
+    pub required_layer: Option<String>,
+    /// Gate or branch the action on AC vs battery power (see `power.rs`)
+    #[serde(default)]
+    pub power_condition: Option<PowerCondition>,
+    /// Only match devices reporting this manufacturer string. Disambiguates
+    /// generic chips that share a VID:PID across vendors.
+    #[serde(default)]
+    pub manufacturer: Option<String>,
+    /// Only match this consumer-control usage, e.g. "play-pause" (see
+    /// `consumer.rs`). Unset matches any input from the device.
+    #[serde(default)]
+    pub consumer_usage: Option<String>,
+    /// Only match this key (virtual-key code). Unset matches any key, so a
+    /// keypad's buttons can each have their own binding.
+    #[serde(default)]
+    pub button_code: Option<u16>,
+    /// Minimum time between executions; unset uses the listener's default
+    /// debounce, `Some(0)` turns it off
+    #[serde(default)]
+    pub cooldown_ms: Option<u64>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -54,6 +148,15 @@ impl DeviceBinding {
             trigger_type,
             action,
             enabled: true,
+            notify_on_execute: false,
+            confirm_dialog: false,
+            arm_to_fire: None,
+            required_layer: None,
+            power_condition: None,
+            manufacturer: None,
+            consumer_usage: None,
+            button_code: None,
+            cooldown_ms: None,
             created_at: now.clone(),
             updated_at: now,
         }
@@ -65,10 +168,43 @@ impl DeviceBinding {
 pub enum TriggerType {
     SinglePress,
     DoublePress,
+    TriplePress,
     LongPress,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    Unknown,
+}
+
+/// Two-step safety: the first press arms the binding, and only a second
+/// press within `window_secs` runs the action
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArmToFire {
+    #[serde(default = "default_arm_window_secs")]
+    pub window_secs: u32,
+    #[serde(default)]
+    pub beep: bool, // Audible cue when armed
+}
+
+fn default_arm_window_secs() -> u32 {
+    5
+}
+
+/// Run the binding's action only on `run_on` power; otherwise run
+/// `otherwise` if set, or do nothing
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerCondition {
+    pub run_on: PowerSource,
+    pub otherwise: Option<ActionConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ActionConfig {
     #[serde(rename = "type")]
@@ -77,15 +213,94 @@ pub struct ActionConfig {
     pub arguments: String,
     pub working_directory: Option<String>,
     pub run_as_admin: Option<bool>,
+    /// Hotkey only: title or process name of a window to post keys to instead of the focused one
+    pub target_window: Option<String>,
+    /// Hotkey only: keep the keys down this long before releasing them, for
+    /// games and apps that ignore instant taps (capped at 5 seconds)
+    #[serde(default)]
+    pub hold_ms: Option<u64>,
+    /// LaunchApp only: how the new window first appears (default normal)
+    pub window_state: Option<WindowState>,
+    /// Sequence only: the steps to run in order
+    #[serde(default)]
+    pub sequence: Option<ActionSequence>,
+    /// Launched processes: extra environment variables as `(name, value)`
+    #[serde(default)]
+    pub env: Option<Vec<(String, String)>>,
+    /// LaunchApp, RunScript, SystemCommand, ScriptBlock: kill the process if
+    /// it's still running after this many milliseconds. ShellCapture uses it
+    /// instead of its 30s default. Elevated and window-state launches can't be watched.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+impl ActionConfig {
+    /// This action followed by its sequence steps, if any
+    pub fn and_steps(&self) -> impl Iterator<Item = &ActionConfig> {
+        std::iter::once(self).chain(self.sequence.iter().flat_map(|s| s.steps.iter()))
+    }
 }
 
+/// Steps of a `Sequence` action. Steps can't themselves be sequences.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionSequence {
+    pub steps: Vec<ActionConfig>,
+    /// Pause between steps, e.g. so a launched app is up before a hotkey is sent
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// Run the remaining steps after one fails (otherwise they're skipped)
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindowState {
+    #[default]
+    Normal,
+    Minimized,
+    Maximized,
+    Hidden,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum ActionType {
+    #[default]
     LaunchApp,
     RunScript,
     SystemCommand,
     Hotkey,
+    /// `executable_path` holds the target percentage, e.g. "50"
+    SetVolume,
+    /// `executable_path` holds a registered plugin name (see `plugins.rs`)
+    Plugin,
+    /// `executable_path` holds a template; `{clipboard}` is replaced with the
+    /// clipboard text and the result is typed into the focused window
+    ClipboardTemplate,
+    /// `executable_path` holds text typed into the focused window as-is
+    TextMacro,
+    /// `executable_path` holds a multi-line script run as one process;
+    /// `arguments` picks the shell ("cmd" default, or "powershell")
+    ScriptBlock,
+    /// `executable_path` holds "x,y" in virtual-desktop pixels, or 0-65535
+    /// values when `arguments` is "normalized" (see `mouse.rs`)
+    MouseMove,
+    /// `executable_path` holds `click:<button>`, `scroll:<direction>[:notches]`
+    /// or `move:dx,dy` (see `mouse.rs`)
+    MouseAction,
+    // Layer actions use `executable_path` as the layer name
+    SetLayer,
+    ClearLayer,
+    ToggleLayer,
+    /// Runs `sequence.steps` in order
+    Sequence,
+    /// `executable_path` holds a wait in milliseconds, capped at 60s (see `delay.rs`)
+    Delay,
+    /// Runs like `SystemCommand` but waits for the command and logs its exit
+    /// code and output (see `shell.rs`)
+    ShellCapture,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +312,71 @@ pub struct AppSettings {
     pub theme: Theme,
     pub log_level: LogLevel,
     pub max_log_entries: u32,
+    /// Re-enable a device's bindings when it reconnects (otherwise leave `enabled` as-is)
+    #[serde(default)]
+    pub reenable_on_reconnect: bool,
+    /// Log messages longer than this (in characters) are truncated by `compact_logs`
+    #[serde(default = "default_max_log_message_length")]
+    pub max_log_message_length: u32,
+    /// Keep active layers across restarts
+    #[serde(default)]
+    pub persist_layers: bool,
+    /// What to do when another instance is already running at startup
+    #[serde(default)]
+    pub second_instance_behavior: SecondInstanceBehavior,
+    /// Ignore device presses for this long after launch
+    #[serde(default)]
+    pub listener_start_delay_ms: u64,
+    /// Count executed action/trigger types in a local stats file (never sent anywhere)
+    #[serde(default)]
+    pub usage_stats_enabled: bool,
+    /// Devices whose raw input reports are written to the log on each press
+    #[serde(default)]
+    pub verbose_devices: Vec<String>,
+    /// Key devices as `VID:PID:SERIAL` so identical devices get separate bindings
+    #[serde(default)]
+    pub use_serial_in_device_id: bool,
+    /// Master switch for all bindings; off pauses every action without
+    /// touching the bindings themselves
+    #[serde(default = "default_remapping_enabled")]
+    pub remapping_enabled: bool,
+    /// activity.log is rotated to activity.log.1 once it grows past this
+    #[serde(default = "default_max_log_file_bytes")]
+    pub max_log_file_bytes: u64,
+    /// Max time between presses that still count toward a double/triple press.
+    /// A key with multi-press bindings runs its binding once this has passed
+    /// after the last press (or right away at the highest bound count).
+    #[serde(default = "default_multi_press_window_ms")]
+    pub multi_press_window_ms: u64,
+    /// Serve the local HTTP control API on 127.0.0.1:`http_api_port`
+    #[serde(default)]
+    pub enable_http_api: bool,
+    #[serde(default = "default_http_api_port")]
+    pub http_api_port: u16,
+    /// Bearer token every HTTP API request must send; generated when the API
+    /// is enabled without one
+    #[serde(default)]
+    pub http_api_token: String,
+}
+
+fn default_max_log_message_length() -> u32 {
+    2000
+}
+
+fn default_remapping_enabled() -> bool {
+    true
+}
+
+fn default_max_log_file_bytes() -> u64 {
+    1024 * 1024
+}
+
+fn default_multi_press_window_ms() -> u64 {
+    400
+}
+
+fn default_http_api_port() -> u16 {
+    17380
 }
 
 impl Default for AppSettings {
@@ -108,10 +388,81 @@ impl Default for AppSettings {
             theme: Theme::System,
             log_level: LogLevel::Info,
             max_log_entries: 100,
+            reenable_on_reconnect: false,
+            max_log_message_length: default_max_log_message_length(),
+            persist_layers: false,
+            second_instance_behavior: SecondInstanceBehavior::default(),
+            listener_start_delay_ms: 0,
+            usage_stats_enabled: false,
+            verbose_devices: Vec::new(),
+            use_serial_in_device_id: false,
+            remapping_enabled: true,
+            max_log_file_bytes: default_max_log_file_bytes(),
+            multi_press_window_ms: default_multi_press_window_ms(),
+            enable_http_api: false,
+            http_api_port: default_http_api_port(),
+            http_api_token: String::new(),
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecondInstanceBehavior {
+    /// Bring the running instance's window forward and exit
+    #[default]
+    FocusExisting,
+    /// Keep running with a warning, but without the background listener
+    Warn,
+}
+
+/// Kiosk lock state as seen by the frontend (see `kiosk.rs`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLockStatus {
+    pub locked: bool,         // A lock is configured
+    pub unlocked: bool,       // Unlocked for this session
+    pub has_passphrase: bool,
+}
+
+/// Where config and logs live on disk (see `get_config_dir`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigPaths {
+    pub config_dir: String,
+    pub config_file: String,  // config.json
+    pub logs_file: String,    // logs.json, the log shown in the app
+    pub activity_log: String, // activity.log, plain-text copy for tailing
+}
+
+/// One device's bindings joined with whether it's connected (see
+/// `get_bindings_grouped`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceBindingGroup {
+    pub device_id: String,       // As saved in the bindings
+    pub device: Option<HidDevice>, // The connected device, if present
+    pub status: DeviceStatus,    // Configured when present, otherwise Disconnected
+    pub bindings: Vec<DeviceBinding>,
+}
+
+/// A binding profile as listed for the frontend (see `config::Profile`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSummary {
+    pub id: String,
+    pub name: String,
+    pub binding_count: usize,
+    pub active: bool, // Its bindings are the ones the listener matches
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceStatus {
+    pub is_primary: bool,
+    pub other_instance_running: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Theme {
@@ -151,6 +502,135 @@ impl LogEntry {
     }
 }
 
+/// A single key resolved from a hotkey string
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedKey {
+    pub name: String,
+    pub vk_code: u16,
+}
+
+/// Result of parsing (but not sending) a hotkey string
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyPreview {
+    pub keys: Vec<ResolvedKey>,
+    pub warnings: Vec<String>,
+}
+
+/// A hotkey action that collides with a shortcut Windows handles itself
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyConflict {
+    pub binding_id: String,
+    pub device_id: String,
+    pub hotkey: String,
+    pub warning: String,
+}
+
+/// Advisory problem found with a saved binding (see `validation.rs`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingWarning {
+    pub binding_id: String,
+    pub device_id: String,
+    pub message: String,
+}
+
+/// How one of a binding's conditions resolves right now (see `explain.rs`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConditionState {
+    Pass,
+    Fail,
+    /// Decided by the press itself (which key, how many presses)
+    DependsOnInput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionCheck {
+    pub condition: String,
+    pub state: ConditionState,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingExplanation {
+    pub binding_id: String,
+    pub conditions: Vec<ConditionCheck>,
+    pub can_fire: bool, // No condition fails outright
+}
+
+/// Every binding that could match a device, in evaluation order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceExplanation {
+    pub device_id: String,
+    pub manufacturer: Option<String>,
+    pub bindings: Vec<BindingExplanation>,
+}
+
+/// Outcome of one action run by `test_binding`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingTestStep {
+    pub step: usize, // 1-based; sequences have one per step
+    pub description: String,
+    pub succeeded: bool,
+    pub message: Option<String>, // Why it failed or was skipped
+}
+
+/// Result of running a saved binding's action through the listener's path
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingTestResult {
+    pub binding_id: String,
+    pub succeeded: bool,
+    pub steps: Vec<BindingTestStep>,
+}
+
+/// Ordering for `get_logs` results
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+/// Local counts of executed actions, keyed by serialized action/trigger type.
+/// Only collected when `usage_stats_enabled` is on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    pub since: Option<String>, // First count after the last reset
+    pub action_types: BTreeMap<String, u64>,
+    pub trigger_types: BTreeMap<String, u64>,
+}
+
+impl UsageStats {
+    pub fn record(&mut self, action_type: &str, trigger_type: &str) {
+        if self.since.is_none() {
+            self.since = Some(chrono::Utc::now().to_rfc3339());
+        }
+        *self.action_types.entry(action_type.to_string()).or_insert(0) += 1;
+        *self.trigger_types.entry(trigger_type.to_string()).or_insert(0) += 1;
+    }
+}
+
+/// Outcome of rewriting logs.json via `compact_logs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactLogsResult {
+    pub entries_removed: usize,
+    pub messages_truncated: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub bytes_reclaimed: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum LogEntryLevel {
@@ -177,11 +657,47 @@ impl Default for MonitoringState {
     }
 }
 
+/// Stable identifier for a failure, so the frontend can react to an error
+/// without matching on its message. One per `HidError`/`ConfigError` variant.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCode {
+    // HID
+    HidInitFailed,
+    DeviceNotFound,
+    DeviceOpenFailed,
+    DeviceReadFailed,
+    DeviceWriteFailed,
+    PermissionDenied,
+    // Config
+    NoConfigDir,
+    Io,
+    InvalidJson,
+    InvalidBinding,
+    BindingNotFound,
+    BindingConflict,
+    ConfigLocked,
+    IncorrectPassphrase,
+    InvalidImport,
+    MigrationFailed,
+    InvalidSettings,
+    ProfileNotFound,
+    InvalidProfile,
+}
+
+/// An error type that maps to an `ErrorCode`
+pub trait CodedError: std::fmt::Display {
+    fn code(&self) -> ErrorCode;
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IpcResult<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Set when `error` came from a typed error; `error` stays the message to show
+    #[serde(default)]
+    pub code: Option<ErrorCode>,
 }
 
 impl<T> IpcResult<T> {
@@ -190,6 +706,7 @@ impl<T> IpcResult<T> {
             success: true,
             data: Some(data),
             error: None,
+            code: None,
         }
     }
 
@@ -198,8 +715,20 @@ impl<T> IpcResult<T> {
             success: false,
             data: None,
             error: Some(error),
+            code: None,
         }
     }
+
+    /// A failure carrying `error`'s message and code
+    pub fn from_error(error: &impl CodedError) -> Self {
+        Self::err(error.to_string()).with_code(error.code())
+    }
+
+    /// Attach a code, e.g. to an error whose message adds context
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
 }
 
 impl IpcResult<()> {
@@ -208,6 +737,7 @@ impl IpcResult<()> {
             success: true,
             data: Some(()),
             error: None,
+            code: None,
         }
     }
 }
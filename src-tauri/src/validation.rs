@@ -0,0 +1,273 @@
+// ============================================
+// Action Validation
+// Advisory checks that flag actions likely to fail at runtime.
+// Nothing here blocks saving a binding.
+// ============================================
+
+use crate::types::{ActionConfig, ActionType, BindingWarning, DeviceBinding};
+
+#[cfg(target_os = "windows")]
+use windows::core::HSTRING;
+#[cfg(target_os = "windows")]
+use windows::Win32::Storage::FileSystem::GetDriveTypeW;
+
+/// Kind of drive a path lives on, as reported by `GetDriveTypeW`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DriveKind {
+    Fixed,
+    Removable,
+    Network,
+    CdRom,
+    RamDisk,
+    Unknown,
+}
+
+impl DriveKind {
+    /// Map a `GetDriveTypeW` return value
+    pub fn from_drive_type(code: u32) -> Self {
+        match code {
+            2 => DriveKind::Removable, // DRIVE_REMOVABLE
+            3 => DriveKind::Fixed,     // DRIVE_FIXED
+            4 => DriveKind::Network,   // DRIVE_REMOTE
+            5 => DriveKind::CdRom,     // DRIVE_CDROM
+            6 => DriveKind::RamDisk,   // DRIVE_RAMDISK
+            _ => DriveKind::Unknown,   // DRIVE_UNKNOWN / DRIVE_NO_ROOT_DIR
+        }
+    }
+}
+
+/// Root of an absolute Windows path: "E:\" for drive paths, "\\server\share\"
+/// for UNC paths. Relative paths (resolved via PATH) have no root.
+pub fn path_root(path: &str) -> Option<String> {
+    let path = path.trim().trim_matches('"');
+
+    if let Some(rest) = path.strip_prefix("\\\\") {
+        let mut parts = rest.split('\\').filter(|p| !p.is_empty());
+        let server = parts.next()?;
+        let share = parts.next()?;
+        return Some(format!("\\\\{}\\{}\\", server, share));
+    }
+
+    let mut chars = path.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), Some(':')) if letter.is_ascii_alphabetic() => {
+            Some(format!("{}:\\", letter.to_ascii_uppercase()))
+        }
+        _ => None,
+    }
+}
+
+/// Classify the drive a path is on. `drive_kind` looks up a root, so tests
+/// can inject drive types.
+pub fn classify_path(path: &str, drive_kind: impl Fn(&str) -> DriveKind) -> Option<DriveKind> {
+    let root = path_root(path)?;
+    if root.starts_with("\\\\") {
+        return Some(DriveKind::Network);
+    }
+    Some(drive_kind(&root))
+}
+
+/// Drive type of a root on this machine
+pub fn system_drive_kind(root: &str) -> DriveKind {
+    #[cfg(target_os = "windows")]
+    {
+        DriveKind::from_drive_type(unsafe { GetDriveTypeW(&HSTRING::from(root)) })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = root;
+        DriveKind::Unknown
+    }
+}
+
+/// Warnings for a single action
+pub fn action_warnings(action: &ActionConfig, drive_kind: impl Fn(&str) -> DriveKind) -> Vec<String> {
+    let mut paths: Vec<(&str, &str)> = Vec::new();
+    if matches!(action.r#type, ActionType::LaunchApp | ActionType::RunScript) {
+        paths.push(("Executable", action.executable_path.as_str()));
+    }
+    if let Some(dir) = &action.working_directory {
+        paths.push(("Working directory", dir.as_str()));
+    }
+
+    let mut warnings = sequence_warnings(action);
+    if action.r#type == ActionType::Delay {
+        match crate::delay::parse_delay_ms(&action.executable_path) {
+            Ok(ms) if ms > crate::delay::MAX_DELAY_MS => warnings.push(format!(
+                "Delay of {}ms will be capped at {}ms",
+                ms,
+                crate::delay::MAX_DELAY_MS
+            )),
+            Ok(_) => {}
+            Err(e) => warnings.push(e),
+        }
+    }
+    warnings.extend(paths.into_iter().filter_map(|(label, path)| {
+            let kind = match classify_path(path, &drive_kind)? {
+                DriveKind::Removable => "a removable drive",
+                DriveKind::Network => "a network drive",
+                DriveKind::CdRom => "an optical drive",
+                _ => return None,
+            };
+            Some(format!(
+                "{} {} is on {} and will fail when it isn't mounted",
+                label, path, kind
+            ))
+    }));
+    warnings
+}
+
+/// Steps of a sequence that can't do anything useful
+fn sequence_warnings(action: &ActionConfig) -> Vec<String> {
+    if action.r#type != ActionType::Sequence {
+        return Vec::new();
+    }
+    let steps = action.sequence.as_ref().map(|s| s.steps.as_slice()).unwrap_or_default();
+    if steps.is_empty() {
+        return vec!["Sequence has no steps".to_string()];
+    }
+
+    steps
+        .iter()
+        .enumerate()
+        .filter_map(|(index, step)| match step.r#type {
+            ActionType::Sequence => Some(format!("Step {} is a sequence; nested sequences don't run", index + 1)),
+            ActionType::SetLayer | ActionType::ClearLayer | ActionType::ToggleLayer => Some(format!(
+                "Step {} is a layer action, which has no effect inside a sequence",
+                index + 1
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Warnings for every saved binding, including power-alternate actions
+pub fn binding_warnings(bindings: &[DeviceBinding], drive_kind: impl Fn(&str) -> DriveKind) -> Vec<BindingWarning> {
+    let mut warnings = Vec::new();
+    for binding in bindings {
+        let alternate = binding.power_condition.as_ref().and_then(|c| c.otherwise.as_ref());
+        let actions = std::iter::once(&binding.action).chain(alternate).flat_map(ActionConfig::and_steps);
+        for action in actions {
+            for message in action_warnings(action, &drive_kind) {
+                warnings.push(BindingWarning {
+                    binding_id: binding.id.clone(),
+                    device_id: binding.device_id.clone(),
+                    message,
+                });
+            }
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_drives(root: &str) -> DriveKind {
+        match root {
+            "C:\\" => DriveKind::Fixed,
+            "E:\\" => DriveKind::Removable,
+            "Z:\\" => DriveKind::Network,
+            "D:\\" => DriveKind::CdRom,
+            _ => DriveKind::Unknown,
+        }
+    }
+
+    fn launch(path: &str) -> ActionConfig {
+        ActionConfig {
+            r#type: ActionType::LaunchApp,
+            executable_path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_path_root() {
+        assert_eq!(path_root("e:\\tools\\app.exe").as_deref(), Some("E:\\"));
+        assert_eq!(path_root("\"C:\\Program Files\\x.exe\"").as_deref(), Some("C:\\"));
+        assert_eq!(path_root("\\\\nas\\share\\run.bat").as_deref(), Some("\\\\nas\\share\\"));
+        assert_eq!(path_root("notepad.exe"), None);
+        assert_eq!(path_root("\\\\nas"), None);
+    }
+
+    #[test]
+    fn test_classify_path_with_injected_roots() {
+        assert_eq!(classify_path("C:\\app.exe", fake_drives), Some(DriveKind::Fixed));
+        assert_eq!(classify_path("E:\\app.exe", fake_drives), Some(DriveKind::Removable));
+        assert_eq!(classify_path("Z:\\app.exe", fake_drives), Some(DriveKind::Network));
+        assert_eq!(classify_path("\\\\nas\\share\\app.exe", fake_drives), Some(DriveKind::Network));
+        assert_eq!(classify_path("app.exe", fake_drives), None);
+    }
+
+    #[test]
+    fn test_drive_type_codes() {
+        assert_eq!(DriveKind::from_drive_type(2), DriveKind::Removable);
+        assert_eq!(DriveKind::from_drive_type(3), DriveKind::Fixed);
+        assert_eq!(DriveKind::from_drive_type(4), DriveKind::Network);
+        assert_eq!(DriveKind::from_drive_type(1), DriveKind::Unknown);
+    }
+
+    #[test]
+    fn test_action_warnings_flag_fragile_paths() {
+        assert!(action_warnings(&launch("C:\\Tools\\app.exe"), fake_drives).is_empty());
+        assert!(action_warnings(&launch("notepad.exe"), fake_drives).is_empty());
+
+        let warnings = action_warnings(&launch("E:\\Tools\\app.exe"), fake_drives);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("removable drive"));
+
+        let mut action = launch("C:\\Tools\\app.exe");
+        action.working_directory = Some("Z:\\work".to_string());
+        let warnings = action_warnings(&action, fake_drives);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Working directory"));
+
+        // Hotkey payloads aren't paths
+        let mut hotkey = launch("E:");
+        hotkey.r#type = ActionType::Hotkey;
+        assert!(action_warnings(&hotkey, fake_drives).is_empty());
+    }
+
+    #[test]
+    fn test_sequence_warnings() {
+        let mut sequence = launch("");
+        sequence.r#type = ActionType::Sequence;
+        assert_eq!(action_warnings(&sequence, fake_drives), vec!["Sequence has no steps".to_string()]);
+
+        let mut layer = launch("gaming");
+        layer.r#type = ActionType::SetLayer;
+        let mut nested = launch("");
+        nested.r#type = ActionType::Sequence;
+        sequence.sequence = Some(crate::types::ActionSequence {
+            steps: vec![launch("C:\\Tools\\app.exe"), layer, nested],
+            delay_ms: 500,
+            continue_on_error: false,
+        });
+        let warnings = action_warnings(&sequence, fake_drives);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("Step 2 is a layer action"));
+        assert!(warnings[1].starts_with("Step 3 is a sequence"));
+
+        // Step paths are checked through binding_warnings via and_steps
+        let steps: Vec<_> = sequence.and_steps().map(|a| a.r#type.clone()).collect();
+        assert_eq!(steps.len(), 4);
+    }
+
+    #[test]
+    fn test_delay_warnings() {
+        let mut delay = launch("1500");
+        delay.r#type = ActionType::Delay;
+        assert!(action_warnings(&delay, fake_drives).is_empty());
+
+        delay.executable_path = "120000".to_string();
+        assert_eq!(
+            action_warnings(&delay, fake_drives),
+            vec!["Delay of 120000ms will be capped at 60000ms".to_string()]
+        );
+
+        delay.executable_path = "a while".to_string();
+        assert_eq!(action_warnings(&delay, fake_drives).len(), 1);
+    }
+}
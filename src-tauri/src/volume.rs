@@ -0,0 +1,136 @@
+// ============================================
+// System Volume
+// Reads/sets the default playback device's master volume via
+// IAudioEndpointVolume (Windows Core Audio)
+// ============================================
+
+#[cfg(target_os = "windows")]
+use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+#[cfg(target_os = "windows")]
+use windows::Win32::Media::Audio::{eConsole, eRender, IMMDeviceEnumerator, MMDeviceEnumerator};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED};
+
+/// Clamp any integer to a 0-100 volume percentage
+pub fn clamp_percent(value: i64) -> u8 {
+    value.clamp(0, 100) as u8
+}
+
+/// Convert a 0-100 percentage to the 0.0-1.0 scalar IAudioEndpointVolume expects
+pub fn percent_to_scalar(percent: u8) -> f32 {
+    percent.min(100) as f32 / 100.0
+}
+
+/// Convert a 0.0-1.0 scalar back to a rounded percentage
+pub fn scalar_to_percent(scalar: f32) -> u8 {
+    clamp_percent((scalar * 100.0).round() as i64)
+}
+
+/// Parse the percentage stored in a SetVolume action (e.g. "50" or "50%").
+/// Out-of-range values are clamped rather than rejected.
+pub fn parse_volume_percent(value: &str) -> Result<u8, String> {
+    let trimmed = value.trim().trim_end_matches('%').trim();
+    trimmed
+        .parse::<i64>()
+        .map(clamp_percent)
+        .map_err(|_| format!("Invalid volume percentage: {}", value))
+}
+
+/// Current master volume of the default playback device, as a percentage
+pub fn get_volume_percent() -> Result<u8, String> {
+    #[cfg(target_os = "windows")]
+    {
+        unsafe {
+            let endpoint = default_endpoint_volume()?;
+            let scalar = endpoint
+                .GetMasterVolumeLevelScalar()
+                .map_err(|e| format!("Failed to read volume: {}", e))?;
+            Ok(scalar_to_percent(scalar))
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("Volume control is only supported on Windows".to_string())
+    }
+}
+
+/// Set the master volume to an absolute percentage.
+/// Returns the (before, after) levels so callers can log the change.
+pub fn set_volume_percent(percent: u8) -> Result<(u8, u8), String> {
+    #[cfg(target_os = "windows")]
+    {
+        unsafe {
+            let endpoint = default_endpoint_volume()?;
+            let before = endpoint
+                .GetMasterVolumeLevelScalar()
+                .map_err(|e| format!("Failed to read volume: {}", e))?;
+            endpoint
+                .SetMasterVolumeLevelScalar(percent_to_scalar(percent), std::ptr::null())
+                .map_err(|e| format!("Failed to set volume: {}", e))?;
+            let after = endpoint
+                .GetMasterVolumeLevelScalar()
+                .map_err(|e| format!("Failed to read volume: {}", e))?;
+            Ok((scalar_to_percent(before), scalar_to_percent(after)))
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = percent;
+        Err("Volume control is only supported on Windows".to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn default_endpoint_volume() -> Result<IAudioEndpointVolume, String> {
+    // S_FALSE / RPC_E_CHANGED_MODE just mean COM is already initialized on this thread
+    let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+    let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+        .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
+    let device = enumerator
+        .GetDefaultAudioEndpoint(eRender, eConsole)
+        .map_err(|e| format!("No default audio device: {}", e))?;
+    device
+        .Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
+        .map_err(|e| format!("Failed to open endpoint volume: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_percent() {
+        assert_eq!(clamp_percent(-20), 0);
+        assert_eq!(clamp_percent(0), 0);
+        assert_eq!(clamp_percent(55), 55);
+        assert_eq!(clamp_percent(100), 100);
+        assert_eq!(clamp_percent(250), 100);
+    }
+
+    #[test]
+    fn test_percent_scalar_conversion() {
+        assert_eq!(percent_to_scalar(0), 0.0);
+        assert_eq!(percent_to_scalar(50), 0.5);
+        assert_eq!(percent_to_scalar(100), 1.0);
+        assert_eq!(percent_to_scalar(200), 1.0);
+
+        assert_eq!(scalar_to_percent(0.0), 0);
+        assert_eq!(scalar_to_percent(0.333), 33);
+        assert_eq!(scalar_to_percent(0.999), 100);
+        for p in 0..=100u8 {
+            assert_eq!(scalar_to_percent(percent_to_scalar(p)), p);
+        }
+    }
+
+    #[test]
+    fn test_parse_volume_percent() {
+        assert_eq!(parse_volume_percent("40"), Ok(40));
+        assert_eq!(parse_volume_percent(" 75% "), Ok(75));
+        assert_eq!(parse_volume_percent("150"), Ok(100));
+        assert_eq!(parse_volume_percent("-5"), Ok(0));
+        assert!(parse_volume_percent("loud").is_err());
+    }
+}